@@ -0,0 +1,26 @@
+//! Writes the list of optional features the crate is being built with into
+//! `$OUT_DIR/features.rs`, for `lolwut --version`.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Features that are always there in the binary, so aren't worth listing.
+const UNLISTED: &[&str] = &["default", "std"];
+
+fn main() {
+    // Cargo sets `CARGO_FEATURE_<NAME>` for each feature that's on, with the
+    // name in upper case and `-` turned into `_`. None of ours have a `_` in
+    // them, so turning it back is safe.
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(var, _)| {
+            var.strip_prefix("CARGO_FEATURE_").map(|name| name.to_lowercase().replace('_', "-"))
+        })
+        .filter(|name| !UNLISTED.contains(&name.as_str()))
+        .collect();
+    features.sort();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("features.rs");
+    fs::write(out, format!("&{:?}", features)).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,29 @@
+//! A Redis module with a `LOLWUT.RS` command, which draws Schotter like
+//! Redis's own `LOLWUT`. Build it with
+//!
+//!     cargo build --release --example lolwut_redis --features redis-module
+//!
+//! and load `target/release/examples/liblolwut_redis.so` with
+//! `redis-server --loadmodule` or `MODULE LOAD`.
+
+use lolwut::redis::lolwut_command;
+use redis_module::{redis_module, Context, RedisError, RedisResult, RedisString, RedisValue};
+
+/// `LOLWUT.RS [VERSION v] [cols [squares-per-row [squares-per-col]]]`
+fn lolwut_rs(_: &Context, args: Vec<RedisString>) -> RedisResult {
+    let args: Vec<&[u8]> = args.iter().skip(1).map(|arg| arg.as_slice()).collect();
+    match lolwut_command(&args, rand::random()) {
+        Ok(text) => Ok(RedisValue::BulkString(text)),
+        Err(err) => Err(RedisError::String(err)),
+    }
+}
+
+redis_module! {
+    name: "lolwut",
+    version: 1,
+    allocator: (redis_module::alloc::RedisAlloc, redis_module::alloc::RedisAlloc),
+    data_types: [],
+    commands: [
+        ["lolwut.rs", lolwut_rs, "readonly fast", 0, 0, 0, ""],
+    ],
+}
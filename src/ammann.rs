@@ -0,0 +1,248 @@
+//! Ammann–Beenker tilings, grown by substitution.
+//!
+//! The tiles are 45° rhombi and squares, and the squares are handled as pairs
+//! of right isosceles triangles, split along a diagonal. Each rhombus can be
+//! cut into three rhombi and four triangles, and each triangle into two rhombi
+//! and three triangles, all smaller by the silver ratio `1 + √2`. Repeating
+//! that from a star of eight rhombi grows a tiling with eight-fold symmetry.
+//!
+//! Which of a triangle's legs is which matters: splitting a leg leaves a
+//! tile edge at one end and a diagonal at the other, and the same has to be
+//! true from both sides of the leg, so triangles come in two mirror-image
+//! kinds.
+
+use alloc::vec;
+use core::f32::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Canvas;
+
+/// The silver ratio, `1 + √2`.
+const SILVER: f32 = 1.0 + SQRT_2;
+
+/// The shortest sides, in pixels, that tiles are subdivided into.
+const MIN_TILE_SIDE: f32 = 2.0;
+
+type Point = (f32, f32);
+
+/// One of the tiles, all of which have sides of the same length.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Tile {
+    /// A rhombus with 45° angles at `o` and `q`, and 135° angles at `p` and
+    /// `r`.
+    Rhombus { o: Point, p: Point, q: Point, r: Point },
+    /// Half of a square, with the right angle at `c`. Splitting the leg from
+    /// `c` to `a` leaves a tile edge at `c`, and the one from `c` to `b` a
+    /// diagonal at `c`.
+    Triangle { c: Point, a: Point, b: Point },
+}
+
+impl Canvas {
+    /// Draw an Ammann–Beenker tiling of squares and rhombi around
+    /// `(center_x, center_y)`.
+    ///
+    /// The tiling starts as a star of eight rhombi meeting in the middle, with
+    /// sides `size` pixels long, and each generation replaces every tile with
+    /// smaller ones, the silver ratio (about 2.414) times smaller. This stops
+    /// after `generations`, or once the tiles' sides would be shorter than 2
+    /// pixels, whichever comes first. Only tiles that reach the canvas are
+    /// replaced, so a tiling much bigger than the canvas takes only as long as
+    /// the part that shows. Only the squares are outlined, not the
+    /// triangles they're split into, so the halves of squares along the edge
+    /// of the star are left open.
+    ///
+    /// Nothing is drawn unless `size` is positive.
+    pub fn draw_ammann_beenker(&mut self,
+                               center_x: i32,
+                               center_y: i32,
+                               size: f32,
+                               generations: u32,
+                               color: u8)
+    {
+        if size.is_nan() || size <= 0.0 {
+            return;
+        }
+
+        let mut depth = 0;
+        let mut side = size;
+        while depth < generations && side / SILVER >= MIN_TILE_SIDE {
+            side /= SILVER;
+            depth += 1;
+        }
+
+        let o = (center_x as f32, center_y as f32);
+        let spoke = |k: i32| {
+            let angle = k as f32 * PI / 4.0;
+            (o.0 + size * angle.cos(), o.1 + size * angle.sin())
+        };
+        for k in 0..8 {
+            let (p, r) = (spoke(k), spoke(k + 1));
+            let q = (p.0 + r.0 - o.0, p.1 + r.1 - o.1);
+            self.draw_ammann_tile(Tile::Rhombus { o, p, q, r }, depth, color);
+        }
+    }
+
+    /// Draw `tile`, substituted `depth` more times. The smaller tiles stay
+    /// inside the big one, so a tile that's off of the canvas is skipped
+    /// entirely.
+    fn draw_ammann_tile(&mut self, tile: Tile, depth: u32, color: u8) {
+        let misses = match tile {
+            Tile::Rhombus { o, p, q, r } => self.misses_canvas(&[o, p, q, r]),
+            Tile::Triangle { c, a, b }   => self.misses_canvas(&[c, a, b]),
+        };
+        if misses {
+            return;
+        }
+
+        let pixel = |(x, y): Point| (x.round() as i32, y.round() as i32);
+        if depth == 0 {
+            match tile {
+                Tile::Rhombus { o, p, q, r } => {
+                    let corners = [pixel(o), pixel(p), pixel(q), pixel(r)];
+                    self.draw_quad(&corners, color);
+                }
+                Tile::Triangle { c, a, b } => {
+                    // The hypotenuse is the middle of a square.
+                    let (c, a, b) = (pixel(c), pixel(a), pixel(b));
+                    self.draw_line(a.0, a.1, c.0, c.1, color);
+                    self.draw_line(c.0, c.1, b.0, b.1, color);
+                }
+            }
+            return;
+        }
+
+        let tiles = match tile {
+            Tile::Rhombus { o, p, q, r } => {
+                // The sides of the smaller tiles, along each pair of sides.
+                let u = scale(sub(p, o), 1.0 / SILVER);
+                let v = scale(sub(r, o), 1.0 / SILVER);
+                let (near_o, near_q) = (add(add(o, u), v), sub(sub(q, u), v));
+                vec![
+                    Tile::Rhombus { o, p: add(o, u), q: near_o, r: add(o, v) },
+                    Tile::Rhombus { o: q, p: sub(q, v), q: near_q, r: sub(q, u) },
+                    Tile::Rhombus { o: p, p: near_q, q: r, r: near_o },
+                    Tile::Triangle { c: near_o, a: add(o, u), b: p },
+                    Tile::Triangle { c: near_o, a: add(o, v), b: r },
+                    Tile::Triangle { c: near_q, a: sub(q, v), b: p },
+                    Tile::Triangle { c: near_q, a: sub(q, u), b: r },
+                ]
+            }
+            Tile::Triangle { c, a, b } => {
+                let u = scale(sub(a, c), 1.0 / SILVER);
+                let v = scale(sub(b, c), 1.0 / SILVER);
+                // Half the diagonals of the smaller squares, one along the
+                // hypotenuse and one across it.
+                let along = scale(sub(u, v), FRAC_1_SQRT_2);
+                let across = scale(add(u, v), FRAC_1_SQRT_2);
+                let middle = add(c, across);
+                let (near_a, near_b) = (sub(a, along), add(b, along));
+                let (on_ca, on_cb) = (add(c, u), add(c, scale(v, SQRT_2)));
+                vec![
+                    Tile::Rhombus { o: c, p: on_ca, q: near_a, r: middle },
+                    Tile::Rhombus { o: b, p: on_cb, q: middle, r: near_b },
+                    Tile::Triangle { c: near_a, a: on_ca, b: a },
+                    Tile::Triangle { c: middle, a: on_cb, b: c },
+                    Tile::Triangle { c: middle, a: near_b, b: near_a },
+                ]
+            }
+        };
+        for tile in tiles {
+            self.draw_ammann_tile(tile, depth - 1, color);
+        }
+    }
+}
+
+fn add(a: Point, b: Point) -> Point {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn scale(a: Point, by: f32) -> Point {
+    (a.0 * by, a.1 * by)
+}
+
+#[cfg(test)]
+mod t {
+    use crate::t::centered_tiling;
+    use crate::Canvas;
+
+    fn tiling(size: f32, generations: u32) -> Canvas {
+        centered_tiling(Canvas::draw_ammann_beenker, 241, size, generations)
+    }
+
+    #[test]
+    fn check_ammann_beenker_star() {
+        // Before any substitution, it's eight rhombi meeting in the middle,
+        // with spokes 45° apart.
+        let star = tiling(60.0, 0);
+        assert_eq!(star.get_pixel(120, 120), 1);
+        for &(x, y) in [(180, 120), (162, 162), (120, 180), (60, 120)].iter() {
+            assert_eq!(star.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        // The far corners are 2cos(22.5°) times further out.
+        assert_eq!(star.get_pixel(222, 162), 1);
+        // And the rhombi are empty.
+        assert_eq!(star.get_pixel(150, 132), 0);
+    }
+
+    #[test]
+    fn check_ammann_beenker_generations() {
+        let mut last = tiling(60.0, 0).pixel_histogram()[1];
+        for generations in 1..4 {
+            let canvas = tiling(60.0, generations);
+            let on = canvas.pixel_histogram()[1];
+            assert!(on > last, "{} generations: {} <= {}", generations, on, last);
+            last = on;
+
+            // Everything is inside the star.
+            for y in 0..241 {
+                for x in 0..241 {
+                    if canvas.get_pixel(x, y) != 0 {
+                        let (dx, dy) = ((x - 120) as f32, (y - 120) as f32);
+                        assert!(dx.hypot(dy) <= 112.0, "({}, {})", x, y);
+                    }
+                }
+            }
+        }
+
+        // Substitution stops once the sides get too short: 60 / 2.414^3 is
+        // 4.3, and 60 / 2.414^4 is 1.8.
+        assert!(tiling(60.0, 3).pixels != tiling(60.0, 2).pixels);
+        assert!(tiling(60.0, 4).pixels == tiling(60.0, 3).pixels);
+        assert!(tiling(60.0, 1000).pixels == tiling(60.0, 3).pixels);
+
+        for &size in [0.0, -3.0, f32::NAN].iter() {
+            assert_eq!(tiling(size, 3).pixel_histogram()[1], 0);
+        }
+    }
+
+    #[test]
+    fn check_ammann_beenker_off_canvas() {
+        // Part of a tiling is the same as that part of the whole thing.
+        let whole = tiling(60.0, 3);
+        let mut part = Canvas::create(50, 30).unwrap();
+        part.draw_ammann_beenker(120 - 150, 120 - 100, 60.0, 3, 1);
+        assert!(part.pixel_histogram()[1] > 0);
+        for y in 0..30 {
+            for x in 0..50 {
+                assert_eq!(part.get_pixel(x, y), whole.get_pixel(x + 150, y + 100),
+                           "({}, {})", x, y);
+            }
+        }
+
+        // And only that part is substituted, so a tiling of millions of
+        // pixels with tiny tiles is quick to draw a corner of.
+        let mut corner = Canvas::create(40, 40).unwrap();
+        corner.draw_ammann_beenker(0, 0, 5e6, u32::MAX, 1);
+        assert!(corner.pixel_histogram()[1] > 0);
+
+        // Nowhere near the canvas, there's nothing to draw.
+        let mut far = Canvas::create(40, 40).unwrap();
+        far.draw_ammann_beenker(-1000, 20, 100.0, 5, 1);
+        assert_eq!(far.pixel_histogram()[1], 0);
+    }
+}
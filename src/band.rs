@@ -0,0 +1,117 @@
+//! Rendering canvases too tall to keep in memory, a band of rows at a time.
+
+use std::io::{self, Write};
+
+use crate::{bits, Canvas, CanvasError, CanvasError::*};
+
+/// Renders a `width` by `height` picture to braille without ever holding
+/// more than `band_height` rows of it.
+///
+/// The picture is drawn one band at a time, onto a canvas that's reused for
+/// every band, and each band is written out as soon as it's drawn. Since the
+/// bands are whole lines of braille, the output is exactly what rendering the
+/// whole picture at once would give.
+pub struct BandRenderer {
+    height: u32,
+    band: Canvas,
+}
+
+impl BandRenderer {
+    /// Create a renderer for a `width` by `height` picture, drawn in bands of
+    /// `band_height` rows.
+    ///
+    /// `band_height` must be a positive multiple of 4, the height of a line
+    /// of braille.
+    pub fn new(width: u32, height: u32, band_height: u32)
+        -> Result<BandRenderer, CanvasError>
+    {
+        if band_height == 0 || !band_height.is_multiple_of(4) {
+            return Err(InvalidParameter { name: "band_height" });
+        }
+
+        Ok(BandRenderer {
+            height,
+            band: Canvas::create(width, band_height.min(height))?,
+        })
+    }
+
+    /// Draw and render the whole picture to `out`.
+    ///
+    /// `draw(band, y_offset)` is called for each band, top to bottom, with a
+    /// blank canvas and the row of the picture that the band's first row is.
+    /// It should draw everything that falls in the band moved up by
+    /// `y_offset`, so that row `y` of the picture is row `y - y_offset` of the
+    /// band. Anything drawn outside of the band is clipped, like always.
+    pub fn render_to<W, F>(&mut self, out: &mut W, mut draw: F) -> io::Result<()>
+        where W: Write + ?Sized,
+              F: FnMut(&mut Canvas, i32)
+    {
+        let band_height = self.band.height as u32;
+        let mut line = String::new();
+
+        let mut top = 0;
+        while top < self.height {
+            // The last band can be shorter than the rest.
+            let rows = band_height.min(self.height - top);
+            self.band.pixels.truncate(bits::words_for(rows as usize * self.band.width as usize));
+            self.band.height = rows as i32;
+            self.band.clear();
+
+            draw(&mut self.band, top as i32);
+
+            for y in (0..self.band.height).step_by(4) {
+                line.clear();
+                self.band.render_line(y, &mut line);
+                out.write_all(line.as_bytes())?;
+            }
+
+            top += rows;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use crate::{BandRenderer, Canvas};
+
+    /// Draw a few shapes crossing lots of bands, with everything moved up by
+    /// `y_offset`.
+    fn draw_shapes(canvas: &mut Canvas, y_offset: i32) {
+        canvas.draw_line(0, -y_offset, 99, 210 - y_offset, 1);
+        canvas.draw_line(99, 3 - y_offset, 2, 150 - y_offset, 1);
+        canvas.draw_circle(50, 100 - y_offset, 45, 1);
+        for y in (0..211).step_by(7) {
+            canvas.draw_line(0, y - y_offset, 10, y - y_offset, 1);
+        }
+    }
+
+    #[test]
+    fn check_bands_match_whole() {
+        let mut whole = Canvas::create(100, 211).unwrap();
+        draw_shapes(&mut whole, 0);
+        let expected = whole.render();
+
+        for &band_height in [4, 12, 16, 64, 208, 212, 400].iter() {
+            let mut out = Vec::new();
+            BandRenderer::new(100, 211, band_height).unwrap()
+                         .render_to(&mut out, draw_shapes).unwrap();
+            assert!(String::from_utf8(out).unwrap() == expected,
+                    "band_height = {}", band_height);
+        }
+    }
+
+    #[test]
+    fn check_band_height() {
+        for &band_height in [0, 2, 6, 13].iter() {
+            assert!(BandRenderer::new(10, 10, band_height).is_err());
+        }
+
+        let mut out = Vec::new();
+        BandRenderer::new(10, 0, 8).unwrap()
+                     .render_to(&mut out, |_, _| panic!("no bands to draw"))
+                     .unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+//! Rendering many Schotter canvases at once.
+
+use rand::prelude::*;
+use rayon::prelude::*;
+
+use crate::{Canvas, CanvasError, SchotterConfig};
+
+/// Render one Schotter canvas per config, in parallel.
+///
+/// Each canvas is rendered independently, with its own randomness, on
+/// rayon's thread pool. Results come back in the same order as `configs`,
+/// so a bad config only fails its own entry.
+pub fn draw_schotter_batch(configs: &[SchotterConfig])
+    -> Vec<Result<Canvas, CanvasError>>
+{
+    configs.par_iter()
+           .map(|config| Canvas::create_and_render_schotter_with(config, &mut thread_rng()))
+           .collect()
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_batch_matches_configs() {
+        let configs = [
+            SchotterConfig::new(66, 8, 12),
+            SchotterConfig::new(0, 8, 12),
+            SchotterConfig { with_caption: true, ..SchotterConfig::new(40, 4, 6) },
+        ];
+        let results = draw_schotter_batch(&configs);
+        assert_eq!(results.len(), configs.len());
+
+        let canvas = results[0].as_ref().unwrap();
+        assert_eq!((canvas.width, canvas.height), (132, 196));
+        assert!(canvas.pixels.iter().any(|&px| px != 0));
+
+        assert!(results[1].is_err());
+
+        let captioned = results[2].as_ref().unwrap();
+        let (width, height) = crate::schotter_dimensions(40, 4, 6).unwrap();
+        assert_eq!(captioned.width as u32, width);
+        assert_eq!(captioned.height as u32, height + crate::font::CAPTION_HEIGHT);
+    }
+
+    #[test]
+    fn check_empty_batch() {
+        assert!(draw_schotter_batch(&[]).is_empty());
+    }
+}
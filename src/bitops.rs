@@ -0,0 +1,191 @@
+//! Combining whole canvases pixel by pixel, with `|`, `&` and `^`.
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign};
+
+use crate::{Canvas, CanvasError};
+
+impl Canvas {
+    /// A canvas with the pixels that are on in this canvas, `other`, or both.
+    ///
+    /// Fails if the canvases aren't the same size. `&a | &b` does the same,
+    /// but panics instead.
+    pub fn try_or(&self, other: &Canvas) -> Result<Canvas, CanvasError> {
+        self.combined(other, |a, b| a | b)
+    }
+
+    /// A canvas with the pixels that are on in both this canvas and `other`.
+    ///
+    /// Fails if the canvases aren't the same size. `&a & &b` does the same,
+    /// but panics instead.
+    pub fn try_and(&self, other: &Canvas) -> Result<Canvas, CanvasError> {
+        self.combined(other, |a, b| a & b)
+    }
+
+    /// A canvas with the pixels that are on in exactly one of this canvas and
+    /// `other`.
+    ///
+    /// Fails if the canvases aren't the same size. `&a ^ &b` does the same,
+    /// but panics instead.
+    pub fn try_xor(&self, other: &Canvas) -> Result<Canvas, CanvasError> {
+        self.combined(other, |a, b| a ^ b)
+    }
+
+    fn combined(&self, other: &Canvas, f: impl Fn(u64, u64) -> u64)
+        -> Result<Canvas, CanvasError>
+    {
+        let mut canvas = self.clone();
+        canvas.combine(other, f)?;
+        Ok(canvas)
+    }
+
+    /// Set each pixel to `f` of it and the same pixel of `other`, a word at
+    /// a time. `f` must keep clear bits clear, so the padding stays clear.
+    fn combine(&mut self, other: &Canvas, f: impl Fn(u64, u64) -> u64)
+        -> Result<(), CanvasError>
+    {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(CanvasError::SizeMismatch {
+                width:        self.width,
+                height:       self.height,
+                other_width:  other.width,
+                other_height: other.height,
+            });
+        }
+        for (word, &other) in self.pixels.iter_mut().zip(other.pixels.iter()) {
+            *word = f(*word, other);
+        }
+        Ok(())
+    }
+
+    fn combine_or_panic(&mut self, other: &Canvas, f: impl Fn(u64, u64) -> u64) {
+        if let Err(err) = self.combine(other, f) {
+            panic!("can't combine canvases: {}", err);
+        }
+    }
+}
+
+impl BitOr for &Canvas {
+    type Output = Canvas;
+
+    /// Panics if the canvases aren't the same size. See `Canvas::try_or`.
+    fn bitor(self, other: &Canvas) -> Canvas {
+        let mut canvas = self.clone();
+        canvas |= other;
+        canvas
+    }
+}
+
+impl BitAnd for &Canvas {
+    type Output = Canvas;
+
+    /// Panics if the canvases aren't the same size. See `Canvas::try_and`.
+    fn bitand(self, other: &Canvas) -> Canvas {
+        let mut canvas = self.clone();
+        canvas &= other;
+        canvas
+    }
+}
+
+impl BitXor for &Canvas {
+    type Output = Canvas;
+
+    /// Panics if the canvases aren't the same size. See `Canvas::try_xor`.
+    fn bitxor(self, other: &Canvas) -> Canvas {
+        let mut canvas = self.clone();
+        canvas ^= other;
+        canvas
+    }
+}
+
+impl BitOrAssign<&Canvas> for Canvas {
+    fn bitor_assign(&mut self, other: &Canvas) {
+        self.combine_or_panic(other, |a, b| a | b);
+    }
+}
+
+impl BitAndAssign<&Canvas> for Canvas {
+    fn bitand_assign(&mut self, other: &Canvas) {
+        self.combine_or_panic(other, |a, b| a & b);
+    }
+}
+
+impl BitXorAssign<&Canvas> for Canvas {
+    fn bitxor_assign(&mut self, other: &Canvas) {
+        self.combine_or_panic(other, |a, b| a ^ b);
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::string::ToString;
+
+    use rand::prelude::*;
+
+    use super::*;
+    use crate::SchotterConfig;
+
+    /// Every pairing of off and on, with a value other than 1 for on.
+    fn pair() -> (Canvas, Canvas) {
+        let a = Canvas::from_fn(2, 2, |x, _| x as u8 * 7).unwrap();
+        let b = Canvas::from_fn(2, 2, |_, y| y as u8 * 255).unwrap();
+        (a, b)
+    }
+
+    fn pixels(canvas: &Canvas) -> [u8; 4] {
+        [canvas.get_pixel(0, 0), canvas.get_pixel(1, 0),
+         canvas.get_pixel(0, 1), canvas.get_pixel(1, 1)]
+    }
+
+    #[test]
+    fn check_truth_tables() {
+        let (a, b) = pair();
+        assert_eq!(pixels(&(&a | &b)), [0, 1, 1, 1]);
+        assert_eq!(pixels(&(&a & &b)), [0, 0, 0, 1]);
+        assert_eq!(pixels(&(&a ^ &b)), [0, 1, 1, 0]);
+
+        let mut c = a.clone();
+        c |= &b;
+        assert_eq!(pixels(&c), [0, 1, 1, 1]);
+        c &= &a;
+        assert_eq!(pixels(&c), [0, 1, 0, 1]);
+        c ^= &b;
+        assert_eq!(pixels(&c), [0, 1, 1, 0]);
+
+        assert_eq!(pixels(&a.try_or(&b).unwrap()), [0, 1, 1, 1]);
+        assert_eq!(pixels(&a.try_and(&b).unwrap()), [0, 0, 0, 1]);
+        assert_eq!(pixels(&a.try_xor(&b).unwrap()), [0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn check_xor_self_is_empty() {
+        let canvas = Canvas::create_and_render_schotter_with(&SchotterConfig::new(20, 4, 6),
+                                                             &mut StdRng::seed_from_u64(3))
+                         .unwrap();
+        let empty = &canvas ^ &canvas;
+        assert_eq!(empty.pixel_histogram(), [canvas.pixel_count(), 0]);
+        assert!(empty.validate_strict().is_ok());
+
+        // Or-ing with the inverse fills it, but not past the last pixel.
+        let mut inverse = canvas.clone();
+        inverse.invert();
+        let full = &canvas | &inverse;
+        assert_eq!(full.pixel_histogram(), [0, canvas.pixel_count()]);
+        assert!(full.validate_strict().is_ok());
+    }
+
+    #[test]
+    fn check_size_mismatch() {
+        let a = Canvas::create(4, 8).unwrap();
+        let b = Canvas::create(8, 4).unwrap();
+        let err = a.try_and(&b).err().unwrap();
+        assert_eq!(err.to_string(), "canvases are 4x8 and 8x4, but need to be the same size");
+
+        #[cfg(feature = "std")]
+        {
+            let panic = std::panic::catch_unwind(|| &a | &b).err().unwrap();
+            assert_eq!(panic.downcast_ref::<std::string::String>().unwrap(),
+                       "can't combine canvases: canvases are 4x8 and 8x4, \
+                        but need to be the same size");
+        }
+    }
+}
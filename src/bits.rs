@@ -0,0 +1,231 @@
+//! Pixels packed 64 to a `u64`, the way `Canvas` stores them.
+//!
+//! Pixel `i` is bit `i % 64` of word `i / 64`. The bits after the last pixel
+//! in the last word are always kept clear, so that whole words can be
+//! compared and counted.
+
+use core::ops::Range;
+
+pub(crate) const WORD_BITS: usize = 64;
+
+/// How many words it takes to hold `len` pixels.
+pub(crate) fn words_for(len: usize) -> usize {
+    len.div_ceil(WORD_BITS)
+}
+
+/// The bits `lo..hi` of a word, where `lo < hi <= 64`.
+fn mask(lo: usize, hi: usize) -> u64 {
+    let below_hi = if hi == WORD_BITS { !0 } else { (1 << hi) - 1 };
+    below_hi & !((1 << lo) - 1)
+}
+
+/// Call `f(word, mask)` for each word that pixels `range` touch, with the
+/// bits of that word in the range.
+fn for_each_word(range: Range<usize>, mut f: impl FnMut(usize, u64)) {
+    if range.start >= range.end {
+        return;
+    }
+    let (first, last) = (range.start / WORD_BITS, (range.end - 1) / WORD_BITS);
+    for word in first..=last {
+        let lo = if word == first { range.start % WORD_BITS } else { 0 };
+        let hi = if word == last { (range.end - 1) % WORD_BITS + 1 } else { WORD_BITS };
+        f(word, mask(lo, hi));
+    }
+}
+
+pub(crate) fn get(words: &[u64], i: usize) -> bool {
+    words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+}
+
+pub(crate) fn set(words: &mut [u64], i: usize, on: bool) {
+    let bit = 1 << (i % WORD_BITS);
+    if on {
+        words[i / WORD_BITS] |= bit;
+    } else {
+        words[i / WORD_BITS] &= !bit;
+    }
+}
+
+/// Turn all of pixels `range` on or off, a word at a time.
+pub(crate) fn fill(words: &mut [u64], range: Range<usize>, on: bool) {
+    for_each_word(range, |word, mask| {
+        if on {
+            words[word] |= mask;
+        } else {
+            words[word] &= !mask;
+        }
+    });
+}
+
+/// Count the pixels in `range` that are on.
+pub(crate) fn count(words: &[u64], range: Range<usize>) -> usize {
+    let mut total = 0;
+    for_each_word(range, |word, mask| total += (words[word] & mask).count_ones() as usize);
+    total
+}
+
+/// The `len` pixels from `start`, where `len <= 64`, as the low bits of a
+/// word.
+fn read(words: &[u64], start: usize, len: usize) -> u64 {
+    if len == 0 {
+        return 0;
+    }
+    let (word, bit) = (start / WORD_BITS, start % WORD_BITS);
+    let mut value = words[word] >> bit;
+    if bit + len > WORD_BITS {
+        value |= words[word + 1] << (WORD_BITS - bit);
+    }
+    value & mask(0, len)
+}
+
+/// Set the `len` pixels from `start`, where `len <= 64`, to the low bits of
+/// `value`.
+fn write(words: &mut [u64], start: usize, len: usize, value: u64) {
+    if len == 0 {
+        return;
+    }
+    let (word, bit) = (start / WORD_BITS, start % WORD_BITS);
+    let value = value & mask(0, len);
+    words[word] = words[word] & !(mask(0, len) << bit) | value << bit;
+    if bit + len > WORD_BITS {
+        let rest = bit + len - WORD_BITS;
+        words[word + 1] = words[word + 1] & !mask(0, rest) | value >> (WORD_BITS - bit);
+    }
+}
+
+/// Copy pixels `src` of `from` to `to`, starting at pixel `dest`, a word at
+/// a time.
+pub(crate) fn copy(from: &[u64], src: Range<usize>, to: &mut [u64], dest: usize) {
+    for offset in (0..src.len()).step_by(WORD_BITS) {
+        let len = (src.len() - offset).min(WORD_BITS);
+        write(to, dest + offset, len, read(from, src.start + offset, len));
+    }
+}
+
+/// Copy pixels `src` to start at pixel `dest`, like `copy`, but within the
+/// same words. The two ranges may overlap.
+pub(crate) fn copy_within(words: &mut [u64], src: Range<usize>, dest: usize) {
+    let total = src.len();
+    let chunk = |offset: usize| (total - offset).min(WORD_BITS);
+    // Copy in the direction that reads each pixel before it's written over.
+    if dest < src.start {
+        for offset in (0..total).step_by(WORD_BITS) {
+            let len = chunk(offset);
+            let value = read(words, src.start + offset, len);
+            write(words, dest + offset, len, value);
+        }
+    } else if dest > src.start {
+        for offset in (0..total).step_by(WORD_BITS) {
+            let len = chunk(offset);
+            let value = read(words, src.end - offset - len, len);
+            write(words, dest + total - offset - len, len, value);
+        }
+    }
+}
+
+/// Swap the `len` pixels from `a` with the `len` pixels from `b`. The two
+/// ranges can't overlap.
+pub(crate) fn swap(words: &mut [u64], a: usize, b: usize, len: usize) {
+    for offset in (0..len).step_by(WORD_BITS) {
+        let n = (len - offset).min(WORD_BITS);
+        let (pa, pb) = (read(words, a + offset, n), read(words, b + offset, n));
+        write(words, a + offset, n, pb);
+        write(words, b + offset, n, pa);
+    }
+}
+
+/// Reverse the order of pixels `range`, a word at a time from each end.
+pub(crate) fn reverse(words: &mut [u64], range: Range<usize>) {
+    let reversed = |value: u64, len: usize| value.reverse_bits() >> (WORD_BITS - len);
+    let (mut lo, mut hi) = (range.start, range.end);
+    while hi - lo >= 2 {
+        let len = ((hi - lo) / 2).min(WORD_BITS);
+        let (left, right) = (read(words, lo, len), read(words, hi - len, len));
+        write(words, lo, len, reversed(right, len));
+        write(words, hi - len, len, reversed(left, len));
+        lo += len;
+        hi -= len;
+    }
+}
+
+/// Clear the bits after the first `len` pixels, after working on whole
+/// words.
+pub(crate) fn clear_past(words: &mut [u64], len: usize) {
+    fill(words, len..words.len() * WORD_BITS, false);
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn check_bits() {
+        let mut words = vec![0; words_for(130)];
+        assert_eq!(words.len(), 3);
+
+        fill(&mut words, 3..70, true);
+        assert_eq!(words, [!0b111, 0b11_1111, 0]);
+        assert_eq!(count(&words, 0..130), 67);
+        assert_eq!(count(&words, 60..66), 6);
+        assert!(get(&words, 69) && !get(&words, 70) && !get(&words, 2));
+
+        set(&mut words, 129, true);
+        set(&mut words, 4, false);
+        assert_eq!(count(&words, 0..130), 67);
+        fill(&mut words, 64..64, false);
+        assert_eq!(count(&words, 0..130), 67);
+
+        words.iter_mut().for_each(|word| *word = !*word);
+        clear_past(&mut words, 130);
+        assert_eq!(words[2], 0b01);
+        assert_eq!(count(&words, 0..130), 130 - 67);
+    }
+
+    /// Pixels with no pattern to them, so misplaced ones show.
+    fn scrambled(len: usize) -> (Vec<u64>, Vec<bool>) {
+        let bools: Vec<bool> = (0..len).map(|i| (i * 7 + i * i / 5) % 3 == 0).collect();
+        let mut words = vec![0; words_for(len)];
+        for (i, &on) in bools.iter().enumerate() {
+            set(&mut words, i, on);
+        }
+        (words, bools)
+    }
+
+    fn unpacked(words: &[u64], len: usize) -> Vec<bool> {
+        (0..len).map(|i| get(words, i)).collect()
+    }
+
+    #[test]
+    fn check_moving_bits() {
+        let len = 300;
+        for &(start, end, dest) in [(0, 300, 0), (0, 200, 100), (100, 300, 0), (3, 140, 5),
+                                    (70, 270, 1), (10, 11, 299), (64, 128, 0)].iter() {
+            let (mut words, mut bools) = scrambled(len);
+            copy_within(&mut words, start..end, dest);
+            bools.copy_within(start..end, dest);
+            assert_eq!(unpacked(&words, len), bools, "{}..{} to {}", start, end, dest);
+
+            let (from, from_bools) = scrambled(len);
+            let mut to = vec![!0; words_for(len)];
+            let mut to_bools = vec![true; len];
+            copy(&from, start..end, &mut to, dest);
+            to_bools[dest..dest + end - start].copy_from_slice(&from_bools[start..end]);
+            assert_eq!(unpacked(&to, len), to_bools, "{}..{} to {}", start, end, dest);
+        }
+
+        for &(start, end) in [(0, 300), (1, 2), (5, 6), (3, 200), (64, 192), (10, 139)].iter() {
+            let (mut words, mut bools) = scrambled(len);
+            reverse(&mut words, start..end);
+            bools[start..end].reverse();
+            assert_eq!(unpacked(&words, len), bools, "{}..{}", start, end);
+        }
+
+        let (mut words, mut bools) = scrambled(len);
+        swap(&mut words, 5, 150, 140);
+        let (a, b) = bools.split_at_mut(150);
+        a[5..145].swap_with_slice(&mut b[..140]);
+        assert_eq!(unpacked(&words, len), bools);
+    }
+}
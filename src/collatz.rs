@@ -0,0 +1,163 @@
+//! The Collatz conjecture, drawn as a tree.
+//!
+//! Every number's Collatz sequence seems to end at 1, so following them all
+//! backwards from 1 grows a tree, with a branch wherever two sequences meet.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::f32::consts::FRAC_PI_2;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Canvas;
+
+/// How far each step turns the tree, in radians. Even numbers turn it one
+/// way and odd numbers the other, a little further, which curls the branches
+/// like seaweed.
+const EVEN_TURN: f32 = 0.15;
+const ODD_TURN: f32 = -0.28;
+
+impl Canvas {
+    /// Draw the tree of the Collatz sequences of every number from 1 to
+    /// `max_start`.
+    ///
+    /// The tree grows up from 1. Each step back along a sequence is a line of
+    /// the same length, turned a little clockwise from the step before it if
+    /// the number it reaches is even, and a little further counterclockwise if
+    /// it's odd. Sequences that meet share the rest of their way down, so each
+    /// number is drawn once, however many sequences it's in. The tree is
+    /// scaled to fill as much of the canvas as it can.
+    ///
+    /// Nothing is drawn when `max_start` is less than 2. Sequences whose
+    /// numbers grow past `u64::MAX` are left out.
+    pub fn draw_collatz_tree(&mut self, max_start: u32, color: u8) {
+        let tree = collatz_tree(max_start);
+        if tree.len() < 2 {
+            return;
+        }
+
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for &(x, y, _) in tree.values() {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        let fit = |pixels: i32, span: f32| {
+            if span > 0.0 { (pixels - 1).max(0) as f32 / span } else { f32::INFINITY }
+        };
+        let scale = fit(self.width, max_x - min_x).min(fit(self.height, max_y - min_y));
+        // Centered, both ways.
+        let (ox, oy) = (((self.width - 1).max(0) as f32 - (max_x - min_x) * scale) / 2.0,
+                        ((self.height - 1).max(0) as f32 - (max_y - min_y) * scale) / 2.0);
+        let to_pixel = |(x, y, _): (f32, f32, f32)| {
+            (((x - min_x) * scale + ox).round() as i32, ((y - min_y) * scale + oy).round() as i32)
+        };
+
+        for (&n, &point) in tree.iter() {
+            if n == 1 {
+                continue;
+            }
+            let (x1, y1) = to_pixel(tree[&collatz_step(n).unwrap_or(1)]);
+            let (x2, y2) = to_pixel(point);
+            self.draw_line(x1, y1, x2, y2, color);
+        }
+    }
+}
+
+/// The number after `n` in its Collatz sequence, or `None` if that doesn't
+/// fit in a `u64`.
+fn collatz_step(n: u64) -> Option<u64> {
+    if n.is_multiple_of(2) {
+        Some(n / 2)
+    } else {
+        n.checked_mul(3)?.checked_add(1)
+    }
+}
+
+/// Lay out the tree of every number in the Collatz sequences from 1 to
+/// `max_start`, as `(x, y, heading)` for each number. The tree grows from 1
+/// at `(0, 0)`, facing up, with each step 1 long.
+fn collatz_tree(max_start: u32) -> BTreeMap<u64, (f32, f32, f32)> {
+    let mut tree = BTreeMap::new();
+    if max_start == 0 {
+        return tree;
+    }
+    tree.insert(1, (0.0, 0.0, -FRAC_PI_2));
+
+    let mut path = Vec::new();
+    'starts: for start in 2..=max_start as u64 {
+        // Follow the sequence down to a number that's already placed...
+        path.clear();
+        let mut n = start;
+        while !tree.contains_key(&n) {
+            path.push(n);
+            n = match collatz_step(n) {
+                Some(next) => next,
+                None       => continue 'starts,
+            };
+        }
+        // ...and then place the rest on the way back up.
+        let (mut x, mut y, mut heading) = tree[&n];
+        for &n in path.iter().rev() {
+            heading += if n.is_multiple_of(2) { EVEN_TURN } else { ODD_TURN };
+            x += heading.cos();
+            y += heading.sin();
+            tree.insert(n, (x, y, heading));
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_collatz_tree() {
+        // 3 goes 10, 5, 16, 8, 4, 2, 1.
+        let tree = collatz_tree(3);
+        let keys: Vec<u64> = tree.keys().copied().collect();
+        assert_eq!(keys, [1, 2, 3, 4, 5, 8, 10, 16]);
+        // Every step is the same length.
+        for (&n, &(x, y, _)) in tree.iter().filter(|&(&n, _)| n != 1) {
+            let (px, py, _) = tree[&collatz_step(n).unwrap()];
+            assert!(((x - px).hypot(y - py) - 1.0).abs() < 1e-4, "{}", n);
+        }
+        // 2 is straight up from 1, turned only a little.
+        let (x, y, _) = tree[&2];
+        assert!(x > 0.0 && x < 0.2 && y < -0.9, "({}, {})", x, y);
+
+        assert!(collatz_tree(0).is_empty());
+        assert_eq!(collatz_tree(1).len(), 1);
+        assert_eq!(collatz_step(u64::MAX), None);
+    }
+
+    #[test]
+    fn check_draw_collatz_tree() {
+        let mut canvas = Canvas::create(60, 60).unwrap();
+        canvas.draw_collatz_tree(30, 1);
+
+        // More starts only add branches: everything already there stays put.
+        let (small, tree) = (collatz_tree(10), collatz_tree(30));
+        assert!(small.iter().all(|(n, point)| tree.get(n) == Some(point)));
+
+        // It fills the canvas one way or the other.
+        let on = |x, y| canvas.get_pixel(x, y) != 0;
+        let touches = |edge: &dyn Fn(i32) -> (i32, i32)| (0..60).any(|i| {
+            let (x, y) = edge(i);
+            on(x, y)
+        });
+        assert!((touches(&|i| (0, i)) && touches(&|i| (59, i))) ||
+                (touches(&|i| (i, 0)) && touches(&|i| (i, 59))));
+        assert!(tree.len() > 30);
+
+        // 1 and 2 are still a line.
+        let mut canvas = Canvas::create(20, 20).unwrap();
+        canvas.draw_collatz_tree(2, 1);
+        assert_eq!(canvas.pixel_histogram()[1], 20);
+
+        canvas.clear();
+        canvas.draw_collatz_tree(1, 1);
+        assert_eq!(canvas.pixel_histogram()[1], 0);
+    }
+}
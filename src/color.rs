@@ -0,0 +1,168 @@
+//! ANSI terminal colors for rendered output.
+
+use alloc::{format, string::{String, ToString}};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::Canvas;
+
+/// The names of the 16 standard ANSI colors, in SGR order.
+const NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright-black", "bright-red", "bright-green", "bright-yellow",
+    "bright-blue", "bright-magenta", "bright-cyan", "bright-white",
+];
+
+/// A foreground color that a terminal can draw text in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 standard colors, by index: 0-7 are the normal colors,
+    /// and 8-15 their bright variants. The terminal's theme decides exactly
+    /// what these look like.
+    Named(u8),
+    /// A 24-bit color. Most modern terminals support these.
+    Rgb(u8, u8, u8),
+}
+
+/// An error parsing a `Color` from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "invalid color `{}`, expected a name ({}) or a hex color like #ff8800",
+               self.input, NAMES.join(", "))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parse a color name, like `red` or `bright-blue`, or a `#rrggbb` hex
+    /// color. Case doesn't matter.
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        let lower = s.to_ascii_lowercase();
+        let err = || ParseColorError { input: s.to_string() };
+
+        if let Some(hex) = lower.strip_prefix('#') {
+            if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(err());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| err());
+            return Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        let name = match lower.as_str() {
+            "gray" | "grey" => "bright-black",
+            name            => name,
+        };
+        NAMES.iter()
+             .position(|&n| n == name)
+             .map(|i| Color::Named(i as u8))
+             .ok_or_else(err)
+    }
+}
+
+/// The escape sequence that resets all text attributes.
+pub const RESET: &str = "\x1b[0m";
+
+impl Color {
+    /// The escape sequence that switches the foreground to this color.
+    pub fn sgr(&self) -> String {
+        match *self {
+            Color::Named(i) if i < 8 => format!("\x1b[{}m", 30 + i),
+            Color::Named(i)          => format!("\x1b[{}m", 90 + (i & 7)),
+            Color::Rgb(r, g, b)      => format!("\x1b[38;2;{};{};{}m", r, g, b),
+        }
+    }
+
+    /// Wrap each line of `text` in this color.
+    ///
+    /// The color is reset at the end of every line, so that a line cut off by
+    /// `head` or a resized terminal doesn't leave the color switched on.
+    pub fn paint(&self, text: &str) -> String {
+        let sgr = self.sgr();
+        let mut out = String::with_capacity(text.len() + text.len() / 8);
+        for line in text.split_inclusive('\n') {
+            let (body, newline) = match line.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None       => (line, ""),
+            };
+            out += &sgr;
+            out += body;
+            out += RESET;
+            out += newline;
+        }
+        out
+    }
+}
+
+impl Canvas {
+    /// Render the canvas like `Canvas::render`, drawn in `color`.
+    pub fn render_ansi(&self, color: Color) -> String {
+        color.paint(&self.render())
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_named_colors() {
+        assert_eq!("black".parse(), Ok(Color::Named(0)));
+        assert_eq!("red".parse(), Ok(Color::Named(1)));
+        assert_eq!("white".parse(), Ok(Color::Named(7)));
+        assert_eq!("bright-black".parse(), Ok(Color::Named(8)));
+        assert_eq!("gray".parse(), Ok(Color::Named(8)));
+        assert_eq!("grey".parse(), Ok(Color::Named(8)));
+        assert_eq!("Bright-Cyan".parse(), Ok(Color::Named(14)));
+        for (i, name) in NAMES.iter().enumerate() {
+            assert_eq!(name.parse(), Ok(Color::Named(i as u8)));
+        }
+    }
+
+    #[test]
+    fn check_hex_colors() {
+        assert_eq!("#ff8800".parse(), Ok(Color::Rgb(0xff, 0x88, 0x00)));
+        assert_eq!("#FF8800".parse(), Ok(Color::Rgb(0xff, 0x88, 0x00)));
+        assert_eq!("#000000".parse(), Ok(Color::Rgb(0, 0, 0)));
+
+        for bad in ["#fff", "#ff88000", "#gg0000", "ff8800", "#", "#+f+f+f"].iter() {
+            assert!(bad.parse::<Color>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn check_invalid_color_message() {
+        let message = "purple".parse::<Color>().unwrap_err().to_string();
+        assert!(message.contains("`purple`"));
+        assert!(message.contains("bright-magenta"));
+        assert!(message.contains("#ff8800"));
+    }
+
+    #[test]
+    fn check_sgr() {
+        assert_eq!(Color::Named(1).sgr(), "\x1b[31m");
+        assert_eq!(Color::Named(9).sgr(), "\x1b[91m");
+        assert_eq!(Color::Rgb(1, 2, 3).sgr(), "\x1b[38;2;1;2;3m");
+    }
+
+    #[test]
+    fn check_paint() {
+        let red = Color::Named(1);
+        assert_eq!(red.paint("ab\ncd\n"), "\x1b[31mab\x1b[0m\n\x1b[31mcd\x1b[0m\n");
+        assert_eq!(red.paint("ab"), "\x1b[31mab\x1b[0m");
+        assert_eq!(red.paint(""), "");
+
+        let mut canvas = Canvas::create(2, 4).unwrap();
+        canvas.fill();
+        assert_eq!(canvas.render_ansi(red), "\x1b[31m⣿\x1b[0m\n");
+    }
+}
@@ -0,0 +1,581 @@
+//! Parametric curves.
+//!
+//! Each of these evaluates a curve at evenly spaced parameter values and
+//! connects consecutive samples with `Canvas::draw_line`, so more `steps`
+//! gives a smoother curve.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::f32::consts::PI;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Canvas;
+
+/// A single step of a path drawn with `Canvas::draw_path`.
+///
+/// These mirror the commands of SVG paths, using absolute coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathCommand {
+    /// Start a new subpath at `(x, y)` without drawing anything.
+    MoveTo(i32, i32),
+    /// Draw a straight line to `(x, y)`.
+    LineTo(i32, i32),
+    /// Draw a quadratic Bézier curve with control point `(cx, cy)`, ending
+    /// at `(x, y)`.
+    QuadTo(i32, i32, i32, i32),
+    /// Draw a cubic Bézier curve with control points `(c1x, c1y)` and
+    /// `(c2x, c2y)`, ending at `(x, y)`.
+    CubicTo(i32, i32, i32, i32, i32, i32),
+    /// Draw a straight line back to the start of the current subpath.
+    Close,
+}
+
+impl Canvas {
+    /// Draw an Archimedean spiral centered at `(cx, cy)`.
+    ///
+    /// The radius grows linearly with the angle, `r = spacing * theta / 2π`,
+    /// for `theta` in `[0, 2π * turns]`. This means neighboring arms are
+    /// always `spacing` pixels apart, unlike a logarithmic spiral whose arms
+    /// spread out as it grows.
+    pub fn draw_archimedean_spiral(&mut self,
+                                   cx: i32,
+                                   cy: i32,
+                                   spacing: f32,
+                                   turns: f32,
+                                   steps: u32,
+                                   color: u8)
+    {
+        let max_theta = 2.0 * PI * turns;
+        self.draw_parametric(steps, color, |i| {
+            let theta = max_theta * i;
+            let r = spacing * theta / (2.0 * PI);
+            (cx as f32 + r * theta.cos(), cy as f32 + r * theta.sin())
+        });
+    }
+
+    /// Draw a hypocycloid centered at `(cx, cy)`: the path of a point on a
+    /// circle of radius `r` rolling around the inside of a fixed circle of
+    /// radius `big_r`.
+    ///
+    /// It's evaluated at `steps` evenly spaced points of
+    /// `x(t) = (R - r) cos(t) + r cos((R - r) / r * t)` and
+    /// `y(t) = (R - r) sin(t) - r sin((R - r) / r * t)`, with `t` going
+    /// around as many times as it takes for the curve to close, up to 100.
+    /// Here `R` is `big_r`. Whole ratios `R / r` make that many cusps: 3
+    /// makes a deltoid, and 4 an astroid. Other rational ratios make stars.
+    ///
+    /// Nothing is drawn when `r` is 0.
+    pub fn draw_hypocycloid(&mut self,
+                            cx: i32,
+                            cy: i32,
+                            big_r: f32,
+                            r: f32,
+                            steps: u32,
+                            color: u8)
+    {
+        if r == 0.0 {
+            return;
+        }
+        let max_t = 2.0 * PI * closing_turns(big_r / r) as f32;
+        let k = (big_r - r) / r;
+        self.draw_parametric(steps, color, |i| {
+            let t = max_t * i;
+            (cx as f32 + (big_r - r) * t.cos() + r * (k * t).cos(),
+             cy as f32 + (big_r - r) * t.sin() - r * (k * t).sin())
+        });
+    }
+
+    /// Draw an epitrochoid centered at `(cx, cy)`: the path of a point `d`
+    /// away from the center of a circle of radius `r` rolling around the
+    /// outside of a fixed circle of radius `big_r`.
+    ///
+    /// It's evaluated at `steps` evenly spaced points of
+    /// `x(t) = (R + r) cos(t) - d cos((R + r) / r * t)` and
+    /// `y(t) = (R + r) sin(t) - d sin((R + r) / r * t)`, with `t` going
+    /// around as many times as it takes for the curve to close, up to 100.
+    /// Here `R` is `big_r`. When `d` is `r`, the point is on the rim of the
+    /// rolling circle, and this is an epicycloid: equal radii make a cardioid.
+    ///
+    /// Nothing is drawn when `r` is 0.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_epitrochoid(&mut self,
+                            cx: i32,
+                            cy: i32,
+                            big_r: f32,
+                            r: f32,
+                            d: f32,
+                            steps: u32,
+                            color: u8)
+    {
+        if r == 0.0 {
+            return;
+        }
+        let max_t = 2.0 * PI * closing_turns(big_r / r) as f32;
+        let k = (big_r + r) / r;
+        self.draw_parametric(steps, color, |i| {
+            let t = max_t * i;
+            (cx as f32 + (big_r + r) * t.cos() - d * (k * t).cos(),
+             cy as f32 + (big_r + r) * t.sin() - d * (k * t).sin())
+        });
+    }
+
+    /// Draw an arc of the circle of `radius` centered at `(cx, cy)`.
+    ///
+    /// Angles are in radians and go from `start` to `end`. Since y grows
+    /// downwards, increasing angles go clockwise on screen: 0 is to the right
+    /// of the center and π/2 is below it.
+    pub fn draw_arc(&mut self,
+                    cx: i32,
+                    cy: i32,
+                    radius: f32,
+                    start: f32,
+                    end: f32,
+                    color: u8)
+    {
+        self.draw_arc_piece((cx as f32, cy as f32), radius, start, end, color, MAX_CURVE_SPLITS);
+    }
+
+    /// Draw the first `terms` terms of the Recamán sequence as arcs, in the
+    /// usual way.
+    ///
+    /// Each term is a point on a horizontal line across the middle of the
+    /// canvas, with 0 at the left edge and the largest term at the right, and
+    /// each pair of consecutive terms is joined by a half circle. The half
+    /// circles alternate between above the line and below it, starting above.
+    /// The scale is the largest that fits both the line and the biggest half
+    /// circle on the canvas.
+    pub fn draw_recaman_sequence(&mut self, terms: u32, color: u8) {
+        let sequence = recaman(terms);
+        let largest = sequence.iter().copied().max().unwrap_or(0);
+        let widest = sequence.windows(2).map(|w| w[0].abs_diff(w[1])).max().unwrap_or(0);
+        if widest == 0 {
+            return;
+        }
+
+        let scale = ((self.width - 1).max(0) as f32 / largest as f32)
+                        .min((self.height - 1).max(0) as f32 / widest as f32);
+        let axis = (self.height - 1).max(0) / 2;
+        for (n, pair) in sequence.windows(2).enumerate() {
+            let middle = (pair[0] + pair[1]) as f32 / 2.0 * scale;
+            let radius = pair[0].abs_diff(pair[1]) as f32 / 2.0 * scale;
+            // y grows downwards, so the half from π to 2π is the top one.
+            let start = if n % 2 == 0 { PI } else { 0.0 };
+            self.draw_arc(middle.round() as i32, axis, radius, start, start + PI, color);
+        }
+    }
+
+    /// Draw a sequence of path commands, in the style of SVG's `<path>`.
+    ///
+    /// Drawing starts at `(0, 0)` until the first `MoveTo`. Curves are
+    /// flattened into enough line segments that no segment is more than a
+    /// couple of pixels long.
+    pub fn draw_path(&mut self, commands: &[PathCommand], color: u8) {
+        let mut current = (0, 0);
+        let mut start = current;
+
+        for command in commands {
+            match *command {
+                PathCommand::MoveTo(x, y) => {
+                    current = (x, y);
+                    start = current;
+                }
+                PathCommand::LineTo(x, y) => {
+                    self.draw_line(current.0, current.1, x, y, color);
+                    current = (x, y);
+                }
+                PathCommand::QuadTo(cx, cy, x, y) => {
+                    let p0 = to_f32(current);
+                    let p1 = to_f32((cx, cy));
+                    let p2 = to_f32((x, y));
+                    self.draw_bezier(&[p0, p1, p2], color, MAX_CURVE_SPLITS);
+                    current = (x, y);
+                }
+                PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                    let p0 = to_f32(current);
+                    let p1 = to_f32((c1x, c1y));
+                    let p2 = to_f32((c2x, c2y));
+                    let p3 = to_f32((x, y));
+                    self.draw_bezier(&[p0, p1, p2, p3], color, MAX_CURVE_SPLITS);
+                    current = (x, y);
+                }
+                PathCommand::Close => {
+                    self.draw_line(current.0, current.1, start.0, start.1, color);
+                    current = start;
+                }
+            }
+        }
+    }
+
+    /// Draw the part of `draw_arc`'s arc from `start` to `end` that can reach
+    /// the canvas.
+    ///
+    /// Arcs too long to flatten in one go are halved, the same way as in
+    /// `draw_bezier`. An arc of at most a quarter turn stays inside the
+    /// triangle of its ends and the corner where the tangents at its ends
+    /// meet, so halves whose triangle misses the canvas are skipped.
+    fn draw_arc_piece(&mut self,
+                      center: (f32, f32),
+                      radius: f32,
+                      start: f32,
+                      end: f32,
+                      color: u8,
+                      splits: u32)
+    {
+        let point = |theta: f32| (center.0 + radius * theta.cos(),
+                                  center.1 + radius * theta.sin());
+        let span = end - start;
+        let middle = start + span / 2.0;
+        if span.abs() <= PI / 2.0 {
+            let corner = radius / (span / 2.0).cos();
+            let hull = [point(start),
+                        (center.0 + corner * middle.cos(), center.1 + corner * middle.sin()),
+                        point(end)];
+            if self.misses_canvas(&hull) {
+                return;
+            }
+        }
+
+        // One segment per couple of pixels of arc, like `flattening_steps`.
+        let steps = (((radius * span).abs() / 2.0).ceil() as u32).max(1);
+        if steps <= MAX_FLATTENING_STEPS || splits == 0 {
+            self.draw_parametric(steps.min(MAX_FLATTENING_STEPS), color, |t| {
+                point(start + span * t)
+            });
+            return;
+        }
+        self.draw_arc_piece(center, radius, start, middle, color, splits - 1);
+        self.draw_arc_piece(center, radius, middle, end, color, splits - 1);
+    }
+
+    /// Draw the part of the Bézier curve with control `points` that can reach
+    /// the canvas.
+    ///
+    /// The curve stays inside the bounding box of its control points, so it's
+    /// skipped if that misses the canvas. Otherwise, if it's too long to
+    /// flatten in one go, it's split in half and each half is drawn the same
+    /// way, so far-off control points don't cost billions of segments.
+    fn draw_bezier(&mut self, points: &[(f32, f32)], color: u8, splits: u32) {
+        if self.misses_canvas(points) {
+            return;
+        }
+
+        let steps = flattening_steps(points);
+        if steps <= MAX_FLATTENING_STEPS || splits == 0 {
+            self.draw_parametric(steps.min(MAX_FLATTENING_STEPS), color, |t| {
+                bezier_point(points, t)
+            });
+            return;
+        }
+        let (first, second) = split_bezier(points);
+        self.draw_bezier(&first[..points.len()], color, splits - 1);
+        self.draw_bezier(&second[..points.len()], color, splits - 1);
+    }
+
+    /// Sample `curve` at `steps + 1` evenly spaced points in `[0, 1]` and
+    /// connect them with lines.
+    fn draw_parametric<F>(&mut self, steps: u32, color: u8, curve: F)
+        where F: Fn(f32) -> (f32, f32)
+    {
+        if steps == 0 {
+            return;
+        }
+
+        let to_pixel = |(x, y): (f32, f32)| (x.round() as i32, y.round() as i32);
+        let mut prev = to_pixel(curve(0.0));
+        for step in 1..=steps {
+            let next = to_pixel(curve(step as f32 / steps as f32));
+            self.draw_line(prev.0, prev.1, next.0, next.1, color);
+            prev = next;
+        }
+    }
+}
+
+/// The first `terms` terms of the Recamán sequence: it starts at 0, and the
+/// `n`th term is `n` less than the one before if that's positive and not
+/// already in the sequence, and `n` more otherwise.
+fn recaman(terms: u32) -> Vec<u64> {
+    let mut sequence = Vec::with_capacity(terms as usize);
+    let mut seen = BTreeSet::new();
+    let mut term = 0u64;
+    for n in 0..terms as u64 {
+        if n > 0 {
+            term = match term.checked_sub(n) {
+                Some(back) if back > 0 && !seen.contains(&back) => back,
+                _ => term + n,
+            };
+        }
+        seen.insert(term);
+        sequence.push(term);
+    }
+    sequence
+}
+
+/// The most times a rolling circle goes around before its curve is drawn as
+/// if it were closed.
+const MAX_ROLLING_TURNS: u32 = 100;
+
+/// How many times a circle rolling around another has to go around for its
+/// curve to close, when the ratio of their radii is `ratio`.
+///
+/// That's the denominator of the ratio as a fraction, if it's small enough.
+fn closing_turns(ratio: f32) -> u32 {
+    (1..MAX_ROLLING_TURNS)
+        .find(|&turns| {
+            let laps = ratio * turns as f32;
+            (laps - laps.round()).abs() < 1e-3
+        })
+        .unwrap_or(MAX_ROLLING_TURNS)
+}
+
+fn to_f32((x, y): (i32, i32)) -> (f32, f32) {
+    (x as f32, y as f32)
+}
+
+/// The most segments a curve is flattened into in one go. Longer curves are
+/// split into pieces first, so that pieces off the canvas can be skipped.
+const MAX_FLATTENING_STEPS: u32 = 1024;
+
+/// The most times a curve is halved while looking for its pieces on the
+/// canvas. Each halving at least halves the pieces' lengths, so this is only
+/// ever reached by curves with infinite or NaN coordinates.
+const MAX_CURVE_SPLITS: u32 = 32;
+
+/// The control points of a Bézier curve of up to cubic degree, padded out
+/// at the end.
+type Controls = [(f32, f32); 4];
+
+fn lerp(p: (f32, f32), q: (f32, f32), t: f32) -> (f32, f32) {
+    (p.0 + (q.0 - p.0) * t, p.1 + (q.1 - p.1) * t)
+}
+
+/// Evaluate the Bézier curve with up to 4 control `points` at `t`, with de
+/// Casteljau's algorithm.
+fn bezier_point(points: &[(f32, f32)], t: f32) -> (f32, f32) {
+    let mut level: Controls = [(0.0, 0.0); 4];
+    level[..points.len()].copy_from_slice(points);
+    for len in (1..points.len()).rev() {
+        for j in 0..len {
+            level[j] = lerp(level[j], level[j + 1], t);
+        }
+    }
+    level[0]
+}
+
+/// Split the Bézier curve with up to 4 control `points` at `t = 0.5`, into
+/// the control points of its two halves.
+fn split_bezier(points: &[(f32, f32)]) -> (Controls, Controls) {
+    let n = points.len();
+    let mut level: Controls = [(0.0, 0.0); 4];
+    level[..n].copy_from_slice(points);
+    let (mut first, mut second): (Controls, Controls) = ([(0.0, 0.0); 4], [(0.0, 0.0); 4]);
+    for len in (1..=n).rev() {
+        first[n - len] = level[0];
+        second[len - 1] = level[len - 1];
+        for j in 0..len - 1 {
+            level[j] = lerp(level[j], level[j + 1], 0.5);
+        }
+    }
+    (first, second)
+}
+
+/// Choose how many line segments to flatten a Bézier curve into.
+///
+/// The curve is never longer than its control polygon, so splitting it into
+/// one segment per 2 pixels of the polygon is always smooth enough.
+fn flattening_steps(points: &[(f32, f32)]) -> u32 {
+    let length: f32 = points.windows(2)
+                            .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+                            .sum();
+    ((length / 2.0).ceil() as u32).max(1)
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::vec::Vec;
+    use std::f32::consts::PI;
+
+    use super::{closing_turns, recaman, MAX_ROLLING_TURNS};
+    use crate::{Canvas, PathCommand::*};
+
+    #[test]
+    fn check_archimedean_spiral_arms_are_evenly_spaced() {
+        let mut canvas = Canvas::create(64, 64).unwrap();
+        canvas.draw_archimedean_spiral(32, 32, 8.0, 3.0, 600, 1);
+
+        // Walking right from the center, we cross an arm every `spacing`
+        // pixels.
+        assert_eq!(canvas.get_pixel(32, 32), 1);
+        for &x in [40, 48, 56].iter() {
+            assert_eq!(canvas.get_pixel(x, 32), 1, "x = {}", x);
+        }
+        assert_eq!(canvas.get_pixel(44, 32), 0);
+        assert_eq!(canvas.get_pixel(52, 32), 0);
+    }
+
+    #[test]
+    fn check_hypocycloid() {
+        // An astroid has cusps at the top, bottom, left, and right, and
+        // curves in between them, nowhere near the corners.
+        let mut canvas = Canvas::create(41, 41).unwrap();
+        canvas.draw_hypocycloid(20, 20, 20.0, 5.0, 400, 1);
+        for &(x, y) in [(40, 20), (20, 0), (0, 20), (20, 40)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        assert_eq!(canvas.get_pixel(20, 20), 0);
+        assert_eq!(canvas.get_pixel(34, 34), 0);
+        // Halfway between cusps, it's R / 2^(3/2) out in x and y.
+        assert_eq!(canvas.get_pixel(27, 27), 1);
+
+        // A deltoid has three cusps, the first pointing right.
+        let mut canvas = Canvas::create(41, 41).unwrap();
+        canvas.draw_hypocycloid(20, 20, 18.0, 6.0, 300, 1);
+        assert_eq!(canvas.get_pixel(38, 20), 1);
+        assert_eq!(canvas.get_pixel(2, 20), 0);
+
+        let mut canvas = Canvas::create(10, 10).unwrap();
+        canvas.draw_hypocycloid(5, 5, 4.0, 0.0, 100, 1);
+        assert!(canvas.pixels.iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn check_epitrochoid() {
+        // A cardioid has its cusp on the right, and reaches three times as
+        // far to the left.
+        let mut canvas = Canvas::create(50, 50).unwrap();
+        canvas.draw_epitrochoid(35, 25, 10.0, 10.0, 10.0, 400, 1);
+        for &(x, y) in [(45, 25), (5, 25), (45, 5), (45, 45)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        assert_eq!(canvas.get_pixel(35, 25), 0);
+        assert_eq!(canvas.get_pixel(25, 25), 0);
+
+        // With `d` at 0, it's just a circle of radius `R + r`.
+        let mut canvas = Canvas::create(50, 50).unwrap();
+        canvas.draw_epitrochoid(25, 25, 15.0, 5.0, 0.0, 200, 1);
+        for &(x, y) in [(45, 25), (25, 5), (5, 25), (25, 45)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        assert_eq!(canvas.get_pixel(25, 25), 0);
+
+        let mut canvas = Canvas::create(10, 10).unwrap();
+        canvas.draw_epitrochoid(5, 5, 4.0, 0.0, 1.0, 100, 1);
+        assert!(canvas.pixels.iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn check_closing_turns() {
+        assert_eq!(closing_turns(4.0), 1);
+        assert_eq!(closing_turns(2.5), 2);
+        assert_eq!(closing_turns(5.0 / 3.0), 3);
+        assert_eq!(closing_turns(PI), MAX_ROLLING_TURNS);
+    }
+
+    #[test]
+    fn check_draw_arc() {
+        let mut canvas = Canvas::create(30, 30).unwrap();
+        canvas.draw_arc(5, 5, 20.0, 0.0, PI / 2.0, 1);
+
+        // The ends, and the point halfway around.
+        assert_eq!(canvas.get_pixel(25, 5), 1);
+        assert_eq!(canvas.get_pixel(5, 25), 1);
+        assert_eq!(canvas.get_pixel(19, 19), 1);
+        // Nothing outside of the quarter turn, or inside the circle.
+        assert_eq!(canvas.get_pixel(5, 5), 0);
+        assert_eq!(canvas.get_pixel(12, 12), 0);
+        for y in 0..5 {
+            for x in 0..30 {
+                assert_eq!(canvas.get_pixel(x, y), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn check_recaman_sequence() {
+        assert_eq!(recaman(11), [0, 1, 3, 6, 2, 7, 13, 20, 12, 21, 11]);
+        assert!(recaman(0).is_empty());
+
+        // 0, 1, 3 is 10 pixels to a step: a little arc up from 0 to 1, and a
+        // bigger one down from 1 to 3, on a line 10 pixels down.
+        let mut canvas = Canvas::create(41, 21).unwrap();
+        canvas.draw_recaman_sequence(3, 1);
+        for &(x, y) in [(0, 10), (5, 5), (10, 10), (20, 20), (30, 10)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        for &(x, y) in [(5, 15), (20, 0), (20, 10), (40, 10)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 0, "({}, {})", x, y);
+        }
+
+        // Many terms are scaled down until they just fit, either across the
+        // canvas or up and down it.
+        let mut canvas = Canvas::create(80, 40).unwrap();
+        canvas.draw_recaman_sequence(60, 1);
+        let on = |x, y| canvas.get_pixel(x, y) != 0;
+        let columns: Vec<i32> = (0..80).filter(|&x| (0..40).any(|y| on(x, y))).collect();
+        let rows: Vec<i32> = (0..40).filter(|&y| (0..80).any(|x| on(x, y))).collect();
+        assert_eq!(columns[0], 0);
+        assert!(columns[columns.len() - 1] == 79 || rows[0] == 0 || rows[rows.len() - 1] == 39,
+                "{:?} {:?}", columns, rows);
+
+        let mut canvas = Canvas::create(10, 10).unwrap();
+        canvas.draw_recaman_sequence(1, 1);
+        assert!(canvas.pixels.iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn check_draw_path_lines() {
+        let mut path = Canvas::create(20, 20).unwrap();
+        path.draw_path(&[MoveTo(2, 2), LineTo(15, 2), LineTo(15, 12), Close,
+                         MoveTo(0, 19), LineTo(19, 19)],
+                       1);
+
+        let mut lines = Canvas::create(20, 20).unwrap();
+        lines.draw_line(2, 2, 15, 2, 1);
+        lines.draw_line(15, 2, 15, 12, 1);
+        lines.draw_line(15, 12, 2, 2, 1);
+        lines.draw_line(0, 19, 19, 19, 1);
+
+        assert!(path.pixels == lines.pixels);
+    }
+
+    #[test]
+    fn check_draw_path_curves() {
+        // A straight "curve" is just a line.
+        let mut curve = Canvas::create(20, 20).unwrap();
+        curve.draw_path(&[MoveTo(0, 5), QuadTo(10, 5, 19, 5),
+                          MoveTo(0, 9), CubicTo(5, 9, 12, 9, 19, 9)],
+                        1);
+        let mut lines = Canvas::create(20, 20).unwrap();
+        lines.draw_line(0, 5, 19, 5, 1);
+        lines.draw_line(0, 9, 19, 9, 1);
+        assert!(curve.pixels == lines.pixels);
+
+        // Curves pass through their endpoints, and bend towards their
+        // control points.
+        let mut curve = Canvas::create(40, 40).unwrap();
+        curve.draw_path(&[MoveTo(0, 39), QuadTo(20, 0, 39, 39)], 1);
+        assert_eq!(curve.get_pixel(0, 39), 1);
+        assert_eq!(curve.get_pixel(39, 39), 1);
+        assert_eq!(curve.get_pixel(20, 20), 1);
+        assert_eq!(curve.get_pixel(20, 39), 0);
+    }
+
+    #[test]
+    fn check_far_off_curves() {
+        // A circle around the whole canvas, and one far off to the side:
+        // neither comes anywhere near a pixel, and neither takes billions of
+        // segments to find that out.
+        let mut canvas = Canvas::create(40, 40).unwrap();
+        canvas.draw_arc(20, 20, 1e9, 0.0, 2.0 * PI, 1);
+        canvas.draw_arc(2_000_000_000, 20, 1e9, 0.0, 2.0 * PI, 1);
+        assert_eq!(canvas.pixel_histogram()[1], 0);
+
+        // Curves with far-off control points still get their ends drawn.
+        canvas.draw_path(&[MoveTo(0, 0), QuadTo(20, 1_000_000_000, 39, 0),
+                           MoveTo(0, 39), CubicTo(0, -1_000_000_000, 39, -1_000_000_000, 39, 39)],
+                         1);
+        for &(x, y) in &[(0, 0), (39, 0), (0, 39), (39, 39)] {
+            assert_eq!(canvas.get_pixel(x, y), 1);
+        }
+        assert_eq!(canvas.get_pixel(20, 20), 0);
+    }
+}
@@ -0,0 +1,420 @@
+//! Exporting the canvas to image file formats.
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{Canvas, CanvasError};
+
+impl Canvas {
+    /// Encode the canvas as a binary ("P4") Portable BitMap.
+    ///
+    /// PBM is about the simplest image format there is, and nearly every
+    /// image tool can read it. On pixels are black and off pixels are white.
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let mut out = format!("P4\n{} {}\n", self.width, self.height).into_bytes();
+        out.extend(self.to_bitvec());
+        out
+    }
+
+    /// Pack the pixels 8 to a byte, most significant bit first, with each
+    /// row padded out to a whole byte. On pixels are set bits.
+    ///
+    /// This is an eighth of the size of the canvas in memory, and the same
+    /// layout as the pixels of a binary PBM.
+    pub fn to_bitvec(&self) -> Vec<u8> {
+        self.packed_rows(|x| 0x80 >> (x % 8))
+    }
+
+    /// Create a canvas from pixels packed the way `to_bitvec` packs them.
+    ///
+    /// Any bytes past the last row are ignored, and padding bits are never
+    /// read. Fails if `bits` is too short for a `width` by `height` canvas.
+    pub fn from_bitvec(width: u32, height: u32, bits: &[u8])
+        -> Result<Canvas, CanvasError>
+    {
+        let row_bytes = (width as usize).div_ceil(8);
+        let needed = row_bytes * height as usize;
+        if bits.len() < needed {
+            return Err(CanvasError::PixelBufferTooSmall { needed, actual: bits.len() });
+        }
+        Canvas::from_fn(width, height, |x, y| {
+            let byte = bits[y as usize * row_bytes + x as usize / 8];
+            (byte >> (7 - x % 8)) & 1
+        })
+    }
+
+    /// Encode the canvas as an uncompressed 8-bit grayscale TGA image.
+    ///
+    /// On pixels are black (`0x00`) and off pixels are white (`0xff`), ready
+    /// for printing. Rows are stored top to bottom.
+    ///
+    /// TGA can't describe images more than 65535 pixels wide or tall, so
+    /// only the top-left 65535 by 65535 pixels of a larger canvas are kept.
+    pub fn export_tga(&self) -> Vec<u8> {
+        let width = self.width.min(u16::MAX as i32);
+        let height = self.height.min(u16::MAX as i32);
+
+        let mut out = Vec::with_capacity(18 + width as usize * height as usize);
+        out.push(0);    // No image ID
+        out.push(0);    // No color map
+        out.push(3);    // Uncompressed grayscale
+        out.extend_from_slice(&[0; 5]);     // Color map specification
+        out.extend_from_slice(&[0; 4]);     // X and Y origin
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+        out.push(8);    // Bits per pixel
+        out.push(0x20); // Top-left origin, no alpha bits
+
+        for y in 0..height as usize {
+            let row = y * self.width as usize;
+            out.extend((row..row + width as usize).map(|i| {
+                if self.pixel_at(i) { 0x00 } else { 0xff }
+            }));
+        }
+        out
+    }
+
+    /// Pack each row 8 pixels to a byte, with the last byte of a row padded
+    /// out with zeros. `bit(x)` is the bit that column `x` sets in its byte.
+    fn packed_rows(&self, bit: impl Fn(i32) -> u8) -> Vec<u8> {
+        let row_bytes = (self.width as usize).div_ceil(8);
+        let mut out = Vec::with_capacity(row_bytes * self.height as usize);
+
+        for y in 0..self.height {
+            let mut byte = 0u8;
+            for x in 0..self.width {
+                if self.get_pixel(x, y) != 0 {
+                    byte |= bit(x);
+                }
+                if x % 8 == 7 {
+                    out.push(byte);
+                    byte = 0;
+                }
+            }
+            if self.width % 8 != 0 {
+                out.push(byte);
+            }
+        }
+
+        out
+    }
+
+    /// Encode the canvas as an X BitMap, C source that X11 tools can read
+    /// and that can be `#include`d straight into a program.
+    ///
+    /// `name` prefixes the `_width`, `_height`, and `_bits` identifiers, so it
+    /// should be a valid C identifier. On pixels are set bits.
+    pub fn write_xbm(&self, name: &str) -> String {
+        // The same layout as PBM, but least significant bit first.
+        let bits = self.packed_rows(|x| 1 << (x % 8));
+
+        let mut out = format!("#define {name}_width {}\n#define {name}_height {}\n",
+                              self.width, self.height, name = name);
+        out += &format!("static unsigned char {}_bits[] = {{", name);
+        // Twelve bytes to a line, like the bitmap(1) tool writes.
+        for (i, byte) in bits.iter().enumerate() {
+            if i > 0 {
+                out += ",";
+            }
+            out += if i % 12 == 0 { "\n   " } else { " " };
+            out += &format!("0x{:02x}", byte);
+        }
+        out += " };\n";
+
+        out
+    }
+
+    /// Wrap the braille render in a RESP bulk string, the reply a Redis
+    /// server would send for it.
+    ///
+    /// The length prefix counts bytes, not characters, since each braille
+    /// character is three bytes of UTF-8.
+    pub fn write_to_redis_resp(&self) -> Vec<u8> {
+        let text = self.render();
+
+        let mut out = format!("${}\r\n", text.len()).into_bytes();
+        out.extend_from_slice(text.as_bytes());
+        out.extend_from_slice(b"\r\n");
+
+        out
+    }
+
+    /// Render the canvas as an HTML `<table>`, one cell per pixel.
+    ///
+    /// On pixels are black and off pixels are white, and every cell is
+    /// `cell_size_px` pixels square. This needs no image encoding, so it can
+    /// go straight into a page, but it's big: use it for small canvases.
+    pub fn render_html(&self, cell_size_px: u32) -> String {
+        let cell = |background| {
+            format!("<td style=\"background: {}; width: {size}px; height: {size}px; \
+                     padding: 0\"></td>",
+                    background, size = cell_size_px)
+        };
+        let (on, off) = (cell("black"), cell("white"));
+
+        let mut out = String::from("<table style=\"border-collapse: collapse\">\n");
+        for y in 0..self.height {
+            out += "<tr>";
+            for x in 0..self.width {
+                out += if self.get_pixel(x, y) != 0 { &on } else { &off };
+            }
+            out += "</tr>\n";
+        }
+        out += "</table>\n";
+
+        out
+    }
+
+    /// Render the canvas as a TikZ picture, to typeset with LaTeX.
+    ///
+    /// Each on pixel is a filled square `scale` centimeters wide. TikZ's y
+    /// axis points up, so rows are counted from the bottom of the picture.
+    pub fn render_latex_tikz(&self, scale: f32) -> String {
+        let mut out = format!("\\begin{{tikzpicture}}[x={scale}cm, y={scale}cm]\n",
+                              scale = scale);
+        for y in 0..self.height {
+            let row = self.height - 1 - y;
+            for x in 0..self.width {
+                if self.get_pixel(x, y) != 0 {
+                    out += &format!("  \\fill ({}, {}) rectangle ({}, {});\n",
+                                    x, row, x + 1, row + 1);
+                }
+            }
+        }
+        out += "\\end{tikzpicture}\n";
+
+        out
+    }
+
+    /// Render the canvas as a Graphviz graph, in the DOT language.
+    ///
+    /// Each on pixel is a node named `p<x>_<y>`, pinned to its place on the
+    /// canvas, and each pair of on pixels next to each other horizontally or
+    /// vertically is an edge. Lay it out with `neato -n` to keep the pixels
+    /// where they are.
+    pub fn render_dot_graphviz(&self) -> String {
+        let on = |x, y| self.get_pixel(x, y) != 0;
+
+        let mut out = String::from("graph canvas {\n    node [shape=point];\n");
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if on(x, y) {
+                    // Graphviz's y axis points up.
+                    out += &format!("    p{x}_{y} [pos=\"{x},{}!\"];\n", -y, x = x, y = y);
+                }
+            }
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !on(x, y) {
+                    continue;
+                }
+                if on(x + 1, y) {
+                    out += &format!("    p{}_{} -- p{}_{};\n", x, y, x + 1, y);
+                }
+                if on(x, y + 1) {
+                    out += &format!("    p{}_{} -- p{}_{};\n", x, y, x, y + 1);
+                }
+            }
+        }
+        out += "}\n";
+
+        out
+    }
+
+    /// Render the canvas as JSON, like
+    /// `{"width": 3, "height": 2, "pixels": [[0,1,0],[1,1,1]]}`, with a row
+    /// array for each row of pixels, from the top. On pixels are 1.
+    pub fn render_as_json(&self) -> String {
+        let mut out = format!("{{\"width\": {}, \"height\": {}, \"pixels\": [",
+                              self.width, self.height);
+        for y in 0..self.height {
+            if y > 0 {
+                out.push(',');
+            }
+            out.push('[');
+            for x in 0..self.width {
+                if x > 0 {
+                    out.push(',');
+                }
+                out.push(if self.get_pixel(x, y) != 0 { '1' } else { '0' });
+            }
+            out.push(']');
+        }
+        out += "]}";
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::vec::Vec;
+
+    use crate::{Canvas, CanvasError};
+
+    /// A 10x2 canvas with pixels on either side of where its rows are split
+    /// into bytes.
+    fn straddling() -> Canvas {
+        let mut canvas = Canvas::create(10, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(9, 0, 1);
+        canvas.draw_pixel(7, 1, 1);
+        canvas.draw_pixel(8, 1, 1);
+        canvas
+    }
+
+    #[test]
+    fn check_pbm() {
+        let canvas = straddling();
+
+        let mut expected = b"P4\n10 2\n".to_vec();
+        expected.extend_from_slice(&[0b1000_0000, 0b0100_0000,
+                                     0b0000_0001, 0b1000_0000]);
+        assert_eq!(canvas.to_pbm(), expected);
+    }
+
+    #[test]
+    fn check_bitvec() {
+        let canvas = straddling();
+
+        let bits = canvas.to_bitvec();
+        assert_eq!(bits, [0b1000_0000, 0b0100_0000, 0b0000_0001, 0b1000_0000]);
+        let copy = Canvas::from_bitvec(10, 2, &bits).unwrap();
+        assert_eq!(copy.render(), canvas.render());
+        assert_eq!(copy.pixel_histogram(), [16, 4]);
+
+        // Padding bits and trailing bytes are ignored.
+        let copy = Canvas::from_bitvec(10, 2, &[0x80, 0x7f, 0x01, 0xbf, 0xff]).unwrap();
+        assert_eq!(copy.render(), canvas.render());
+
+        match Canvas::from_bitvec(10, 2, &bits[..3]) {
+            Err(CanvasError::PixelBufferTooSmall { needed: 4, actual: 3 }) => (),
+            other => panic!("Expected PixelBufferTooSmall, got {:?}", other.map(|_| ())),
+        }
+        assert!(Canvas::from_bitvec(0, 0, &[]).unwrap().to_bitvec().is_empty());
+    }
+
+    #[test]
+    fn check_xbm() {
+        let canvas = straddling();
+
+        assert_eq!(canvas.write_xbm("art"),
+                   "#define art_width 10\n\
+                    #define art_height 2\n\
+                    static unsigned char art_bits[] = {\n   \
+                    0x01, 0x02, 0x80, 0x01 };\n");
+    }
+
+    #[test]
+    fn check_xbm_wraps_lines() {
+        let canvas = Canvas::create(8, 13).unwrap();
+        let xbm = canvas.write_xbm("tall");
+        let lines: Vec<&str> = xbm.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[3], format!("   {}", ["0x00"; 12].join(", ")) + ",");
+        assert_eq!(lines[4], "   0x00 };");
+    }
+
+    #[test]
+    fn check_redis_resp() {
+        let mut canvas = Canvas::create(4, 4).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        assert_eq!(canvas.write_to_redis_resp(), "$7\r\n⠁⠀\n\r\n".as_bytes());
+
+        let canvas = Canvas::create(0, 0).unwrap();
+        assert_eq!(canvas.write_to_redis_resp(), b"$0\r\n\r\n");
+    }
+
+    #[test]
+    fn check_html() {
+        let mut canvas = Canvas::create(2, 1).unwrap();
+        canvas.draw_pixel(1, 0, 1);
+        assert_eq!(canvas.render_html(3),
+                   "<table style=\"border-collapse: collapse\">\n<tr>\
+                    <td style=\"background: white; width: 3px; height: 3px; padding: 0\"></td>\
+                    <td style=\"background: black; width: 3px; height: 3px; padding: 0\"></td>\
+                    </tr>\n</table>\n");
+
+        let html = Canvas::create(4, 3).unwrap().render_html(1);
+        assert_eq!(html.matches("<tr>").count(), 3);
+        assert_eq!(html.matches("<td ").count(), 12);
+    }
+
+    #[test]
+    fn check_tikz() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(2, 1, 1);
+        assert_eq!(canvas.render_latex_tikz(0.25),
+                   "\\begin{tikzpicture}[x=0.25cm, y=0.25cm]\n  \
+                    \\fill (0, 1) rectangle (1, 2);\n  \
+                    \\fill (2, 0) rectangle (3, 1);\n\
+                    \\end{tikzpicture}\n");
+    }
+
+    #[test]
+    fn check_dot() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(1, 0, 1);
+        canvas.draw_pixel(1, 1, 1);
+        canvas.draw_pixel(2, 0, 0);
+        assert_eq!(canvas.render_dot_graphviz(),
+                   "graph canvas {\n    node [shape=point];\n\
+                    \x20   p0_0 [pos=\"0,0!\"];\n\
+                    \x20   p1_0 [pos=\"1,0!\"];\n\
+                    \x20   p1_1 [pos=\"1,-1!\"];\n\
+                    \x20   p0_0 -- p1_0;\n\
+                    \x20   p1_0 -- p1_1;\n}\n");
+
+        // Diagonal neighbors aren't connected.
+        let mut canvas = Canvas::create(2, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(1, 1, 1);
+        assert!(!canvas.render_dot_graphviz().contains("--"));
+    }
+
+    #[test]
+    fn check_json() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.draw_pixel(1, 0, 1);
+        canvas.draw_line(0, 1, 2, 1, 1);
+        assert_eq!(canvas.render_as_json(),
+                   "{\"width\": 3, \"height\": 2, \"pixels\": [[0,1,0],[1,1,1]]}");
+
+        assert_eq!(Canvas::create(0, 0).unwrap().render_as_json(),
+                   "{\"width\": 0, \"height\": 0, \"pixels\": []}");
+        assert_eq!(Canvas::create(0, 2).unwrap().render_as_json(),
+                   "{\"width\": 0, \"height\": 2, \"pixels\": [[],[]]}");
+    }
+
+    #[test]
+    fn check_tga() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(2, 1, 1);
+
+        let tga = canvas.export_tga();
+        assert_eq!(tga[..18], [0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 2, 0, 8, 0x20]);
+        assert_eq!(tga[18..], [0x00, 0xff, 0xff,
+                               0xff, 0xff, 0x00]);
+
+        let wide = Canvas::create(300, 1).unwrap().export_tga();
+        assert_eq!(wide[12..14], [0x2c, 0x01]);
+        assert_eq!(wide.len(), 18 + 300);
+
+        // Too wide for TGA, so the right edge is cut off.
+        let mut too_wide = Canvas::create(65537, 2).unwrap();
+        too_wide.draw_pixel(65534, 1, 1);
+        too_wide.draw_pixel(65535, 0, 1);
+        let tga = too_wide.export_tga();
+        assert_eq!(tga[12..16], [0xff, 0xff, 2, 0]);
+        assert_eq!(tga.len(), 18 + 65535 * 2);
+        assert!(tga[18..18 + 65535].iter().all(|&px| px == 0xff));
+        assert_eq!(tga[18 + 65535 * 2 - 1], 0x00);
+
+        let too_tall = Canvas::create(1, 70000).unwrap().export_tga();
+        assert_eq!(too_tall[12..16], [1, 0, 0xff, 0xff]);
+        assert_eq!(too_tall.len(), 18 + 65535);
+    }
+}
@@ -0,0 +1,189 @@
+//! A C API, for drawing from programs that aren't written in Rust.
+//!
+//! A canvas is an opaque `Canvas *`, made by `lolwut_canvas_new` and given
+//! back to `lolwut_canvas_free`. Every function checks its pointers for null,
+//! and none of them unwind into C: a panic is caught at the boundary and
+//! reported like any other error, with a negative return value or a null
+//! pointer.
+//!
+//! The signatures stick to fixed-size integers and pointers, so `cbindgen`
+//! can generate a header for them.
+
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use rand::prelude::*;
+
+use crate::{Canvas, SchotterConfig};
+
+/// Create a blank canvas, `width` by `height` pixels.
+///
+/// Returns null if the canvas can't be made. The canvas must be freed with
+/// `lolwut_canvas_free`.
+#[no_mangle]
+pub extern "C" fn lolwut_canvas_new(width: u32, height: u32) -> *mut Canvas {
+    guard(ptr::null_mut(), || match Canvas::create(width, height) {
+        Ok(canvas) => Box::into_raw(Box::new(canvas)),
+        Err(_)     => ptr::null_mut(),
+    })
+}
+
+/// Free a canvas made by `lolwut_canvas_new`. Freeing null does nothing.
+///
+/// # Safety
+///
+/// `canvas` must be null or come from `lolwut_canvas_new`, and mustn't be
+/// used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn lolwut_canvas_free(canvas: *mut Canvas) {
+    if !canvas.is_null() {
+        guard((), || drop(Box::from_raw(canvas)));
+    }
+}
+
+/// Draw Schotter onto `canvas`, taking all randomness from `seed`, like
+/// `Canvas::draw_schotter_with`.
+///
+/// Returns 0 on success, or -1 if `canvas` is null or too small for the
+/// squares, or a parameter is out of range.
+///
+/// # Safety
+///
+/// `canvas` must be null or a live canvas from `lolwut_canvas_new`.
+#[no_mangle]
+pub unsafe extern "C" fn lolwut_draw_schotter(canvas: *mut Canvas,
+                                              cols: i32,
+                                              squares_per_row: i32,
+                                              squares_per_col: i32,
+                                              seed: u64)
+    -> c_int
+{
+    let canvas = match canvas.as_mut() {
+        Some(canvas) => canvas,
+        None         => return -1,
+    };
+    guard(-1, || {
+        let config = SchotterConfig::new(cols, squares_per_row, squares_per_col);
+        match canvas.draw_schotter_with(&config, &mut StdRng::seed_from_u64(seed)) {
+            Ok(()) => 0,
+            Err(_) => -1,
+        }
+    })
+}
+
+/// Render `canvas` as braille text, like `Canvas::render`, into the
+/// `out_len` bytes at `out_buf`.
+///
+/// Returns the size of the rendering in bytes, UTF-8 with a terminating NUL.
+/// If that's more than `out_len`, nothing is written, so the buffer can be
+/// sized by calling this with a null `out_buf` and an `out_len` of 0 first.
+/// Returns -1 if `canvas` is null, or if `out_buf` is null but `out_len`
+/// isn't 0.
+///
+/// # Safety
+///
+/// `canvas` must be null or a live canvas from `lolwut_canvas_new`, and
+/// `out_buf` must be null or valid for writing `out_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lolwut_render(canvas: *const Canvas,
+                                       out_buf: *mut c_char,
+                                       out_len: usize)
+    -> isize
+{
+    let canvas = match canvas.as_ref() {
+        Some(canvas) => canvas,
+        None         => return -1,
+    };
+    if out_buf.is_null() && out_len != 0 {
+        return -1;
+    }
+    guard(-1, || {
+        let text = canvas.render();
+        let needed = text.len() + 1;
+        if needed <= out_len {
+            ptr::copy_nonoverlapping(text.as_ptr(), out_buf as *mut u8, text.len());
+            *out_buf.add(text.len()) = 0;
+        }
+        needed as isize
+    })
+}
+
+/// The pixel at `(x, y)` on `canvas`, like `Canvas::get_pixel`: 0 when out
+/// of bounds, or -1 if `canvas` is null.
+///
+/// # Safety
+///
+/// `canvas` must be null or a live canvas from `lolwut_canvas_new`.
+#[no_mangle]
+pub unsafe extern "C" fn lolwut_get_pixel(canvas: *const Canvas, x: i32, y: i32) -> c_int {
+    match canvas.as_ref() {
+        Some(canvas) => guard(-1, || canvas.get_pixel(x, y) as c_int),
+        None         => -1,
+    }
+}
+
+/// Run `f`, returning `on_panic` instead if it panics, so that panics never
+/// unwind into C.
+fn guard<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_ffi_schotter() {
+        let canvas = lolwut_canvas_new(80, 118);
+        assert!(!canvas.is_null());
+        unsafe {
+            assert_eq!(lolwut_draw_schotter(canvas, 40, 8, 12, 7), 0);
+
+            let config = SchotterConfig::new(40, 8, 12);
+            let expected = Canvas::create_and_render_schotter_with(&config,
+                                                                   &mut StdRng::seed_from_u64(7))
+                               .unwrap();
+            for &(x, y) in [(0, 0), (10, 10), (40, 60), (79, 117), (80, 0)].iter() {
+                assert_eq!(lolwut_get_pixel(canvas, x, y),
+                           expected.get_pixel(x, y) as c_int);
+            }
+
+            // Ask for the size, then render for real.
+            let needed = lolwut_render(canvas, ptr::null_mut(), 0);
+            let text = expected.render();
+            assert_eq!(needed, text.len() as isize + 1);
+            let mut buf = vec![1 as c_char; needed as usize];
+            assert_eq!(lolwut_render(canvas, buf.as_mut_ptr(), buf.len()), needed);
+            let bytes: Vec<u8> = buf.iter().map(|&b| b as u8).collect();
+            assert_eq!(&bytes[..text.len()], text.as_bytes());
+            assert_eq!(bytes[text.len()], 0);
+
+            // Too small, by just the NUL: nothing is written.
+            let mut small = vec![1 as c_char; needed as usize - 1];
+            assert_eq!(lolwut_render(canvas, small.as_mut_ptr(), small.len()), needed);
+            assert!(small.iter().all(|&b| b == 1));
+
+            // Too small for the squares.
+            let tiny = lolwut_canvas_new(10, 10);
+            assert_eq!(lolwut_draw_schotter(tiny, 40, 8, 12, 7), -1);
+
+            lolwut_canvas_free(tiny);
+            lolwut_canvas_free(canvas);
+        }
+    }
+
+    #[test]
+    fn check_ffi_nulls() {
+        unsafe {
+            lolwut_canvas_free(ptr::null_mut());
+            assert_eq!(lolwut_draw_schotter(ptr::null_mut(), 40, 8, 12, 7), -1);
+            assert_eq!(lolwut_render(ptr::null(), ptr::null_mut(), 0), -1);
+            assert_eq!(lolwut_get_pixel(ptr::null(), 0, 0), -1);
+
+            let canvas = lolwut_canvas_new(2, 4);
+            assert_eq!(lolwut_render(canvas, ptr::null_mut(), 10), -1);
+            lolwut_canvas_free(canvas);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! A tiny embedded bitmap font, and text drawing built on top of it.
+//!
+//! Glyphs are 3 pixels wide and 5 pixels tall, which is about as small as
+//! legible text gets. There is only one case: lowercase letters are drawn
+//! with the uppercase glyphs.
+
+use crate::{Canvas, CanvasError, CanvasError::*};
+
+/// Width of a single glyph, in pixels.
+pub const GLYPH_WIDTH: u32 = 3;
+
+/// Height of a single glyph, in pixels.
+pub const GLYPH_HEIGHT: u32 = 5;
+
+/// Horizontal distance from the start of one character to the next.
+pub const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+/// Number of pixel rows at the bottom of a canvas that `draw_caption` uses.
+///
+/// This is two rows of braille characters, with the text centered in it.
+pub const CAPTION_HEIGHT: u32 = 8;
+
+/// Each glyph is stored as 5 rows, top to bottom. The low 3 bits of each row
+/// are the pixels, with the leftmost pixel in the highest bit.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 5]; 95] = [
+    [0b000, 0b000, 0b000, 0b000, 0b000], // ' '
+    [0b010, 0b010, 0b010, 0b000, 0b010], // '!'
+    [0b101, 0b101, 0b000, 0b000, 0b000], // '"'
+    [0b101, 0b111, 0b101, 0b111, 0b101], // '#'
+    [0b011, 0b110, 0b010, 0b011, 0b110], // '$'
+    [0b101, 0b001, 0b010, 0b100, 0b101], // '%'
+    [0b010, 0b101, 0b010, 0b101, 0b011], // '&'
+    [0b010, 0b010, 0b000, 0b000, 0b000], // '\''
+    [0b001, 0b010, 0b010, 0b010, 0b001], // '('
+    [0b100, 0b010, 0b010, 0b010, 0b100], // ')'
+    [0b000, 0b101, 0b010, 0b101, 0b000], // '*'
+    [0b000, 0b010, 0b111, 0b010, 0b000], // '+'
+    [0b000, 0b000, 0b000, 0b010, 0b100], // ','
+    [0b000, 0b000, 0b111, 0b000, 0b000], // '-'
+    [0b000, 0b000, 0b000, 0b000, 0b010], // '.'
+    [0b001, 0b001, 0b010, 0b100, 0b100], // '/'
+    [0b111, 0b101, 0b101, 0b101, 0b111], // '0'
+    [0b010, 0b110, 0b010, 0b010, 0b111], // '1'
+    [0b111, 0b001, 0b111, 0b100, 0b111], // '2'
+    [0b111, 0b001, 0b111, 0b001, 0b111], // '3'
+    [0b101, 0b101, 0b111, 0b001, 0b001], // '4'
+    [0b111, 0b100, 0b111, 0b001, 0b111], // '5'
+    [0b111, 0b100, 0b111, 0b101, 0b111], // '6'
+    [0b111, 0b001, 0b001, 0b010, 0b010], // '7'
+    [0b111, 0b101, 0b111, 0b101, 0b111], // '8'
+    [0b111, 0b101, 0b111, 0b001, 0b111], // '9'
+    [0b000, 0b010, 0b000, 0b010, 0b000], // ':'
+    [0b000, 0b010, 0b000, 0b010, 0b100], // ';'
+    [0b001, 0b010, 0b100, 0b010, 0b001], // '<'
+    [0b000, 0b111, 0b000, 0b111, 0b000], // '='
+    [0b100, 0b010, 0b001, 0b010, 0b100], // '>'
+    [0b111, 0b001, 0b010, 0b000, 0b010], // '?'
+    [0b010, 0b101, 0b111, 0b100, 0b011], // '@'
+    [0b010, 0b101, 0b111, 0b101, 0b101], // 'A'
+    [0b110, 0b101, 0b110, 0b101, 0b110], // 'B'
+    [0b011, 0b100, 0b100, 0b100, 0b011], // 'C'
+    [0b110, 0b101, 0b101, 0b101, 0b110], // 'D'
+    [0b111, 0b100, 0b110, 0b100, 0b111], // 'E'
+    [0b111, 0b100, 0b110, 0b100, 0b100], // 'F'
+    [0b011, 0b100, 0b101, 0b101, 0b011], // 'G'
+    [0b101, 0b101, 0b111, 0b101, 0b101], // 'H'
+    [0b111, 0b010, 0b010, 0b010, 0b111], // 'I'
+    [0b001, 0b001, 0b001, 0b101, 0b010], // 'J'
+    [0b101, 0b101, 0b110, 0b101, 0b101], // 'K'
+    [0b100, 0b100, 0b100, 0b100, 0b111], // 'L'
+    [0b101, 0b111, 0b111, 0b101, 0b101], // 'M'
+    [0b110, 0b101, 0b101, 0b101, 0b101], // 'N'
+    [0b010, 0b101, 0b101, 0b101, 0b010], // 'O'
+    [0b110, 0b101, 0b110, 0b100, 0b100], // 'P'
+    [0b010, 0b101, 0b101, 0b110, 0b011], // 'Q'
+    [0b110, 0b101, 0b110, 0b101, 0b101], // 'R'
+    [0b011, 0b100, 0b010, 0b001, 0b110], // 'S'
+    [0b111, 0b010, 0b010, 0b010, 0b010], // 'T'
+    [0b101, 0b101, 0b101, 0b101, 0b111], // 'U'
+    [0b101, 0b101, 0b101, 0b101, 0b010], // 'V'
+    [0b101, 0b101, 0b111, 0b111, 0b101], // 'W'
+    [0b101, 0b101, 0b010, 0b101, 0b101], // 'X'
+    [0b101, 0b101, 0b010, 0b010, 0b010], // 'Y'
+    [0b111, 0b001, 0b010, 0b100, 0b111], // 'Z'
+    [0b110, 0b100, 0b100, 0b100, 0b110], // '['
+    [0b100, 0b100, 0b010, 0b001, 0b001], // '\\'
+    [0b011, 0b001, 0b001, 0b001, 0b011], // ']'
+    [0b010, 0b101, 0b000, 0b000, 0b000], // '^'
+    [0b000, 0b000, 0b000, 0b000, 0b111], // '_'
+    [0b100, 0b010, 0b000, 0b000, 0b000], // '`'
+    // Lowercase letters are never looked up, see `glyph()`.
+    [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5],
+    [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5],
+    [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5], [0; 5],
+    [0b011, 0b010, 0b110, 0b010, 0b011], // '{'
+    [0b010, 0b010, 0b010, 0b010, 0b010], // '|'
+    [0b110, 0b010, 0b011, 0b010, 0b110], // '}'
+    [0b000, 0b011, 0b110, 0b000, 0b000], // '~'
+];
+
+/// Look up the glyph for `c`. Characters without a glyph are drawn as '?'.
+pub(crate) fn glyph(c: char) -> &'static [u8; 5] {
+    let c = c.to_ascii_uppercase();
+    let c = if (' '..='~').contains(&c) { c } else { '?' };
+    &GLYPHS[c as usize - ' ' as usize]
+}
+
+/// Width in pixels of `text` when drawn on a single line.
+pub fn text_width(text: &str) -> u32 {
+    match text.chars().count() as u32 {
+        0 => 0,
+        n => n * GLYPH_ADVANCE - 1,
+    }
+}
+
+/// Width in pixels of each glyph of a `Canvas::create_banner` banner
+/// `pixel_height` pixels tall: the font's width, scaled and rounded, and
+/// never less than 1.
+pub fn banner_glyph_width(pixel_height: u32) -> u32 {
+    scaled(GLYPH_WIDTH, pixel_height)
+}
+
+/// Pixels between the characters and lines of a `Canvas::create_banner`
+/// banner `pixel_height` pixels tall: one font pixel, scaled and rounded,
+/// and never less than 1.
+pub fn banner_spacing(pixel_height: u32) -> u32 {
+    scaled(1, pixel_height)
+}
+
+/// `font_pixels` scaled from the font's height to `pixel_height`, rounded
+/// to the nearest pixel, and at least 1.
+fn scaled(font_pixels: u32, pixel_height: u32) -> u32 {
+    let pixels = (font_pixels as u64 * pixel_height as u64 * 2 + GLYPH_HEIGHT as u64)
+               / (2 * GLYPH_HEIGHT as u64);
+    pixels.clamp(1, u32::MAX as u64) as u32
+}
+
+impl Canvas {
+    /// Draw `text` on a single line with its top-left corner at `(x, y)`.
+    ///
+    /// Text that runs off the canvas is clipped.
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, color: u8) {
+        let mut left = x;
+        for c in text.chars() {
+            if left >= self.width {
+                break;
+            }
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        self.draw_pixel(left + col as i32, y + row as i32, color);
+                    }
+                }
+            }
+            left += GLYPH_ADVANCE as i32;
+        }
+    }
+
+    /// Create a canvas with `text` written on it in letters `pixel_height`
+    /// pixels tall, and just big enough to hold it.
+    ///
+    /// The glyphs are scaled up from the embedded font, nearest neighbor, so
+    /// each is `banner_glyph_width(pixel_height)` pixels wide. Characters and
+    /// lines are spaced `banner_spacing(pixel_height)` pixels apart, the same
+    /// proportion as `draw_text` uses. Each line of `text` is drawn below the
+    /// last, and the canvas is as wide as the longest one. Empty text gives
+    /// an empty canvas.
+    ///
+    /// Fails with `InvalidParameter` if `pixel_height` is 0, or with
+    /// `InvalidDimensions` if the banner is too big for a canvas.
+    pub fn create_banner(text: &str, pixel_height: u32) -> Result<Canvas, CanvasError> {
+        if pixel_height == 0 {
+            return Err(InvalidParameter { name: "pixel_height" });
+        }
+        let glyph_width = banner_glyph_width(pixel_height) as u64;
+        let spacing = banner_spacing(pixel_height) as u64;
+        let advance = glyph_width + spacing;
+
+        // Work the size out in u64, so huge banners fail in `create` rather
+        // than overflowing.
+        let size = |count: u64, each: u64| (count * (each + spacing)).saturating_sub(spacing);
+        let longest = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let width = size(longest as u64, glyph_width);
+        let height = size(text.lines().count() as u64, pixel_height as u64);
+        let clamp = |size: u64| size.min(u32::MAX as u64) as u32;
+        let mut canvas = Canvas::create(clamp(width), clamp(height))?;
+
+        for (i, line) in text.lines().enumerate() {
+            let top = i as u64 * (pixel_height as u64 + spacing);
+            for (j, c) in line.chars().enumerate() {
+                let left = j as u64 * advance;
+                let rows = glyph(c);
+                for y in 0..pixel_height as u64 {
+                    let bits = rows[(y * GLYPH_HEIGHT as u64 / pixel_height as u64) as usize];
+                    for x in 0..glyph_width {
+                        let col = x * GLYPH_WIDTH as u64 / glyph_width;
+                        if bits & (1 << (GLYPH_WIDTH as u64 - 1 - col)) != 0 {
+                            // Everything is on the canvas, which fits in an i32.
+                            canvas.draw_pixel((left + x) as i32, (top + y) as i32, 1);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(canvas)
+    }
+
+    /// Draw `text` as a caption along the bottom of the canvas.
+    ///
+    /// The caption lives in the bottom `CAPTION_HEIGHT` rows of the canvas,
+    /// which the caller is expected to have left free. That strip is cleared
+    /// before the text is drawn, and nothing outside of it is touched.
+    /// Text too long to fit is clipped at the right edge, not wrapped.
+    pub fn draw_caption(&mut self, text: &str) {
+        let strip = CAPTION_HEIGHT as i32;
+        let top = (self.height - strip).max(0);
+        for y in top..self.height {
+            for x in 0..self.width {
+                self.draw_pixel(x, y, 0);
+            }
+        }
+
+        // Center the text vertically in the strip, and indent it to line up
+        // with Schotter's padding.
+        let y = top + (strip - GLYPH_HEIGHT as i32) / 2;
+        let x = if self.width > 4 { 2 } else { 0 };
+        self.draw_text(x, y, text, 1);
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn set_pixels(canvas: &Canvas) -> Vec<(i32, i32)> {
+        let mut set = vec![];
+        for y in 0..canvas.height {
+            for x in 0..canvas.width {
+                if canvas.get_pixel(x, y) != 0 {
+                    set.push((x, y));
+                }
+            }
+        }
+        set
+    }
+
+    #[test]
+    fn check_glyphs() {
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_eq!(glyph('\u{2800}'), glyph('?'));
+        assert_eq!(glyph(' '), &[0; 5]);
+        for c in (b'!'..=b'~').map(char::from) {
+            assert!(glyph(c).iter().any(|&row| row != 0), "{:?}", c);
+            assert!(glyph(c).iter().all(|&row| row < 8), "{:?}", c);
+        }
+
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("A"), 3);
+        assert_eq!(text_width("AB"), 7);
+    }
+
+    #[test]
+    fn check_banner() {
+        // At the font's own height, it's the same as `draw_text`.
+        let banner = Canvas::create_banner("Hi!", GLYPH_HEIGHT).unwrap();
+        assert_eq!((banner.width() as u32, banner.height() as u32),
+                   (text_width("Hi!"), GLYPH_HEIGHT));
+        let mut text = Canvas::create(text_width("Hi!"), GLYPH_HEIGHT).unwrap();
+        text.draw_text(0, 0, "Hi!", 1);
+        assert_eq!(set_pixels(&banner), set_pixels(&text));
+
+        // Twice as tall is twice as wide, so every pixel becomes four.
+        let double = Canvas::create_banner("Hi!", 2 * GLYPH_HEIGHT).unwrap();
+        assert_eq!((double.width(), double.height()), (2 * banner.width(), 2 * banner.height()));
+        assert_eq!(double.pixel_histogram()[1], 4 * banner.pixel_histogram()[1]);
+        // The top of the H, `# #`, is now `##  ##`.
+        let top: Vec<u8> = (0..6).map(|x| double.get_pixel(x, 1)).collect();
+        assert_eq!(top, [1, 1, 0, 0, 1, 1]);
+
+        // The widest line sets the width, and lines are spaced like
+        // characters.
+        for &height in [1, 4, 7, 12, 40].iter() {
+            let (w, gap) = (banner_glyph_width(height) as i32, banner_spacing(height) as i32);
+            let banner = Canvas::create_banner("LOLWUT\nHI\n\nA", height).unwrap();
+            assert_eq!(banner.width(), 6 * (w + gap) - gap, "{}", height);
+            assert_eq!(banner.height(), 4 * (height as i32 + gap) - gap, "{}", height);
+        }
+        assert_eq!((banner_glyph_width(1), banner_spacing(1)), (1, 1));
+        assert_eq!((banner_glyph_width(12), banner_spacing(12)), (7, 2));
+
+        let empty = Canvas::create_banner("", 20).unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert!(Canvas::create_banner("A", 0).is_err());
+        match Canvas::create_banner("AB", u32::MAX) {
+            Err(InvalidDimensions { .. }) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+
+    #[test]
+    fn check_caption_stays_in_strip() {
+        let mut canvas = Canvas::create(200, 40).unwrap();
+        canvas.fill();
+        canvas.draw_caption(crate::ATTRIBUTION);
+
+        let top = 40 - CAPTION_HEIGHT as i32;
+        for y in 0..top {
+            for x in 0..200 {
+                assert_eq!(canvas.get_pixel(x, y), 1);
+            }
+        }
+        let caption = set_pixels(&canvas).into_iter()
+                                         .filter(|&(_, y)| y >= top)
+                                         .count();
+        assert!(caption > 0);
+    }
+
+    #[test]
+    fn check_caption_is_clipped() {
+        let text = "A caption much too long for this tiny canvas";
+        let mut narrow = Canvas::create(20, CAPTION_HEIGHT).unwrap();
+        narrow.draw_caption(text);
+
+        // Clipping means the narrow canvas looks exactly like the left edge of
+        // a wide one. Wrapping would have put text on other rows.
+        let mut wide = Canvas::create(1000, CAPTION_HEIGHT).unwrap();
+        wide.draw_caption(text);
+        let expected: Vec<_> = set_pixels(&wide).into_iter()
+                                                .filter(|&(x, _)| x < 20)
+                                                .collect();
+        assert_eq!(set_pixels(&narrow), expected);
+        assert!(set_pixels(&narrow).iter().any(|&(x, _)| x >= 18));
+    }
+}
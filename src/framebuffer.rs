@@ -0,0 +1,264 @@
+//! Drawing canvases straight onto a Linux framebuffer device, like
+//! `/dev/fb0`, with no terminal or display server in the way.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::os::raw::{c_char, c_ulong};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use crate::Canvas;
+
+// From <linux/fb.h>.
+const FBIOGET_VSCREENINFO: c_ulong = 0x4600;
+const FBIOGET_FSCREENINFO: c_ulong = 0x4602;
+
+#[repr(C)]
+#[derive(Default, Copy, Clone)]
+struct FbBitfield {
+    offset:    u32,
+    length:    u32,
+    msb_right: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct FbVarScreeninfo {
+    xres:           u32,
+    yres:           u32,
+    xres_virtual:   u32,
+    yres_virtual:   u32,
+    xoffset:        u32,
+    yoffset:        u32,
+    bits_per_pixel: u32,
+    grayscale:      u32,
+    red:            FbBitfield,
+    green:          FbBitfield,
+    blue:           FbBitfield,
+    transp:         FbBitfield,
+    nonstd:         u32,
+    activate:       u32,
+    height:         u32,
+    width:          u32,
+    accel_flags:    u32,
+    pixclock:       u32,
+    left_margin:    u32,
+    right_margin:   u32,
+    upper_margin:   u32,
+    lower_margin:   u32,
+    hsync_len:      u32,
+    vsync_len:      u32,
+    sync:           u32,
+    vmode:          u32,
+    rotate:         u32,
+    colorspace:     u32,
+    reserved:       [u32; 4],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct FbFixScreeninfo {
+    id:           [c_char; 16],
+    smem_start:   c_ulong,
+    smem_len:     u32,
+    type_:        u32,
+    type_aux:     u32,
+    visual:       u32,
+    xpanstep:     u16,
+    ypanstep:     u16,
+    ywrapstep:    u16,
+    line_length:  u32,
+    mmio_start:   c_ulong,
+    mmio_len:     u32,
+    accel:        u32,
+    capabilities: u16,
+    reserved:     [u16; 2],
+}
+
+/// How one pixel is laid out in the framebuffer's memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct PixelFormat {
+    bytes_per_pixel: usize,
+    /// The value of a white pixel. Black is all zeros, except for any alpha
+    /// bits, which are set in both so neither is see-through.
+    white: u32,
+    alpha: u32,
+}
+
+impl PixelFormat {
+    fn from_var(var: &FbVarScreeninfo) -> io::Result<PixelFormat> {
+        let bits = var.bits_per_pixel;
+        if ![8, 16, 24, 32].contains(&bits) {
+            return Err(unsupported(&format!("{} bits per pixel", bits)));
+        }
+        let mask = |field: FbBitfield| {
+            let ones = if field.length >= 32 { u32::MAX } else { (1 << field.length) - 1 };
+            ones.checked_shl(field.offset).unwrap_or(0)
+        };
+
+        let white = if var.grayscale == 1 || bits == 8 && var.red.length == 0 {
+            // Greyscale, or a palette, whose last entry is white by
+            // convention.
+            if bits == 32 { u32::MAX } else { (1 << bits) - 1 }
+        } else {
+            mask(var.red) | mask(var.green) | mask(var.blue)
+        };
+        if white == 0 {
+            return Err(unsupported("a pixel format without red, green or blue"));
+        }
+        Ok(PixelFormat {
+            bytes_per_pixel: bits as usize / 8,
+            white,
+            alpha: mask(var.transp),
+        })
+    }
+
+    /// The bytes of one pixel, in the machine's byte order, as the kernel
+    /// lays them out.
+    fn encode(&self, on: bool) -> Vec<u8> {
+        let value = self.alpha | if on { self.white } else { 0 };
+        let bytes = value.to_ne_bytes();
+        if cfg!(target_endian = "little") {
+            bytes[..self.bytes_per_pixel].to_vec()
+        } else {
+            bytes[4 - self.bytes_per_pixel..].to_vec()
+        }
+    }
+}
+
+fn unsupported(what: &str) -> io::Error {
+    io::Error::other(format!("unsupported framebuffer: {}", what))
+}
+
+impl Canvas {
+    /// Draw the canvas onto the Linux framebuffer device at `fb_path`, such as
+    /// `/dev/fb0`, with each pixel blown up to a `pixel_scale` by
+    /// `pixel_scale` square. On pixels are white, and off pixels are black.
+    ///
+    /// The canvas goes in the top-left corner of the visible screen, and
+    /// whatever doesn't fit is cut off. The pixel format is read from the
+    /// device, and anything with 8, 16, 24 or 32 bits per pixel works.
+    ///
+    /// Fails if the device can't be opened, if it isn't a framebuffer, or if
+    /// `pixel_scale` is 0.
+    pub fn write_frame_buffer(&self, fb_path: &Path, pixel_scale: u32) -> io::Result<()> {
+        if pixel_scale == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "pixel_scale must be at least 1"));
+        }
+        let fb = OpenOptions::new().read(true).write(true).open(fb_path)?;
+
+        let mut var = FbVarScreeninfo::default();
+        let mut fix = FbFixScreeninfo::default();
+        // Both only fill in the struct they're given, which is sized to match
+        // the kernel's.
+        unsafe {
+            if libc::ioctl(fb.as_raw_fd(), FBIOGET_VSCREENINFO as _, &mut var) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::ioctl(fb.as_raw_fd(), FBIOGET_FSCREENINFO as _, &mut fix) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        let format = PixelFormat::from_var(&var)?;
+
+        let rows = self.frame_rows(&format, pixel_scale, var.xres as usize, var.yres as usize);
+        let first_byte = var.yoffset as u64 * fix.line_length as u64
+                       + var.xoffset as u64 * format.bytes_per_pixel as u64;
+        for (y, row) in rows.iter().enumerate() {
+            fb.write_all_at(row, first_byte + y as u64 * fix.line_length as u64)?;
+        }
+        Ok(())
+    }
+
+    /// The canvas scaled up by `scale` and encoded in `format`, cut off at
+    /// `max_width` by `max_height` pixels, one `Vec` to each row of pixels.
+    fn frame_rows(&self, format: &PixelFormat, scale: u32, max_width: usize, max_height: usize)
+        -> Vec<Vec<u8>>
+    {
+        let scale = scale as usize;
+        let (on, off) = (format.encode(true), format.encode(false));
+        let width = (self.width as usize * scale).min(max_width);
+        let height = (self.height as usize * scale).min(max_height);
+
+        (0..height).map(|y| {
+            let mut row = Vec::with_capacity(width * format.bytes_per_pixel);
+            for x in 0..width {
+                let px = self.get_pixel((x / scale) as i32, (y / scale) as i32);
+                row.extend_from_slice(if px != 0 { &on } else { &off });
+            }
+            row
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    fn var(bits: u32, fields: [(u32, u32); 4]) -> FbVarScreeninfo {
+        let field = |(offset, length)| FbBitfield { offset, length, msb_right: 0 };
+        FbVarScreeninfo {
+            bits_per_pixel: bits,
+            red:    field(fields[0]),
+            green:  field(fields[1]),
+            blue:   field(fields[2]),
+            transp: field(fields[3]),
+            ..FbVarScreeninfo::default()
+        }
+    }
+
+    #[test]
+    fn check_pixel_formats() {
+        // The kernel's structs, byte for byte.
+        assert_eq!(std::mem::size_of::<FbVarScreeninfo>(), 160);
+        assert_eq!(std::mem::size_of::<FbFixScreeninfo>(),
+                   if cfg!(target_pointer_width = "64") { 80 } else { 68 });
+
+        let argb = PixelFormat::from_var(&var(32, [(16, 8), (8, 8), (0, 8), (24, 8)])).unwrap();
+        assert_eq!(argb.encode(true), 0xffff_ffffu32.to_ne_bytes());
+        assert_eq!(argb.encode(false), 0xff00_0000u32.to_ne_bytes());
+
+        let rgb565 = PixelFormat::from_var(&var(16, [(11, 5), (5, 6), (0, 5), (0, 0)])).unwrap();
+        assert_eq!(rgb565.encode(true).len(), 2);
+        assert_eq!(rgb565.encode(false), [0, 0]);
+
+        let grey = PixelFormat::from_var(&var(8, [(0, 0); 4])).unwrap();
+        assert_eq!((grey.encode(true), grey.encode(false)), (vec![0xff], vec![0]));
+
+        assert!(PixelFormat::from_var(&var(12, [(0, 4), (4, 4), (8, 4), (0, 0)])).is_err());
+        assert!(PixelFormat::from_var(&var(32, [(0, 0); 4])).is_err());
+    }
+
+    #[test]
+    fn check_frame_rows() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(2, 1, 1);
+        let format = PixelFormat { bytes_per_pixel: 1, white: 0xff, alpha: 0 };
+
+        let rows = canvas.frame_rows(&format, 2, 100, 100);
+        assert_eq!(rows, [
+            [0xff, 0xff, 0, 0, 0, 0],
+            [0xff, 0xff, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0xff, 0xff],
+            [0, 0, 0, 0, 0xff, 0xff],
+        ]);
+
+        // Cut off at the edges of the screen.
+        assert_eq!(canvas.frame_rows(&format, 2, 3, 1), [[0xff, 0xff, 0]]);
+    }
+
+    #[test]
+    fn check_not_a_framebuffer() {
+        let canvas = Canvas::create(2, 2).unwrap();
+        let path = std::env::temp_dir().join(format!("lolwut-fb-{}", std::process::id()));
+        std::fs::write(&path, [0; 16]).unwrap();
+        let result = canvas.write_frame_buffer(&path, 1);
+        let zero_scale = canvas.write_frame_buffer(&path, 0);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(zero_scale.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,230 @@
+//! Turning grayscale images into canvases.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{Canvas, CanvasError, CanvasError::*};
+
+/// How to decide which pixels of a grayscale image are on.
+///
+/// Either way, pixels darker than the threshold are on, like ink.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Halftone {
+    /// Each pixel is on if it's darker than the threshold, and off otherwise.
+    /// Smooth gradients come out as hard edges.
+    Threshold(u8),
+    /// Floyd-Steinberg error diffusion around the threshold. Each pixel's
+    /// rounding error is spread over its unvisited neighbors, so shades of
+    /// gray come out as denser or sparser dots.
+    Dither(u8),
+}
+
+/// A grayscale image, stored row by row with 0 for black and 255 for white,
+/// that can be adjusted before it's halftoned into a `Canvas`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreyscaleCanvas {
+    luma: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl GreyscaleCanvas {
+    /// Wrap a grayscale image, `width` by `height` pixels, stored in `luma`
+    /// like for `Canvas::from_grayscale`.
+    pub fn new(width: u32, height: u32, luma: Vec<u8>) -> Result<GreyscaleCanvas, CanvasError> {
+        if luma.len() as u64 != width as u64 * height as u64 {
+            return Err(InvalidParameter { name: "luma" });
+        }
+        Ok(GreyscaleCanvas { luma, width, height })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The pixels, row by row.
+    pub fn luma(&self) -> &[u8] {
+        &self.luma
+    }
+
+    /// Raise each pixel, as a fraction of white, to the power of `1 / gamma`.
+    ///
+    /// A `gamma` above 1 lightens the midtones and below 1 darkens them,
+    /// while black and white stay put. That decides which of the grays along
+    /// anti-aliased edges end up darker than a halftoning threshold. Nothing
+    /// changes unless `gamma` is positive.
+    pub fn gamma_correct(&mut self, gamma: f32) {
+        if gamma.is_nan() || gamma <= 0.0 {
+            return;
+        }
+
+        // There are only 256 levels, so work each one out once.
+        let mut table = [0; 256];
+        for (level, corrected) in table.iter_mut().enumerate() {
+            let fraction = (level as f32 / 255.0).powf(1.0 / gamma);
+            *corrected = (fraction * 255.0).round() as u8;
+        }
+        for l in &mut self.luma {
+            *l = table[*l as usize];
+        }
+    }
+
+    /// Halftone the image into a `Canvas`, like `Canvas::from_grayscale`.
+    pub fn to_canvas(&self, halftone: Halftone) -> Result<Canvas, CanvasError> {
+        Canvas::from_grayscale(self.width, self.height, &self.luma, halftone)
+    }
+}
+
+impl Canvas {
+    /// Create a Canvas from a grayscale image, `width` by `height` pixels,
+    /// stored row by row in `luma` with 0 for black and 255 for white.
+    pub fn from_grayscale(width: u32, height: u32, luma: &[u8], halftone: Halftone)
+        -> Result<Canvas, CanvasError>
+    {
+        if luma.len() as u64 != width as u64 * height as u64 {
+            return Err(InvalidParameter { name: "luma" });
+        }
+
+        match halftone {
+            Halftone::Threshold(threshold) => {
+                let stride = width as usize;
+                Canvas::from_fn(width, height, |x, y| {
+                    (luma[y as usize * stride + x as usize] < threshold) as u8
+                })
+            }
+            Halftone::Dither(threshold) => {
+                let mut canvas = Canvas::create(width, height)?;
+                let (width, height) = (width as usize, height as usize);
+                let mut levels: Vec<f32> = luma.iter().map(|&l| l as f32).collect();
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let i = y * width + x;
+                        let on = levels[i] < threshold as f32;
+                        let error = levels[i] - if on { 0.0 } else { 255.0 };
+                        canvas.set_pixel_at(i, on);
+
+                        let mut spread = |dx: isize, dy: usize, weight: f32| {
+                            let nx = x as isize + dx;
+                            if nx >= 0 && (nx as usize) < width && y + dy < height {
+                                levels[(y + dy) * width + nx as usize] += error * weight;
+                            }
+                        };
+                        spread(1, 0, 7.0 / 16.0);
+                        spread(-1, 1, 3.0 / 16.0);
+                        spread(0, 1, 5.0 / 16.0);
+                        spread(1, 1, 1.0 / 16.0);
+                    }
+                }
+
+                Ok(canvas)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    /// A left-to-right gradient from black to white.
+    fn gradient(width: u32, height: u32) -> Vec<u8> {
+        (0..height).flat_map(|_| (0..width).map(move |x| (x * 255 / (width - 1)) as u8))
+                   .collect()
+    }
+
+    #[test]
+    fn check_threshold() {
+        let canvas = Canvas::from_grayscale(8, 2, &gradient(8, 2), Halftone::Threshold(128))
+                            .unwrap();
+        assert_eq!(canvas.render_ascii(), "####    \n####    \n");
+
+        let none = Canvas::from_grayscale(8, 2, &gradient(8, 2), Halftone::Threshold(0)).unwrap();
+        assert!(none.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_dither() {
+        let (width, height) = (64, 32);
+        let canvas = Canvas::from_grayscale(width, height, &gradient(width, height),
+                                            Halftone::Dither(128))
+                            .unwrap();
+
+        // Going from black to white, each band of columns is lighter than
+        // the one before.
+        let band_density = |band: i32| {
+            let on = (0..height as i32)
+                .flat_map(|y| (band * 8..band * 8 + 8).map(move |x| (x, y)))
+                .filter(|&(x, y)| canvas.get_pixel(x, y) != 0)
+                .count();
+            on as f32 / (8 * height) as f32
+        };
+        let densities: Vec<f32> = (0..8).map(band_density).collect();
+        assert!(densities.windows(2).all(|w| w[0] > w[1]), "{:?}", densities);
+        assert!(densities[0] > 0.85 && densities[7] < 0.15, "{:?}", densities);
+
+        // Mid-gray comes out as about half on.
+        let gray = vec![128; 32 * 32];
+        let canvas = Canvas::from_grayscale(32, 32, &gray, Halftone::Dither(128)).unwrap();
+        let density = canvas.render_statistics().density;
+        assert!((density - 0.5).abs() < 0.05, "{}", density);
+    }
+
+    #[test]
+    fn check_gamma_correct() {
+        let mut image = GreyscaleCanvas::new(8, 2, gradient(8, 2)).unwrap();
+        image.gamma_correct(1.0);
+        assert_eq!(image.luma(), &gradient(8, 2)[..]);
+
+        // Lightening keeps black black, and so fewer pixels pass the
+        // threshold.
+        image.gamma_correct(2.2);
+        assert_eq!(&image.luma()[..8], [0, 105, 144, 173, 197, 219, 237, 255]);
+        let canvas = image.to_canvas(Halftone::Threshold(128)).unwrap();
+        assert_eq!(canvas.render_ascii(), "##      \n##      \n");
+
+        // And darkening undoes it, give or take rounding.
+        image.gamma_correct(1.0 / 2.2);
+        for (&l, &original) in image.luma().iter().zip(gradient(8, 2).iter()) {
+            assert!((l as i32 - original as i32).abs() <= 1, "{} vs {}", l, original);
+        }
+
+        for &gamma in [0.0, -1.0, f32::NAN].iter() {
+            let before = image.clone();
+            image.gamma_correct(gamma);
+            assert_eq!(image, before);
+        }
+    }
+
+    #[test]
+    fn check_wrong_size() {
+        match GreyscaleCanvas::new(4, 4, vec![0; 15]) {
+            Err(InvalidParameter { name: "luma" }) => (),
+            other => panic!("Expected an error, got {:?}", other),
+        }
+
+        for &halftone in [Halftone::Threshold(128), Halftone::Dither(128)].iter() {
+            match Canvas::from_grayscale(4, 4, &[0; 15], halftone) {
+                Err(InvalidParameter { name: "luma" }) => (),
+                other => panic!("Expected an error, got {:?}", other.map(|c| c.pixels)),
+            }
+        }
+    }
+
+    #[test]
+    fn check_empty() {
+        for &halftone in [Halftone::Threshold(128), Halftone::Dither(128)].iter() {
+            for &(width, height) in [(0, 5), (5, 0), (0, 0)].iter() {
+                let canvas = Canvas::from_grayscale(width, height, &[], halftone).unwrap();
+                assert_eq!((canvas.width(), canvas.height()), (width as i32, height as i32),
+                           "{:?}", halftone);
+                assert!(canvas.pixels.is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Loading image files into canvases, through the `image` crate.
+
+use std::{error, fmt, io};
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageError};
+
+use crate::{Canvas, CanvasError, CanvasError::*, Halftone};
+
+/// How `Canvas::from_image_path` turns an image into a canvas.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImportOptions {
+    /// The widest the canvas may be, in pixels. Wider images are shrunk to
+    /// fit, keeping their shape. Images are never enlarged.
+    pub max_width: Option<u32>,
+    /// The tallest the canvas may be, in pixels, like `max_width`.
+    pub max_height: Option<u32>,
+    /// How many times taller than wide each pixel will look when shown, so
+    /// the image can be squashed to make up for it.
+    ///
+    /// Braille packs 2x4 dots into a terminal cell that's about twice as tall
+    /// as it is wide, so its dots are about square, and 1 is right. For cells
+    /// of other shapes, this is the cell's height over twice its width.
+    pub pixel_aspect: f32,
+    /// How gray pixels become on or off. Dark pixels are on.
+    pub halftone: Halftone,
+}
+
+impl Default for ImportOptions {
+    /// Keep the image's size, and threshold at the middle gray.
+    fn default() -> ImportOptions {
+        ImportOptions {
+            max_width:    None,
+            max_height:   None,
+            pixel_aspect: 1.0,
+            halftone:     Halftone::Threshold(128),
+        }
+    }
+}
+
+/// An error loading an image into a canvas.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file was read, but isn't an image the `image` crate understands.
+    Decode(ImageError),
+    /// The image can't be made into a canvas that size, or the options asked
+    /// for an impossible size.
+    Size(CanvasError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io(e)     => write!(f, "could not read the image: {}", e),
+            ImportError::Decode(e) => write!(f, "could not decode the image: {}", e),
+            ImportError::Size(e)   => write!(f, "could not size the image: {}", e),
+        }
+    }
+}
+
+impl error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ImportError::Io(e)     => Some(e),
+            ImportError::Decode(e) => Some(e),
+            ImportError::Size(e)   => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(e: io::Error) -> ImportError {
+        ImportError::Io(e)
+    }
+}
+
+impl From<ImageError> for ImportError {
+    fn from(e: ImageError) -> ImportError {
+        match e {
+            ImageError::IoError(e) => ImportError::Io(e),
+            e                      => ImportError::Decode(e),
+        }
+    }
+}
+
+impl From<CanvasError> for ImportError {
+    fn from(e: CanvasError) -> ImportError {
+        ImportError::Size(e)
+    }
+}
+
+impl Canvas {
+    /// Load the image file at `path`, in any format the `image` crate can
+    /// read, and halftone it into a canvas as `opts` says.
+    pub fn from_image_path(path: &Path, opts: ImportOptions) -> Result<Canvas, ImportError> {
+        let image = image::io::Reader::open(path)?.with_guessed_format()?.decode()?;
+        Ok(Canvas::from_image(&image, opts)?)
+    }
+
+    /// Halftone an image that's already been decoded into a canvas, like
+    /// `Canvas::from_image_path`.
+    pub fn from_image(image: &DynamicImage, opts: ImportOptions) -> Result<Canvas, CanvasError> {
+        let (width, height) = fit(image.width(), image.height(), &opts)?;
+        let mut luma = image.to_luma8();
+        if (width, height) != luma.dimensions() {
+            luma = image::imageops::resize(&luma, width, height, FilterType::Triangle);
+        }
+        Canvas::from_grayscale(width, height, luma.as_raw(), opts.halftone)
+    }
+}
+
+/// The size to shrink a `width` by `height` image to, as `opts` says.
+fn fit(width: u32, height: u32, opts: &ImportOptions) -> Result<(u32, u32), CanvasError> {
+    if !(opts.pixel_aspect > 0.0 && opts.pixel_aspect.is_finite()) {
+        return Err(InvalidParameter { name: "pixel_aspect" });
+    }
+    if opts.max_width == Some(0) {
+        return Err(InvalidParameter { name: "max_width" });
+    }
+    if opts.max_height == Some(0) {
+        return Err(InvalidParameter { name: "max_height" });
+    }
+
+    let (w, h) = (width as f64, height as f64 / opts.pixel_aspect as f64);
+    let limit = |max: Option<u32>, size: f64| max.map_or(1.0, |max| max as f64 / size);
+    let scale = limit(opts.max_width, w).min(limit(opts.max_height, h)).min(1.0);
+    // Round, but don't round anything away entirely.
+    let size = |size: f64, original: u32| {
+        if original == 0 { 0 } else { ((size * scale).round() as u32).max(1) }
+    };
+    Ok((size(w, width), size(h, height)))
+}
+
+#[cfg(test)]
+mod t {
+    use std::string::ToString;
+
+    use image::{GrayImage, Luma};
+
+    use super::*;
+
+    /// A gradient from black on the left to white on the right.
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, _| {
+            Luma([(x * 255 / (width - 1)) as u8])
+        }))
+    }
+
+    fn density(canvas: &Canvas, xs: std::ops::Range<i32>) -> usize {
+        xs.map(|x| (0..canvas.height()).filter(|&y| canvas.get_pixel(x, y) != 0).count()).sum()
+    }
+
+    #[test]
+    fn check_fit() {
+        let opts = |max_width, max_height, pixel_aspect| ImportOptions {
+            max_width,
+            max_height,
+            pixel_aspect,
+            ..ImportOptions::default()
+        };
+        assert_eq!(fit(200, 100, &opts(None, None, 1.0)).unwrap(), (200, 100));
+        assert_eq!(fit(200, 100, &opts(Some(80), None, 1.0)).unwrap(), (80, 40));
+        assert_eq!(fit(200, 100, &opts(Some(80), Some(10), 1.0)).unwrap(), (20, 10));
+        // Never enlarged, and never shrunk to nothing.
+        assert_eq!(fit(20, 10, &opts(Some(80), Some(80), 1.0)).unwrap(), (20, 10));
+        assert_eq!(fit(1000, 1, &opts(Some(10), None, 1.0)).unwrap(), (10, 1));
+        // Tall pixels need fewer rows.
+        assert_eq!(fit(200, 100, &opts(None, None, 2.0)).unwrap(), (200, 50));
+        assert_eq!(fit(200, 100, &opts(Some(100), None, 2.0)).unwrap(), (100, 25));
+
+        for &(bad, name) in [(opts(Some(0), None, 1.0), "max_width"),
+                             (opts(None, Some(0), 1.0), "max_height"),
+                             (opts(None, None, 0.0), "pixel_aspect"),
+                             (opts(None, None, f32::NAN), "pixel_aspect")].iter() {
+            match fit(10, 10, &bad) {
+                Err(InvalidParameter { name: n }) => assert_eq!(n, name),
+                other => panic!("Expected an error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn check_from_image() {
+        for &halftone in [Halftone::Threshold(128), Halftone::Dither(128)].iter() {
+            let opts = ImportOptions { max_width: Some(80), halftone, ..ImportOptions::default() };
+            let canvas = Canvas::from_image(&gradient(200, 100), opts).unwrap();
+            assert_eq!((canvas.width(), canvas.height()), (80, 40));
+
+            // Dark on the left, light on the right, and about half on.
+            let (left, right) = (density(&canvas, 0..20), density(&canvas, 60..80));
+            assert!(left > 700 && right < 100, "{:?}: {} {}", halftone, left, right);
+            let on = canvas.pixel_histogram()[1];
+            assert!((1400..1800).contains(&on), "{:?}: {}", halftone, on);
+        }
+    }
+
+    #[test]
+    fn check_from_image_path() {
+        let path = std::env::temp_dir().join(format!("lolwut-import-{}.png", std::process::id()));
+        gradient(40, 20).save(&path).unwrap();
+        let canvas = Canvas::from_image_path(&path, ImportOptions::default());
+        let not_an_image = std::fs::write(&path, b"not an image")
+            .map(|_| Canvas::from_image_path(&path, ImportOptions::default()));
+        std::fs::remove_file(&path).unwrap();
+
+        let canvas = canvas.unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (40, 20));
+        assert_eq!(canvas.get_pixel(0, 0), 1);
+        assert_eq!(canvas.get_pixel(39, 19), 0);
+
+        match not_an_image.unwrap() {
+            Err(ImportError::Decode(_)) => (),
+            other => panic!("Expected a decode error, got {:?}", other.map(|_| ())),
+        }
+        match Canvas::from_image_path(&path, ImportOptions::default()) {
+            Err(ImportError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("Expected an I/O error, got {:?}", other.map(|_| ())),
+        }
+
+        let err = ImportError::Size(InvalidParameter { name: "max_width" });
+        assert_eq!(err.to_string(),
+                   "could not size the image: invalid value for parameter `max_width`");
+    }
+}
@@ -0,0 +1,206 @@
+//! Tweaking Schotter live in the terminal.
+
+use std::{
+    error,
+    io::{self, Write},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    terminal::{self, ClearType},
+};
+use rand::prelude::*;
+
+use crate::{Canvas, CanvasError, JitterDistribution, SchotterConfig};
+
+/// How much `+` and `-` change the chaos by.
+const CHAOS_STEP: f32 = 0.1;
+
+/// Draw Schotter in the terminal and redraw it as keys are pressed, until `q`
+/// is.
+///
+/// The arrow keys change the number of squares: left and right in each row,
+/// and up and down in each column. `+` and `-` turn the chaos up and down,
+/// space draws from a new seed, and `q`, escape, or Ctrl-C quit.
+///
+/// Chaos is the standard deviation of the jitter, relative to the row's
+/// factor. Changing it switches uniform jitter to normal jitter with the same
+/// standard deviation, `1/√3`, first.
+pub fn run_interactive(initial_config: SchotterConfig)
+    -> Result<(), Box<dyn error::Error>>
+{
+    let mut state = State { config: initial_config, seed: random() };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    terminal::enable_raw_mode()?;
+    let _guard = RawModeGuard;
+    queue!(out, cursor::Hide)?;
+
+    let mut last_lines = 0;
+    loop {
+        let frame = state.frame()?;
+        // `MoveUp(0)` still moves up a line in most terminals.
+        if last_lines > 0 {
+            queue!(out, cursor::MoveUp(last_lines))?;
+        }
+        queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+        out.write_all(frame.as_bytes())?;
+        out.flush()?;
+        last_lines = frame.matches('\n').count() as u16;
+
+        let key = loop {
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => break key,
+                _ => {}
+            }
+        };
+        if !state.apply(key, &mut random) {
+            return Ok(());
+        }
+    }
+}
+
+/// Puts the terminal back the way it was when dropped, even when unwinding
+/// from a panic.
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = queue!(out, cursor::Show);
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// What the interactive session is showing.
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    config: SchotterConfig,
+    seed:   u64,
+}
+
+impl State {
+    /// Update for `key`, taking a new seed from `new_seed` when asked to.
+    /// Returns false when it's time to quit.
+    fn apply(&mut self, key: KeyEvent, new_seed: &mut dyn FnMut() -> u64) -> bool {
+        let config = &mut self.config;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Right => config.squares_per_row += 1,
+            KeyCode::Left  => config.squares_per_row = (config.squares_per_row - 1).max(1),
+            KeyCode::Down  => config.squares_per_col += 1,
+            KeyCode::Up    => config.squares_per_col = (config.squares_per_col - 1).max(1),
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                config.jitter = with_chaos(chaos(config.jitter) + CHAOS_STEP);
+            }
+            KeyCode::Char('-') | KeyCode::Char('_') => {
+                config.jitter = with_chaos((chaos(config.jitter) - CHAOS_STEP).max(0.0));
+            }
+            KeyCode::Char(' ') => self.seed = new_seed(),
+            _ => {}
+        }
+        true
+    }
+
+    /// The artwork, followed by a line describing it and the keys.
+    ///
+    /// Lines end with `\r\n`, since raw mode doesn't turn `\n` into that.
+    fn frame(&self) -> Result<String, CanvasError> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let canvas = Canvas::create_and_render_schotter_with(&self.config, &mut rng)?;
+
+        let mut frame = canvas.render().replace('\n', "\r\n");
+        frame += &format!("{}x{} squares, chaos {:.1}, seed {}  \
+                           arrows: squares  +/-: chaos  space: new seed  q: quit\r\n",
+                          self.config.squares_per_row,
+                          self.config.squares_per_col,
+                          chaos(self.config.jitter),
+                          self.seed);
+        Ok(frame)
+    }
+}
+
+/// The standard deviation of `jitter`, relative to each row's factor.
+fn chaos(jitter: JitterDistribution) -> f32 {
+    match jitter {
+        // Uniform in [-1, 1].
+        JitterDistribution::Uniform => 1.0 / 3f32.sqrt(),
+        JitterDistribution::Normal { sigma_scale } => sigma_scale,
+    }
+}
+
+fn with_chaos(chaos: f32) -> JitterDistribution {
+    JitterDistribution::Normal { sigma_scale: chaos }
+}
+
+#[cfg(test)]
+mod t {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::State;
+    use crate::{JitterDistribution, SchotterConfig};
+
+    fn press(state: &mut State, code: KeyCode) -> bool {
+        let mut seeds = 100..;
+        state.apply(KeyEvent::new(code, KeyModifiers::NONE), &mut || seeds.next().unwrap())
+    }
+
+    #[test]
+    fn check_keys() {
+        let start = State { config: SchotterConfig::new(20, 2, 1), seed: 9 };
+        let mut state = start.clone();
+
+        assert!(press(&mut state, KeyCode::Right));
+        assert!(press(&mut state, KeyCode::Down));
+        assert!(press(&mut state, KeyCode::Char('x')));
+        assert_eq!((state.config.squares_per_row, state.config.squares_per_col), (3, 2));
+        assert_eq!(state.seed, 9);
+
+        // There's always at least one square.
+        for _ in 0..5 {
+            press(&mut state, KeyCode::Left);
+            press(&mut state, KeyCode::Up);
+        }
+        assert_eq!((state.config.squares_per_row, state.config.squares_per_col), (1, 1));
+
+        press(&mut state, KeyCode::Char(' '));
+        assert_eq!(state.seed, 100);
+
+        // Chaos starts from uniform jitter's standard deviation, and stops
+        // at 0.
+        press(&mut state, KeyCode::Char('+'));
+        match state.config.jitter {
+            JitterDistribution::Normal { sigma_scale } => {
+                assert!((sigma_scale - 0.677).abs() < 1e-3, "{}", sigma_scale)
+            }
+            jitter => panic!("{:?}", jitter),
+        }
+        for _ in 0..10 {
+            press(&mut state, KeyCode::Char('-'));
+        }
+        assert_eq!(state.config.jitter, JitterDistribution::Normal { sigma_scale: 0.0 });
+
+        assert!(!press(&mut start.clone(), KeyCode::Char('q')));
+        assert!(!press(&mut start.clone(), KeyCode::Esc));
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(!start.clone().apply(ctrl_c, &mut || 0));
+    }
+
+    #[test]
+    fn check_frame() {
+        let state = State { config: SchotterConfig::new(20, 3, 5), seed: 9 };
+        let frame = state.frame().unwrap();
+        assert!(frame.ends_with("3x5 squares, chaos 0.6, seed 9  \
+                                 arrows: squares  +/-: chaos  space: new seed  q: quit\r\n"),
+                "{}", frame);
+        assert!(!frame.replace("\r\n", "").contains('\n'));
+
+        // Seeded, so the same state always draws the same frame.
+        assert_eq!(frame, state.frame().unwrap());
+    }
+}
@@ -6,29 +6,122 @@
 //! [here](https://github.com/antirez/redis/blob/91685eeeb/src/lolwut5.c).
 //!
 //! This implementation is mostly a port, and it does re-use code/comments.
+//!
+//! Without the default `std` feature, the library is `no_std` and only needs
+//! an allocator, so it can drive displays on microcontrollers. The functions
+//! that need `thread_rng`, the clock, or I/O aren't available then, and the
+//! `libm` feature has to be on to do the floating point math instead.
 
-use std::{
-    error,
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("lolwut needs either the `std` feature or, for `no_std`, the `libm` feature");
+
+extern crate alloc;
+// The tests run under `std` either way, and may use its macros.
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+use core::{
     fmt,
-    ptr,
+    ops::Range,
     str,
     f32::consts::PI,
+    f64::consts::SQRT_2,
+};
+#[cfg(feature = "std")]
+use std::{
+    error,
+    hint,
+    time::{Duration, Instant},
 };
 
 use rand::prelude::*;
 
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+
+mod ammann;
+#[cfg(feature = "std")]
+mod band;
+#[cfg(feature = "rayon")]
+mod batch;
+mod bits;
+mod bitops;
+mod collatz;
+mod color;
+mod curves;
+mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod font;
+#[cfg(all(feature = "framebuffer", target_os = "linux"))]
+mod framebuffer;
+mod halftone;
+#[cfg(feature = "image")]
+mod import;
+#[cfg(feature = "crossterm")]
+mod interactive;
+mod lsystem;
+#[cfg(not(feature = "std"))]
+mod math;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod params;
+mod patterns;
+mod penrose;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "redis-module")]
+pub mod redis;
+#[cfg(feature = "std")]
+pub mod term;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "ratatui")]
+mod widget;
+
+use crate::font::CAPTION_HEIGHT;
+
+#[cfg(feature = "std")]
+pub use crate::band::BandRenderer;
+#[cfg(feature = "rayon")]
+pub use crate::batch::draw_schotter_batch;
+pub use crate::color::{Color, ParseColorError};
+pub use crate::curves::PathCommand;
+pub use crate::halftone::{GreyscaleCanvas, Halftone};
+#[cfg(feature = "image")]
+pub use crate::import::{ImportError, ImportOptions};
+#[cfg(feature = "crossterm")]
+pub use crate::interactive::run_interactive;
+pub use crate::params::{ParseParamsError, SchotterParams};
+#[cfg(feature = "wasm")]
+pub use crate::wasm::WasmCanvas;
+#[cfg(feature = "ratatui")]
+pub use crate::widget::CanvasWidget;
+
+/// The attribution that belongs with every rendering of Schotter.
+pub const ATTRIBUTION: &str = "Georg Nees - schotter, plotter on paper, 1968";
+
 /// The Canvas represents the area that's drawn in. Each pixel is either:
 ///     1 - and "on"
 ///     0 - and "off"
 /// Other values will silently turn into 1.
+#[derive(Clone)]
 pub struct Canvas {
-    pixels: Vec<u8>,
+    /// One bit per pixel, row by row, packed as `bits` describes.
+    pixels: Vec<u64>,
     width:  i32,
     height: i32,
 }
 
 /// An error related to the Canvas creation.
+///
+/// More variants may be added, so matches need a wildcard arm.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum CanvasError {
     /// The provided buffer does not have enough bytes to be used as the
     /// backing memory for a canvas.
@@ -44,16 +137,67 @@ pub enum CanvasError {
         actual_width: i32,
         needed_height: i32,
         actual_height: i32,
-    }
+    },
+
+    /// A canvas can't be made this size: a side is more than `i32::MAX`, or
+    /// there are more than `u32::MAX` pixels in all.
+    InvalidDimensions {
+        width: u32,
+        height: u32,
+    },
+
+    /// A pixel was asked for that isn't on the canvas.
+    OutOfBounds {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    },
+
+    /// Two canvases needed to be the same size, but weren't.
+    SizeMismatch {
+        width: i32,
+        height: i32,
+        other_width: i32,
+        other_height: i32,
+    },
+
+    /// A parameter was outside of the range it's allowed to take,
+    /// e.g. a non-positive number of columns. `name` is the parameter's.
+    InvalidParameter {
+        name: &'static str,
+    },
 }
 use self::CanvasError::*;
 
 impl fmt::Display for CanvasError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match *self {
+            PixelBufferTooSmall { needed, actual } => {
+                write!(f, "pixel buffer has {} bytes but needs at least {}", actual, needed)
+            }
+            CanvasTooSmall { needed_width, actual_width, needed_height, actual_height } => {
+                write!(f, "canvas is {}x{} but needs to be at least {}x{}",
+                       actual_width, actual_height, needed_width, needed_height)
+            }
+            InvalidDimensions { width, height } => {
+                write!(f, "a {}x{} canvas is too large to create", width, height)
+            }
+            OutOfBounds { x, y, width, height } => {
+                write!(f, "pixel ({}, {}) is outside of the {}x{} canvas", x, y, width, height)
+            }
+            SizeMismatch { width, height, other_width, other_height } => {
+                write!(f, "canvases are {}x{} and {}x{}, but need to be the same size",
+                       width, height, other_width, other_height)
+            }
+            InvalidParameter { name } => {
+                write!(f, "invalid value for parameter `{}`", name)
+            }
+        }
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for CanvasError {}
 
 impl Canvas {
@@ -62,62 +206,324 @@ impl Canvas {
     pub fn create(width: u32, height: u32)
         -> Result<Canvas, CanvasError>
     {
-        let px_count = width * height;
+        let px_count = match width.checked_mul(height) {
+            Some(count) if width <= i32::MAX as u32 && height <= i32::MAX as u32 => count,
+            _ => return Err(InvalidDimensions { width, height }),
+        };
 
         Ok(Canvas {
-            pixels: vec![0; px_count as usize],
+            pixels: vec![0; bits::words_for(px_count as usize)],
             width:  width as i32,
             height: height as i32,
         })
     }
 
+    /// Create a Canvas of the specified size, with each pixel set to the
+    /// color `f(x, y)`.
+    pub fn from_fn<F>(width: u32, height: u32, f: F) -> Result<Canvas, CanvasError>
+        where F: FnMut(i32, i32) -> u8
+    {
+        let mut canvas = Canvas::create(width, height)?;
+        canvas.fill_with(f);
+        Ok(canvas)
+    }
+
+    /// Create a Canvas of the specified size, with the pixel at each of
+    /// `points` set. Points that aren't on the canvas are ignored.
+    pub fn from_points<I>(width: u32, height: u32, points: I) -> Result<Canvas, CanvasError>
+        where I: IntoIterator<Item = (i32, i32)>
+    {
+        let mut canvas = Canvas::create(width, height)?;
+        for (x, y) in points {
+            canvas.draw_pixel(x, y, 1);
+        }
+        Ok(canvas)
+    }
+
+    /// Create a Canvas just big enough for all of `points`, with `margin`
+    /// empty pixels on every side, and set the pixel at each of them.
+    ///
+    /// The points are moved so that the top-left corner of their bounding box
+    /// lands at `(margin, margin)`, so negative coordinates work too. With no
+    /// points at all, the canvas is 0x0. Fails with `InvalidDimensions` if the
+    /// points are too spread out to fit on a canvas.
+    pub fn from_points_auto_sized<I>(points: I, margin: u32) -> Result<Canvas, CanvasError>
+        where I: IntoIterator<Item = (i32, i32)>
+    {
+        let points: Vec<(i32, i32)> = points.into_iter().collect();
+        let (min_x, max_x, min_y, max_y) = match points.first() {
+            Some(&(x, y)) => points.iter().fold((x, x, y, y), |(x0, x1, y0, y1), &(x, y)| {
+                (x0.min(x), x1.max(x), y0.min(y), y1.max(y))
+            }),
+            None => return Canvas::create(0, 0),
+        };
+
+        // The spans can reach 2^32 + 2 * margin, so work in u64.
+        let size = |min: i32, max: i32| {
+            let size = (max as i64 - min as i64 + 1) as u64 + 2 * margin as u64;
+            size.min(u32::MAX as u64) as u32
+        };
+        let mut canvas = Canvas::create(size(min_x, max_x), size(min_y, max_y))?;
+        // Now that it fits on a canvas, every moved point fits in an i32.
+        let (dx, dy) = (margin as i64 - min_x as i64, margin as i64 - min_y as i64);
+        for (x, y) in points {
+            canvas.draw_pixel((x as i64 + dx) as i32, (y as i64 + dy) as i32, 1);
+        }
+        Ok(canvas)
+    }
+
+    /// Set every pixel to the color `f(x, y)`, row by row.
+    fn fill_with<F>(&mut self, mut f: F)
+        where F: FnMut(i32, i32) -> u8
+    {
+        let width = self.width.max(1) as usize;
+        for i in 0..self.pixel_count() {
+            let on = f((i % width) as i32, (i / width) as i32) != 0;
+            self.set_pixel_at(i, on);
+        }
+    }
+
+    /// Width of the canvas in pixels
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Height of the canvas in pixels
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Number of pixels in the canvas, `width * height`
+    pub fn pixel_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Whether pixel `i`, counting row by row, is on.
+    fn pixel_at(&self, i: usize) -> bool {
+        bits::get(&self.pixels, i)
+    }
+
+    fn set_pixel_at(&mut self, i: usize, on: bool) {
+        bits::set(&mut self.pixels, i, on);
+    }
+
+    /// Clear the bits after the last pixel, after changing whole words.
+    fn clear_padding(&mut self) {
+        let len = self.pixel_count();
+        bits::clear_past(&mut self.pixels, len);
+    }
+
+    /// The pixels row by row, one byte each: 1 for on and 0 for off.
+    #[cfg(any(feature = "python", feature = "wasm", test))]
+    fn pixel_bytes(&self) -> Vec<u8> {
+        (0..self.pixel_count()).map(|i| self.pixel_at(i) as u8).collect()
+    }
+
+    /// Check that the canvas is internally consistent: its width and height
+    /// aren't negative, and there are exactly enough words of pixels for its
+    /// size.
+    ///
+    /// Every safe method keeps these true, so this is for catching code that
+    /// builds or patches canvases by hand. The error names whichever field is
+    /// out of line.
+    pub fn validate(&self) -> Result<(), CanvasError> {
+        if self.width < 0 {
+            return Err(InvalidParameter { name: "width" });
+        }
+        if self.height < 0 {
+            return Err(InvalidParameter { name: "height" });
+        }
+        let needed = bits::words_for(self.width as usize * self.height as usize);
+        if self.pixels.len() != needed {
+            return Err(InvalidParameter { name: "pixels" });
+        }
+        Ok(())
+    }
+
+    /// Like `validate`, but also require the unused bits after the last pixel
+    /// to be clear, which counting and comparing pixels a word at a time
+    /// relies on.
+    pub fn validate_strict(&self) -> Result<(), CanvasError> {
+        self.validate()?;
+        let len = self.pixel_count();
+        if bits::count(&self.pixels, len..self.pixels.len() * bits::WORD_BITS) != 0 {
+            return Err(InvalidParameter { name: "pixels" });
+        }
+        Ok(())
+    }
+
     /// Create a Canvas large enough and render Schotter onto it
+    #[cfg(feature = "std")]
     pub fn create_and_render_schotter(
         console_cols: i32,
         squares_per_row: i32,
         squares_per_col: i32
     ) -> Result<Canvas, CanvasError>
     {
-        let needed_width:  i32 = 2 * console_cols;
-        let padding:       f32 = if needed_width > 4 { 2.0 } else { 0.0 };
-        let square_side:   f32 = (needed_width as f32 - 2.0 * padding)
-                                   / squares_per_row as f32;
-        let needed_height: i32 = (square_side * squares_per_col as f32
-                                   + 2.0 * padding).round() as i32;
+        let layout = SchotterLayout::compute(console_cols,
+                                             squares_per_row,
+                                             squares_per_col)?;
+        layout.trace();
 
-        let mut canvas = Canvas::create(needed_width as u32, needed_height as u32)?;
+        let mut canvas = Canvas::create(layout.needed_width as u32, layout.needed_height as u32)?;
         canvas.draw_schotter(console_cols, squares_per_row, squares_per_col)?;
 
         Ok(canvas)
     }
 
-    // We want `clear()` and `fill()` to be dumb `memcpy()`s. Rust doesn't expose
-    // a safe wrapper around memcpy yet, so we write the bytes directly.
-    // This is unsafe in the general case - writing an arbitrary byte to
-    // arbitrary types can go horribly wrong.
-    // We are writing 0 and 1 into a fixed size buffer with known bounds, so
-    // this is safe.
+    /// Create a Canvas large enough for `config` and render Schotter onto it,
+    /// taking all randomness from `rng`.
+    ///
+    /// If `config.with_caption` is set, the canvas is made `CAPTION_HEIGHT`
+    /// pixels taller and the attribution is drawn into that space.
+    pub fn create_and_render_schotter_with<R: Rng + ?Sized>(
+        config: &SchotterConfig,
+        rng: &mut R
+    ) -> Result<Canvas, CanvasError>
+    {
+        let layout = SchotterLayout::compute(config.console_cols,
+                                             config.squares_per_row,
+                                             config.squares_per_col)?;
+        layout.trace();
+
+        let (width, mut height) = (layout.needed_width as u32, layout.needed_height as u32);
+        if config.with_caption {
+            height += CAPTION_HEIGHT;
+        }
+
+        let mut canvas = Canvas::create(width, height)?;
+        canvas.draw_schotter_with(config, rng)?;
+        if config.with_caption {
+            canvas.draw_caption(ATTRIBUTION);
+        }
+
+        Ok(canvas)
+    }
+
+    /// Render Schotter split into `layers` separate canvases by chaos level.
+    ///
+    /// The rows of squares are divided into `layers` evenly sized bands, and
+    /// each canvas holds only the squares of one band. The first canvas is the
+    /// calmest, and the last is the most chaotic. Overlaying all of them gives
+    /// the same image as a single Schotter render with the same `seed`, so
+    /// drawing each layer in a different color shows how the chaos ramps up.
+    pub fn draw_schotter_layers(console_cols: i32,
+                                squares_per_row: i32,
+                                squares_per_col: i32,
+                                layers: u32,
+                                seed: u64)
+        -> Result<Vec<Canvas>, CanvasError>
+    {
+        if layers == 0 {
+            return Err(InvalidParameter { name: "layers" });
+        }
+
+        let config = SchotterConfig::new(console_cols,
+                                         squares_per_row,
+                                         squares_per_col);
+        let (width, height) = schotter_dimensions(console_cols,
+                                                  squares_per_row,
+                                                  squares_per_col)?;
+
+        // Every layer draws from the same stream of random numbers, which is
+        // what keeps the union of the layers identical to a single render.
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rows = squares_per_col as i64;
+        let layers = layers as i64;
+        let mut canvases = Vec::with_capacity(layers as usize);
+        for layer in 0..layers {
+            let start = (rows * layer / layers) as i32;
+            let end   = (rows * (layer + 1) / layers) as i32;
+
+            let mut canvas = Canvas::create(width, height)?;
+            canvas.draw_schotter_rows(&config, start..end, &mut rng)?;
+            canvases.push(canvas);
+        }
+
+        Ok(canvases)
+    }
+
+    /// Render three independent Schotters, one for each of the red, green,
+    /// and blue channels, from the matching seed in `seeds`.
+    ///
+    /// Drawing each canvas in its own color and overlaying them mixes the
+    /// colors wherever the squares cross.
+    pub fn draw_schotter_rgb(console_cols: i32,
+                             squares_per_row: i32,
+                             squares_per_col: i32,
+                             seeds: [u64; 3])
+        -> Result<(Canvas, Canvas, Canvas), CanvasError>
+    {
+        let config = SchotterConfig::new(console_cols,
+                                         squares_per_row,
+                                         squares_per_col);
+        let (width, height) = schotter_dimensions(console_cols,
+                                                  squares_per_row,
+                                                  squares_per_col)?;
+        let channel = |seed: u64| -> Result<Canvas, CanvasError> {
+            let mut canvas = Canvas::create(width, height)?;
+            canvas.draw_schotter_with(&config, &mut StdRng::seed_from_u64(seed))?;
+            Ok(canvas)
+        };
+
+        Ok((channel(seeds[0])?, channel(seeds[1])?, channel(seeds[2])?))
+    }
 
     /// Set all pixel values to clear
     pub fn clear(&mut self) {
-        unsafe {
-            ptr::write_bytes(&mut self.pixels[0], 0, self.pixels.len());
-        }
+        self.fill_value(0);
     }
 
     /// Set all pixel values to set
     pub fn fill(&mut self) {
-        unsafe {
-            ptr::write_bytes(&mut self.pixels[0], 1, self.pixels.len());
+        self.fill_value(1);
+    }
+
+    /// Turn every pixel on if `value` is nonzero, and off if it's 0.
+    pub fn fill_value(&mut self, value: u8) {
+        self.pixels.fill(if value != 0 { !0 } else { 0 });
+        self.clear_padding();
+    }
+
+    /// Clear the pixels of the `w` by `h` rectangle with its top-left corner
+    /// at `(x, y)`. The rectangle is clamped to the canvas first.
+    pub fn clear_region(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        self.fill_region_value(x, y, w, h, 0);
+    }
+
+    /// Set the pixels of the `w` by `h` rectangle with its top-left corner at
+    /// `(x, y)`. The rectangle is clamped to the canvas first.
+    pub fn fill_region(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        self.fill_region_value(x, y, w, h, 1);
+    }
+
+    fn fill_region_value(&mut self, x: i32, y: i32, w: u32, h: u32, value: u8) {
+        let (cols, rows) = match self.clamp_rect(x, y, w, h) {
+            Some(ranges) => ranges,
+            None         => return,
+        };
+
+        let width = self.width as usize;
+        for row in rows {
+            let start = row * width;
+            bits::fill(&mut self.pixels, start + cols.start..start + cols.end, value != 0);
+        }
+    }
+
+    /// Turn every on pixel off, and every off pixel on.
+    pub fn invert(&mut self) {
+        for word in self.pixels.iter_mut() {
+            *word = !*word;
         }
+        self.clear_padding();
     }
 
     /// Construct an index into the pixels buffer from an `(x, y)` coordinate.
     /// If the coordinate would be out of bounds, or if overflow occurs,
     /// return `None`.
     fn index(&self, x: i32, y: i32) -> Option<usize> {
-        if 0 <= x && x < self.width as i32 &&
-           0 <= y && y < self.height as i32
+        if 0 <= x && x < self.width &&
+           0 <= y && y < self.height
         {
             let x = x as usize;
             let y = y as usize;
@@ -126,7 +532,7 @@ impl Canvas {
 
             // Because we're in bounds, we cannot overflow.
             let index = x + y * width;
-            if index < self.pixels.len() {
+            if index < self.pixel_count() {
                 return Some(index);
             }
         }
@@ -136,263 +542,3132 @@ impl Canvas {
     /// Get the pixel at `(x, y)`. Out of bounds pixels are read as empty (0).
     pub fn get_pixel(&self, x: i32, y: i32) -> u8 {
         match self.index(x, y) {
-            Some(index) => self.pixels[index],
+            Some(index) => self.pixel_at(index) as u8,
             None        => 0,
         }
     }
 
     /// Draw a single pixel at `(x, y)`. Out of bounds writes are ignored.
     pub fn draw_pixel(&mut self, x: i32, y: i32, color: u8) {
-        match self.index(x, y) {
-            Some(index) => self.pixels[index] = color,
-            None        => {},
+        if let Some(index) = self.index(x, y) {
+            self.set_pixel_at(index, color != 0);
         }
     }
 
-    /// Draw a line from `(x1, y1)` to `(x2, y2)` using the Bresenham algorithm.
-    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: u8) {
-        // TODO: Explain how this works.
-        //      https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
-        let sx = if x1 < x2 { 1 } else { -1 };
-        let sy = if y1 < y2 { 1 } else { -1 };
-        let x2 = x2 as isize;
-        let y2 = y2 as isize;
-        let dx = (x2 - x1 as isize).abs();
-        let dy = (y2 - y1 as isize).abs();
-
-        let mut x = x1 as isize;
-        let mut y = y1 as isize;
-        let mut err = dx - dy;
+    /// Clamp the `w` by `h` rectangle with its top-left corner at `(x, y)` to
+    /// the canvas, returning the column and row ranges it covers.
+    /// Returns `None` if none of the rectangle is on the canvas.
+    fn clamp_rect(&self, x: i32, y: i32, w: u32, h: u32)
+        -> Option<(Range<usize>, Range<usize>)>
+    {
+        // Widen so that `x + w` can't overflow.
+        let clamp = |start: i32, len: u32, max: i32| {
+            let end = (start as i64 + len as i64).min(max as i64);
+            let start = (start as i64).max(0);
+            if start < end {
+                Some(start as usize..end as usize)
+            } else {
+                None
+            }
+        };
+        Some((clamp(x, w, self.width)?, clamp(y, h, self.height)?))
+    }
 
-        loop {
-            self.draw_pixel(x as i32, y as i32, color);
-            if x == x2 && y == y2 { break; }
+    /// Compute the fraction of pixels that are on in the `w` by `h` rectangle
+    /// with its top-left corner at `(x, y)`.
+    ///
+    /// The rectangle is clamped to the canvas first, and an empty rectangle
+    /// has a density of 0. Comparing regions is a quick way to see how the
+    /// chaos in Schotter is distributed.
+    pub fn sample_region(&self, x: i32, y: i32, w: u32, h: u32) -> f32 {
+        let (cols, rows) = match self.clamp_rect(x, y, w, h) {
+            Some(ranges) => ranges,
+            None         => return 0.0,
+        };
 
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x += sx;
-            }
-            if e2 < dx {
-                err += dx;
-                y += sy;
-            }
-        }
+        let width = self.width as usize;
+        let set: usize = rows.clone()
+                             .map(|row| {
+                                 let start = row * width;
+                                 bits::count(&self.pixels, start + cols.start..start + cols.end)
+                             })
+                             .sum();
+        set as f32 / (cols.len() * rows.len()) as f32
     }
 
-    /// Draw a square centered at the specified `(x, y)` coordinates, with the
-    /// specified rotation angle and size.
-    pub fn draw_square(&mut self, x: i32, y: i32, size: f32, angle: f32) {
-        // `size`, as passed into this function, represents the scaling of a
-        // unit square.
-        // We will operate on four equally spaced points on a unit circle that
-        // represent our square's corners.
-        // We must adjust this `size` by the ratio between our square's diagonal
-        // and the radius of the circle that encloses it to get the correct
-        // scaling in the final square.
-        // The square has unit side lengths, and thus has a diagonal of sqrt(2).
-        let size = ((size as f64) / 1.4142135623).round() as f32;
-
-        // We construct the four corners of the square by using our parametric
-        // equations for the circle at four equally-spaced `k` values.
-        let mut points: [(i32, i32); 4] = Default::default();
-        // The first point of a non-rotated square is at t=PI/4. When we rotate
-        // the square, we just offset this initial radian value.
-        let mut k = PI/4.0 + angle;
-        for j in 0..4 {
-            points[j].0 = (k.sin() * size + x as f32).round() as i32;
-            points[j].1 = (k.cos() * size + y as f32).round() as i32;
-            k += PI/2.0;
+    /// Make a copy of the canvas `factor` times larger in each direction,
+    /// with every pixel turned into a `factor` by `factor` block.
+    pub fn scaled(&self, factor: u32) -> Result<Canvas, CanvasError> {
+        if factor == 0 {
+            return Err(InvalidParameter { name: "factor" });
         }
+        let grow = |size: i32| (size as u32).checked_mul(factor)
+                                            .filter(|&size| size <= i32::MAX as u32)
+                                            .ok_or(InvalidParameter { name: "factor" });
+        let (width, height) = (grow(self.width)?, grow(self.height)?);
+        width.checked_mul(height).ok_or(InvalidParameter { name: "factor" })?;
 
-        // Each of the four points needs to be connected. We connect them in
-        // counter-clockwise order
-        for j in 0..4 {
-            let p = points[j];
-            let q = points[(j + 1) % 4];
-            self.draw_line(p.0, p.1, q.0, q.1, 1);
-        }
+        let factor = factor as i32;
+        Canvas::from_fn(width, height, |x, y| self.get_pixel(x / factor, y / factor))
     }
 
-    /// Draw Georg Ness's "Schotter"
+    /// Make an `out_width` by `out_height` canvas covered in copies of this
+    /// one, repeated in both directions from the top-left corner. Copies that
+    /// don't fit at the right and bottom edges are cut off.
     ///
-    /// "Schotter" is a tiled arrangement of squares that grow increasingly
-    /// chaotic as you advance down the image.
-    pub fn draw_schotter(&mut self,
-                         console_cols:    i32,
-                         squares_per_row: i32,
-                         squares_per_col: i32)
-        -> Result<(), CanvasError>
-    {
-        let needed_width:  i32 = 2 * console_cols;
-        let padding:       f32 = if needed_width > 4 { 2.0 } else { 0.0 };
-        let square_side:   f32 = (needed_width as f32 - 2.0 * padding)
-                                   / squares_per_row as f32;
-        let needed_height: i32 = (square_side * squares_per_col as f32
-                                   + 2.0 * padding).round() as i32;
-
-        if needed_width  > self.width as i32  ||
-           needed_height > self.height as i32
-        {
+    /// An empty canvas has nothing to repeat, so that's an error.
+    pub fn tiled(&self, out_width: u32, out_height: u32) -> Result<Canvas, CanvasError> {
+        if self.width == 0 || self.height == 0 {
             return Err(CanvasTooSmall {
-                needed_width,
-                needed_height,
+                needed_width: 1,
+                needed_height: 1,
                 actual_width: self.width,
                 actual_height: self.height,
             });
         }
+        if out_width > i32::MAX as u32 {
+            return Err(InvalidParameter { name: "out_width" });
+        }
+        if out_height > i32::MAX as u32 ||
+           out_width.checked_mul(out_height).is_none()
+        {
+            return Err(InvalidParameter { name: "out_height" });
+        }
 
-        for y in 0..squares_per_col {
-            // This scaling factor is chosen per row, and increases as you go
-            // down the rows. (Row number increases downward).
-            let factor = (y + 1) as f32 / (squares_per_col + 1) as f32;
-            for x in 0..squares_per_row {
-                let mut sx = (x as f32 * square_side +
-                              square_side/2.0 + padding).round() as i32;
-                let mut sy = (y as f32 * square_side +
-                              square_side/2.0 + padding).round() as i32;
+        // Build the first copy's worth of rows a piece of a row at a time, and
+        // then copy whole rows from above for the rest.
+        let mut out = Canvas::create(out_width, out_height)?;
+        let (src_width, src_height) = (self.width as usize, self.height as usize);
+        let out_width = out_width as usize;
+        for y in 0..out_height as usize {
+            let row = y * out_width;
+            if y < src_height {
+                for x in (0..out_width).step_by(src_width) {
+                    let len = src_width.min(out_width - x);
+                    let src = y * src_width;
+                    bits::copy(&self.pixels, src..src + len, &mut out.pixels, row + x);
+                }
+            } else {
+                let above = row - src_height * out_width;
+                bits::copy_within(&mut out.pixels, above..above + out_width, row);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Copy all of `src` onto this canvas, with its top-left corner at
+    /// `(x, y)`. Parts of `src` that land out of bounds are ignored.
+    pub fn blit(&mut self, src: &Canvas, x: i32, y: i32) {
+        for sy in 0..src.height {
+            for sx in 0..src.width {
+                self.draw_pixel(x + sx, y + sy, src.get_pixel(sx, sy));
+            }
+        }
+    }
 
-                let mut r1: f32 = random::<f32>() * factor;
-                if random() { r1 = -r1; }
+    /// Move everything on the canvas `dx` pixels right and `dy` pixels down,
+    /// setting the pixels left behind to `fill`. Negative values move it left
+    /// and up, and whatever moves off of the canvas is gone.
+    pub fn shift(&mut self, dx: i32, dy: i32, fill: u8) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let (x_by, y_by) = (dx.unsigned_abs() as usize, dy.unsigned_abs() as usize);
+        if x_by >= width || y_by >= height {
+            self.fill_value(fill);
+            return;
+        }
 
-                let mut r2: f32 = random::<f32>() * factor;
-                if random() { r2 = -r2; }
+        let fill = fill != 0;
+        let len = self.pixel_count();
 
-                let mut r3: f32 = random::<f32>() * factor;
-                if random() { r3 = -r3; }
+        // Move whole rows up or down...
+        let rows = y_by * width;
+        if dy > 0 {
+            bits::copy_within(&mut self.pixels, 0..len - rows, rows);
+            bits::fill(&mut self.pixels, 0..rows, fill);
+        } else if dy < 0 {
+            bits::copy_within(&mut self.pixels, rows..len, 0);
+            bits::fill(&mut self.pixels, len - rows..len, fill);
+        }
 
-                let angle = r1;
-                sx += (r2 * square_side / 3.0).round() as i32;
-                sy += (r3 * square_side / 3.0).round() as i32;
-                self.draw_square(sx as i32, sy as i32, square_side, angle);
+        // ...and then each row left or right.
+        if dx != 0 {
+            for row in (0..len).step_by(width) {
+                if dx > 0 {
+                    bits::copy_within(&mut self.pixels, row..row + width - x_by, row + x_by);
+                    bits::fill(&mut self.pixels, row..row + x_by, fill);
+                } else {
+                    bits::copy_within(&mut self.pixels, row + x_by..row + width, row);
+                    bits::fill(&mut self.pixels, row + width - x_by..row + width, fill);
+                }
             }
         }
+    }
 
-        Ok(())
+    /// Mirror the canvas left-to-right.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.width as usize;
+        for row in (0..self.pixel_count()).step_by(width.max(1)) {
+            bits::reverse(&mut self.pixels, row..row + width);
+        }
     }
 
-    /// Render the canvas into a multi-line string. Pixels are either "on" or
-    /// "off".
+    /// Mirror the canvas top-to-bottom.
+    pub fn flip_vertical(&mut self) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        for y in 0..height / 2 {
+            bits::swap(&mut self.pixels, y * width, (height - 1 - y) * width, width);
+        }
+    }
+
+    /// Rotate the canvas a quarter turn clockwise, without copying it.
     ///
-    /// This string is designed to be seen by humans, and should not be used
-    /// in lieu of iterating over the pixels with `Canvas::get_pixel`.
-    /// On  pixels are rendered as a dot, or other dark, solid marking.
-    /// Off pixels are rendered as empty space or white space.
-    pub fn render(&self) -> String {
-        let mut out = String::with_capacity(self.pixels.len());
-        // Iterate over the range in 2x4 vertical blocks.
-        // TODO: Check edge case when height % 4 != 0, and width % 2 != 0.
-        for y in (0..self.height).step_by(4) {
-            for x in (0..self.width).step_by(2) {
-                let x = x as i32;
-                let y = y as i32;
-                // Each bit in the byte corresponds to a different pixel in the
-                // tile. The ordering here is specially chosen so that this
-                // maps cleanly to the Braille character set.
-                let mut byte: u8 = 0;
-                if self.get_pixel(x,   y)   != 0 { byte |= 1 << 0; }
-                if self.get_pixel(x,   y+1) != 0 { byte |= 1 << 1; }
-                if self.get_pixel(x,   y+2) != 0 { byte |= 1 << 2; }
-                if self.get_pixel(x+1, y)   != 0 { byte |= 1 << 3; }
-                if self.get_pixel(x+1, y+1) != 0 { byte |= 1 << 4; }
-                if self.get_pixel(x+1, y+2) != 0 { byte |= 1 << 5; }
-                if self.get_pixel(x,   y+3) != 0 { byte |= 1 << 6; }
-                if self.get_pixel(x+1, y+3) != 0 { byte |= 1 << 7; }
-                out.push(translate_pixels_group(byte));
+    /// Square canvases are rotated four pixels at a time, cycling each pixel
+    /// through the four positions it takes on as the canvas turns. Other
+    /// canvases also swap their width and height, so the pixels are instead
+    /// moved along the cycles of that permutation. Each cycle is moved once,
+    /// starting from its lowest position, which is found by following the
+    /// cycle around. That takes more time than remembering which pixels have
+    /// moved, but no more memory.
+    pub fn rotate_pixels_90_in_place(&mut self) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+
+        if width == height {
+            let n = width;
+            for y in 0..n / 2 {
+                for x in y..n - 1 - y {
+                    // The four corners of a ring, going around clockwise.
+                    let top    = y * n + x;
+                    let right  = x * n + (n - 1 - y);
+                    let bottom = (n - 1 - y) * n + (n - 1 - x);
+                    let left   = (n - 1 - x) * n + y;
+
+                    let px = self.pixel_at(left);
+                    self.set_pixel_at(left,   self.pixel_at(bottom));
+                    self.set_pixel_at(bottom, self.pixel_at(right));
+                    self.set_pixel_at(right,  self.pixel_at(top));
+                    self.set_pixel_at(top,    px);
+                }
             }
-            out.push('\n');
+            return;
         }
-        out
-    }
-}
 
-/// Translate a group of 8 pixels (2x4 rectangle) into their corresponding
-/// braille character.
-///
-/// Each bit of `byte` should correspond to the pixels arranged as follows:
-/// ```norun
-/// [0] [3]
-/// [1] [4]
-/// [2] [5]
-/// [6] [7]
-/// ```
-pub fn translate_pixels_group(byte: u8) -> char {
-    // See: https://en.wikipedia.org/wiki/Braille_Patterns
+        // The pixel at (x, y) moves to (height - 1 - y, x), in a canvas
+        // `height` pixels wide.
+        let destination = |i: usize| (i % width) * height + (height - 1 - i / width);
+        for start in 0..self.pixel_count() {
+            // Only the lowest position in a cycle leads it. Any other
+            // position's cycle reaches a lower one, and was already moved.
+            let mut i = destination(start);
+            while i > start {
+                i = destination(i);
+            }
+            if i != start {
+                continue;
+            }
 
-    // Braille has a standard 6-dot cell for conveying symbols, which was later
-    // expanded into the 8-cell. Each of the 8 cells (as labeled above) can be
-    // either raised or not, hence the obvious mapping to a byte.
-    // Note that some fonts may render these with hollow dots in place of space,
-    // and that will change the appearance of the script.
+            let mut carried = self.pixel_at(start);
+            loop {
+                i = destination(i);
+                let here = self.pixel_at(i);
+                self.set_pixel_at(i, carried);
+                carried = here;
+                if i == start {
+                    break;
+                }
+            }
+        }
 
-    // Convert to unicode. This is in the U0800-UFFFF range,
-    // so we need to emit it like this in three bytes:
-    //      1110_xxxx 10xx_xxxx 10xx_xxxx
-    let code = 0x2800 + byte as u32;
-    let out: [u8; 3] = [
-        0xe0 | ((code >> 12) as u8),
+        core::mem::swap(&mut self.width, &mut self.height);
+    }
+
+    /// Draw a line from `(x1, y1)` to `(x2, y2)` using the Bresenham algorithm.
+    ///
+    /// Any endpoints work, even far off of the canvas, and only the part of
+    /// the line that's on the canvas takes any time to draw.
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: u8) {
+        // Axis-aligned lines are common in grids and frames, and they're
+        // just runs of pixels.
+        if y1 == y2 {
+            self.draw_horizontal_line(x1, x2, y1, color);
+        } else if x1 == x2 {
+            self.draw_vertical_line(x1, y1, y2, color);
+        } else {
+            self.draw_line_bresenham(x1, y1, x2, y2, color);
+        }
+    }
+
+    /// The number of pixels `draw_line` visits for a line from `(x1, y1)` to
+    /// `(x2, y2)`, whether or not they're on any canvas: one for each step
+    /// along the axis the line spans more of, counting both ends.
+    pub fn measure_line_length(x1: i32, y1: i32, x2: i32, y2: i32) -> usize {
+        let dx = (x2 as i64 - x1 as i64).unsigned_abs();
+        let dy = (y2 as i64 - y1 as i64).unsigned_abs();
+        // Only a 32-bit target can't count the longest lines.
+        (dx.max(dy) + 1).min(usize::MAX as u64) as usize
+    }
+
+    /// Fill the pixels of row `y` from `x1` to `x2`, inclusive.
+    fn draw_horizontal_line(&mut self, x1: i32, x2: i32, y: i32, color: u8) {
+        if y < 0 || y >= self.height {
+            return;
+        }
+        if let Some(xs) = clamp_span(x1, x2, self.width) {
+            let row = y as usize * self.width as usize;
+            bits::fill(&mut self.pixels, row + xs.start..row + xs.end, color != 0);
+        }
+    }
+
+    /// Fill the pixels of column `x` from `y1` to `y2`, inclusive.
+    fn draw_vertical_line(&mut self, x: i32, y1: i32, y2: i32, color: u8) {
+        if x < 0 || x >= self.width {
+            return;
+        }
+        if let Some(ys) = clamp_span(y1, y2, self.height) {
+            let width = self.width as usize;
+            for y in ys {
+                self.set_pixel_at(y * width + x as usize, color != 0);
+            }
+        }
+    }
+
+    fn draw_line_bresenham(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: u8) {
+        // Bresenham's algorithm steps one pixel at a time along the major
+        // axis, the one the line spans more of, and one pixel along the minor
+        // axis whenever the line has drifted more than half a pixel away.
+        // So the `k`th pixel is `k * minor / major` along the minor axis,
+        // rounded with ties going down, and any pixel can be found without
+        // walking there. That lets us visit only the stretch of the major axis
+        // that's on the canvas.
+        //      https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
+        //
+        // The spans of i32 coordinates need 33 bits, so the math is in i64,
+        // and i128 where two spans are multiplied.
+        let (x1, y1, x2, y2) = (x1 as i64, y1 as i64, x2 as i64, y2 as i64);
+        let sx = if x1 < x2 { 1 } else { -1 };
+        let sy = if y1 < y2 { 1 } else { -1 };
+        let dx = (x2 - x1).abs();
+        let dy = (y2 - y1).abs();
+
+        let x_major = dx >= dy;
+        let (major, minor) = if x_major { (dx, dy) } else { (dy, dx) };
+        let (start, step, size) = if x_major {
+            (x1, sx, self.width as i64)
+        } else {
+            (y1, sy, self.height as i64)
+        };
+
+        // The steps that land on the canvas along the major axis. There are
+        // never more than `major + 1` of them, however the inputs look.
+        let (first, last) = if step > 0 {
+            (-start, size - 1 - start)
+        } else {
+            (start - (size - 1), start)
+        };
+        for k in first.max(0)..=last.min(major) {
+            let offset = if major == 0 {
+                0
+            } else {
+                ((2 * minor as i128 * k as i128 + major as i128 - 1) / (2 * major as i128)) as i64
+            };
+            let (x, y) = if x_major {
+                (x1 + sx * k, y1 + sy * offset)
+            } else {
+                (x1 + sx * offset, y1 + sy * k)
+            };
+            // Both are between the endpoints, so they fit in an i32.
+            self.draw_pixel(x as i32, y as i32, color);
+        }
+    }
+
+    /// Draw a square centered at the specified `(x, y)` coordinates, with the
+    /// specified rotation angle and size.
+    pub fn draw_square(&mut self, x: i32, y: i32, size: f32, angle: f32) {
+        self.draw_square_with_color(x, y, size, angle, 1);
+    }
+
+    /// Draw a square centered at the specified `(x, y)` coordinates, with the
+    /// specified rotation angle and size.
+    ///
+    /// Unlike `draw_square`, the center and size are kept as they are, and
+    /// only the corners are rounded to pixels.
+    pub fn draw_square_f(&mut self, x: f32, y: f32, size: f32, angle: f32) {
+        self.draw_quad(&square_corners_f(x, y, size, angle), 1);
+    }
+
+    fn draw_square_with_color(&mut self, x: i32, y: i32, size: f32, angle: f32, color: u8) {
+        self.draw_quad(&square_corners(x, y, size, angle), color);
+    }
+
+    /// Draw a solid square centered at `(x, y)`, with the specified rotation
+    /// angle and size.
+    ///
+    /// The square covers the same corners and outline as `draw_square`, and
+    /// everything inside it.
+    pub fn draw_square_filled(&mut self, x: i32, y: i32, size: f32, angle: f32) {
+        let corners = square_corners(x, y, size, angle);
+        self.fill_quad(&corners, 1);
+        self.draw_quad(&corners, 1);
+    }
+
+    /// Fill the inside of the quadrilateral with corners `points`, one row
+    /// at a time. Pixels exactly on an edge count as inside, but the rounding
+    /// can miss some of the outline, so `draw_quad` is still needed for it.
+    fn fill_quad(&mut self, points: &[(i32, i32); 4], color: u8) {
+        let top = points.iter().map(|p| p.1).min().unwrap_or(0).max(0);
+        let bottom = points.iter().map(|p| p.1).max().unwrap_or(-1).min(self.height - 1);
+
+        let mut crossings = Vec::with_capacity(4);
+        for y in top..=bottom {
+            // Where the row crosses the edges, counting each edge from its top
+            // end but not its bottom one, like `polygon_mask`.
+            let yf = y as f64;
+            crossings.clear();
+            for (j, p) in points.iter().enumerate() {
+                let q = points[(j + 1) % 4];
+                let (p, q) = ((p.0 as f64, p.1 as f64), (q.0 as f64, q.1 as f64));
+                if (p.1 > yf) != (q.1 > yf) {
+                    crossings.push(p.0 + (yf - p.1) * (q.0 - p.0) / (q.1 - p.1));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                // Saturating casts keep far-off crossings far off.
+                let (start, end) = (span[0].ceil() as i32, span[1].floor() as i32);
+                if start <= end {
+                    self.draw_horizontal_line(start, end, y, color);
+                }
+            }
+        }
+    }
+
+    /// Draw the outline of the quadrilateral with corners `points`.
+    fn draw_quad(&mut self, points: &[(i32, i32); 4], color: u8) {
+        // Each of the four points needs to be connected. We connect them in
+        // counter-clockwise order
+        for j in 0..4 {
+            let p = points[j];
+            let q = points[(j + 1) % 4];
+            self.draw_line(p.0, p.1, q.0, q.1, color);
+        }
+    }
+
+    /// Draw the outlines of all of `quads`, as if by `Canvas::draw_quad`.
+    fn draw_quads(&mut self, quads: &[[(i32, i32); 4]], color: u8) {
+        #[cfg(feature = "rayon")]
+        {
+            if parallel::worth_it(self.pixel_count()) {
+                return self.draw_quads_parallel(quads, color);
+            }
+        }
+
+        for quad in quads {
+            self.draw_quad(quad, color);
+        }
+    }
+
+    /// Whether the bounding box of `points` misses the canvas, so that
+    /// nothing inside of them could be drawn, even once they're rounded to
+    /// whole pixels.
+    fn misses_canvas(&self, points: &[(f32, f32)]) -> bool {
+        let (right, bottom) = (self.width as f32 - 0.5, self.height as f32 - 0.5);
+        points.iter().all(|&(x, _)| x < -0.5) || points.iter().all(|&(x, _)| x >= right) ||
+        points.iter().all(|&(_, y)| y < -0.5) || points.iter().all(|&(_, y)| y >= bottom)
+    }
+
+    /// Draw the outline of the polygon with corners `points`, joining the
+    /// last corner back to the first.
+    pub fn draw_polygon(&mut self, points: &[(i32, i32)], color: u8) {
+        for (j, p) in points.iter().enumerate() {
+            let q = points[(j + 1) % points.len()];
+            self.draw_line(p.0, p.1, q.0, q.1, color);
+        }
+    }
+
+    /// Draw the outline of the polygon with corners `points`, like
+    /// `Canvas::draw_polygon`, and hatch its inside with parallel lines
+    /// `spacing` pixels apart, running at `angle` degrees clockwise from
+    /// horizontal.
+    ///
+    /// The inside follows the even-odd rule, so where a polygon crosses over
+    /// itself, the overlap is left empty. The lines are placed relative to
+    /// the canvas rather than the polygon, so neighboring polygons hatched
+    /// the same way line up. A `spacing` of 0 draws just the outline.
+    pub fn draw_hatching_fill(&mut self,
+                              points: &[(i32, i32)],
+                              spacing: u32,
+                              angle: f32,
+                              color: u8)
+    {
+        self.draw_polygon(points, color);
+        if spacing == 0 || points.len() < 3 {
+            return;
+        }
+
+        let min_x = points.iter().map(|p| p.0).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.0).max().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.1).max().unwrap_or(0);
+        let span = |min: i32, max: i32| (max as i64 - min as i64 + 1).min(u32::MAX as i64) as u32;
+        let (cols, rows) = match self.clamp_rect(min_x, min_y, span(min_x, max_x),
+                                                 span(min_y, max_y)) {
+            Some(ranges) => ranges,
+            None         => return,
+        };
+        let mask = polygon_mask(points, cols.clone(), rows.clone());
+
+        // Rule the lines across the whole of the clamped bounding box, then
+        // keep only what's inside.
+        let (w, h) = (cols.len(), rows.len());
+        let mut hatching = match Canvas::create(w as u32, h as u32) {
+            Ok(canvas) => canvas,
+            Err(_)     => return,
+        };
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (ox, oy) = (cols.start as f32, rows.start as f32);
+        let (mid_x, mid_y) = (ox + w as f32 / 2.0, oy + h as f32 / 2.0);
+        // Each line is the set of points this far across the lines from the
+        // origin, and a multiple of `spacing`.
+        let across = |x: f32, y: f32| -sin * x + cos * y;
+        let corners = [across(ox, oy), across(ox + w as f32, oy),
+                       across(ox, oy + h as f32), across(ox + w as f32, oy + h as f32)];
+        let nearest = corners.iter().cloned().fold(f32::INFINITY, f32::min);
+        let farthest = corners.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let spacing = spacing as f32;
+        let (first, last) = ((nearest / spacing).ceil() as i64, (farthest / spacing).floor() as i64);
+        let reach = (w + h) as f32;
+        for k in first..=last {
+            // The point on the line nearest the middle of the box.
+            let offset = k as f32 * spacing - across(mid_x, mid_y);
+            let (x, y) = (mid_x - sin * offset - ox, mid_y + cos * offset - oy);
+            hatching.draw_line((x - cos * reach).round() as i32, (y - sin * reach).round() as i32,
+                               (x + cos * reach).round() as i32, (y + sin * reach).round() as i32,
+                               1);
+        }
+
+        let width = self.width as usize;
+        for (i, &inside) in mask.iter().enumerate() {
+            if inside && hatching.pixel_at(i) {
+                self.set_pixel_at((rows.start + i / w) * width + cols.start + i % w, color != 0);
+            }
+        }
+    }
+
+    /// Draw `count` squares centered at `(cx, cy)`, all rotated by `angle`.
+    ///
+    /// The smallest square is `spacing` pixels in size, and each one after it
+    /// is `spacing` pixels larger than the last, like the rings of a target.
+    pub fn draw_concentric_squares(&mut self,
+                                   cx: i32,
+                                   cy: i32,
+                                   count: u32,
+                                   spacing: f32,
+                                   angle: f32,
+                                   color: u8)
+    {
+        for i in 1..=count {
+            self.draw_square_with_color(cx, cy, spacing * i as f32, angle, color);
+        }
+    }
+
+    /// Draw `turns` squares centered at `(cx, cy)`, spiraling outward.
+    ///
+    /// The first square is `initial_size` pixels in size and unrotated. Each
+    /// one after it is `growth_rate` times the size of the last, and turned
+    /// `angle_step` radians further, so a `growth_rate` a little over 1 with a
+    /// small `angle_step` gives a whirl of nested squares.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_spiral_square(&mut self,
+                              cx: i32,
+                              cy: i32,
+                              initial_size: f32,
+                              growth_rate: f32,
+                              turns: u32,
+                              angle_step: f32,
+                              color: u8)
+    {
+        let mut size = initial_size;
+        for i in 0..turns {
+            self.draw_square_with_color(cx, cy, size, angle_step * i as f32, color);
+            size *= growth_rate;
+        }
+    }
+
+    /// Draw a circle of `radius` pixels centered at `(cx, cy)`.
+    ///
+    /// This uses the midpoint circle algorithm, so the outline is exactly one
+    /// pixel thick with no gaps. A radius of 0 draws the single center pixel.
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: u32, color: u8) {
+        // Work in i64 so that huge radii can't overflow.
+        let (cx, cy, r) = (cx as i64, cy as i64, radius as i64);
+
+        // Skip circles that miss the canvas entirely, either by being off to
+        // one side or by being so big that the whole canvas is inside.
+        let (w, h) = (self.width as i64, self.height as i64);
+        if cx + r < 0 || cx - r >= w || cy + r < 0 || cy - r >= h {
+            return;
+        }
+        let far_x = cx.abs().max((w - 1 - cx).abs());
+        let far_y = cy.abs().max((h - 1 - cy).abs());
+        if r > far_x + far_y + 1 {
+            return;
+        }
+
+        let mut x = r;
+        let mut y = 0;
+        let mut err = 1 - x;
+
+        // Walk one octant, from (r, 0) until the diagonal, and mirror each
+        // point into the other seven.
+        while x >= y {
+            for &(dx, dy) in [(x, y), (y, x), (-y, x), (-x, y),
+                              (-x, -y), (-y, -x), (y, -x), (x, -y)].iter() {
+                let (px, py) = (cx + dx, cy + dy);
+                if px >= 0 && px < w && py >= 0 && py < h {
+                    self.draw_pixel(px as i32, py as i32, color);
+                }
+            }
+
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Draw `count` circles centered at `(cx, cy)`, with radii `spacing`,
+    /// `2 * spacing`, up to `count * spacing`.
+    pub fn draw_concentric_circles(&mut self,
+                                   cx: i32,
+                                   cy: i32,
+                                   count: u32,
+                                   spacing: u32,
+                                   color: u8)
+    {
+        for i in 1..=count {
+            self.draw_circle(cx, cy, spacing.saturating_mul(i), color);
+        }
+    }
+
+    /// Check whether any part of the bounding box of `points` lies on the
+    /// canvas.
+    fn overlaps_bounds(&self, points: &[(i32, i32)]) -> bool {
+        let min_x = points.iter().map(|p| p.0).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.0).max().unwrap_or(-1);
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.1).max().unwrap_or(-1);
+        max_x >= 0 && min_x < self.width && max_y >= 0 && min_y < self.height
+    }
+
+    /// Draw Georg Ness's "Schotter"
+    ///
+    /// "Schotter" is a tiled arrangement of squares that grow increasingly
+    /// chaotic as you advance down the image.
+    #[cfg(feature = "std")]
+    pub fn draw_schotter(&mut self,
+                         console_cols:    i32,
+                         squares_per_row: i32,
+                         squares_per_col: i32)
+        -> Result<(), CanvasError>
+    {
+        let config = SchotterConfig::new(console_cols,
+                                         squares_per_row,
+                                         squares_per_col);
+        self.draw_schotter_with(&config, &mut thread_rng())
+    }
+
+    /// Draw "Schotter" where the size of each square is also randomized,
+    /// along with its position and angle.
+    ///
+    /// See `SchotterConfig::scale_jitter`.
+    #[cfg(feature = "std")]
+    pub fn draw_schotter_with_scale_jitter(&mut self,
+                                           console_cols:    i32,
+                                           squares_per_row: i32,
+                                           squares_per_col: i32)
+        -> Result<(), CanvasError>
+    {
+        let config = SchotterConfig {
+            scale_jitter: true,
+            ..SchotterConfig::new(console_cols, squares_per_row, squares_per_col)
+        };
+        self.draw_schotter_with(&config, &mut thread_rng())
+    }
+
+    /// Draw "Schotter" as described by `config`, taking all randomness
+    /// from `rng`.
+    ///
+    /// Seeding `rng` makes the output reproducible.
+    /// `config.with_caption` is ignored, since the canvas is already sized.
+    pub fn draw_schotter_with<R: Rng + ?Sized>(&mut self,
+                                               config: &SchotterConfig,
+                                               rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        self.draw_schotter_rows(config, 0..config.squares_per_col, rng)
+    }
+
+    /// Draw only the rows of squares in `rows` of "Schotter", as described by
+    /// `config`.
+    ///
+    /// Randomness is consumed one square at a time in row-major order, so
+    /// drawing `0..k` and then `k..n` with the same `rng` produces exactly the
+    /// same canvas as drawing `0..n` all at once. This lets callers stream the
+    /// artwork progressively.
+    ///
+    /// `rows` must lie within `0..config.squares_per_col`.
+    pub fn draw_schotter_rows<R: Rng + ?Sized>(&mut self,
+                                               config: &SchotterConfig,
+                                               rows: Range<i32>,
+                                               rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        self.draw_schotter_rows_impl(config, rows, rng, false, None)
+    }
+
+    /// Draw "Schotter" just like `Canvas::draw_schotter_with`, but skip the
+    /// squares that would land entirely off of the canvas.
+    ///
+    /// The output is identical, but no time is wasted rasterizing squares
+    /// that can't be seen. This pays off when the jitter is large enough to
+    /// throw squares well outside of the canvas.
+    pub fn draw_schotter_overlap_aware<R: Rng + ?Sized>(&mut self,
+                                                        config: &SchotterConfig,
+                                                        rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        self.draw_schotter_rows_impl(config, 0..config.squares_per_col, rng, true, None)
+    }
+
+    /// Draw "Schotter" clipped to the shape of `mask`, which must be the same
+    /// size as this canvas.
+    ///
+    /// Squares whose center lands on an empty (0) pixel of `mask` are
+    /// skipped. Randomness is still drawn for them, so the squares that are
+    /// kept match the unmasked artwork from the same `rng`.
+    pub fn draw_schotter_masked<R: Rng + ?Sized>(&mut self,
+                                                 console_cols:    i32,
+                                                 squares_per_row: i32,
+                                                 squares_per_col: i32,
+                                                 mask: &Canvas,
+                                                 rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        if mask.width != self.width || mask.height != self.height {
+            return Err(InvalidParameter { name: "mask" });
+        }
+
+        let config = SchotterConfig::new(console_cols,
+                                         squares_per_row,
+                                         squares_per_col);
+        self.draw_schotter_rows_impl(&config, 0..squares_per_col, rng, false, Some(mask))
+    }
+
+    /// Draw "Schotter" as described by `config`, with each row of squares
+    /// shifted sideways along a sine wave.
+    ///
+    /// Row `y` (counting rows of squares, from 0 at the top) moves
+    /// `amplitude * sin(y * frequency)` pixels to the right, rounded to a
+    /// whole pixel so that the squares keep their shape. The randomness is
+    /// the same as `Canvas::draw_schotter_with`, so an `amplitude` of 0 draws
+    /// the same thing. Shifted squares are cut off at the edges of the canvas.
+    pub fn draw_schotter_sine_row_offset<R: Rng + ?Sized>(&mut self,
+                                                          config: &SchotterConfig,
+                                                          amplitude: f32,
+                                                          frequency: f32,
+                                                          rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        let mut squares = self.schotter_squares(config, 0..config.squares_per_col, rng,
+                                                false, None)?;
+        // None of the squares are skipped, so each row is a run of
+        // `squares_per_row` of them.
+        for (y, row) in squares.chunks_mut(config.squares_per_row as usize).enumerate() {
+            let dx = (amplitude * (y as f32 * frequency).sin()).round() as i32;
+            for corner in row.iter_mut().flatten() {
+                corner.0 = corner.0.saturating_add(dx);
+            }
+        }
+        self.draw_quads(&squares, 1);
+        Ok(())
+    }
+
+    /// Divide the canvas into a `grid_cols` by `grid_rows` grid of cells, and
+    /// draw a separate, square "Schotter" of `squares_per_cell` by
+    /// `squares_per_cell` squares in each one, like a gallery wall.
+    ///
+    /// The cells are numbered across and then down, and cell `i` is drawn
+    /// from the seed `base_seed + i`, so any of them can be reproduced on its
+    /// own. Each artwork is as big as fits in its cell and centered there.
+    pub fn draw_grid_schotter(&mut self,
+                              grid_cols: u32,
+                              grid_rows: u32,
+                              squares_per_cell: u32,
+                              base_seed: u64)
+        -> Result<(), CanvasError>
+    {
+        if grid_cols == 0 || grid_cols > self.width as u32 {
+            return Err(InvalidParameter { name: "grid_cols" });
+        }
+        if grid_rows == 0 || grid_rows > self.height as u32 {
+            return Err(InvalidParameter { name: "grid_rows" });
+        }
+        if squares_per_cell == 0 || squares_per_cell > i32::MAX as u32 {
+            return Err(InvalidParameter { name: "squares_per_cell" });
+        }
+
+        let cell_width = self.width / grid_cols as i32;
+        let cell_height = self.height / grid_rows as i32;
+        // A square Schotter is as tall as it is wide, and is 2 pixels wide for
+        // every console column.
+        let console_cols = cell_width.min(cell_height) / 2;
+        if console_cols == 0 {
+            return Err(CanvasTooSmall {
+                needed_width: 2 * grid_cols as i32,
+                needed_height: 2 * grid_rows as i32,
+                actual_width: self.width,
+                actual_height: self.height,
+            });
+        }
+
+        let squares = squares_per_cell as i32;
+        let config = SchotterConfig::new(console_cols, squares, squares);
+        let (width, height) = schotter_dimensions(console_cols, squares, squares)?;
+        let mut cell = Canvas::create(width, height)?;
+        for row in 0..grid_rows {
+            for col in 0..grid_cols {
+                let index = row as u64 * grid_cols as u64 + col as u64;
+                let mut rng = StdRng::seed_from_u64(base_seed.wrapping_add(index));
+                cell.clear();
+                cell.draw_schotter_with(&config, &mut rng)?;
+
+                let x = col as i32 * cell_width + (cell_width - cell.width) / 2;
+                let y = row as i32 * cell_height + (cell_height - cell.height) / 2;
+                self.blit(&cell, x, y);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_schotter_rows_impl<R: Rng + ?Sized>(&mut self,
+                                                config: &SchotterConfig,
+                                                rows: Range<i32>,
+                                                rng: &mut R,
+                                                skip_offscreen: bool,
+                                                mask: Option<&Canvas>)
+        -> Result<(), CanvasError>
+    {
+        // All of the randomness is drawn first, in order, and then the
+        // squares are drawn all together, maybe in parallel. Drawing only sets
+        // pixels, so the order they're drawn in doesn't matter.
+        let squares = self.schotter_squares(config, rows, rng, skip_offscreen, mask)?;
+        self.draw_quads(&squares, 1);
+        Ok(())
+    }
+
+    /// Work out the corners of each square that
+    /// `Canvas::draw_schotter_rows_impl` draws, without drawing them.
+    fn schotter_squares<R: Rng + ?Sized>(&self,
+                                         config: &SchotterConfig,
+                                         rows: Range<i32>,
+                                         rng: &mut R,
+                                         skip_offscreen: bool,
+                                         mask: Option<&Canvas>)
+        -> Result<Vec<[(i32, i32); 4]>, CanvasError>
+    {
+        let SchotterConfig {
+            console_cols,
+            squares_per_row,
+            squares_per_col,
+            jitter,
+            scale_jitter,
+            with_caption: _,
+            snap_to_pixels,
+        } = *config;
+
+        let SchotterLayout {
+            needed_width,
+            needed_height,
+            padding,
+            square_side,
+        } = SchotterLayout::compute(console_cols,
+                                    squares_per_row,
+                                    squares_per_col)?;
+
+        if needed_width  > self.width  ||
+           needed_height > self.height
+        {
+            return Err(CanvasTooSmall {
+                needed_width,
+                needed_height,
+                actual_width: self.width,
+                actual_height: self.height,
+            });
+        }
+
+        if rows.start < 0 || rows.start > rows.end || rows.end > squares_per_col {
+            return Err(InvalidParameter { name: "rows" });
+        }
+
+        let mut squares = Vec::with_capacity(rows.len() * squares_per_row as usize);
+        for y in rows {
+            // This scaling factor is chosen per row, and increases as you go
+            // down the rows. (Row number increases downward).
+            let factor = (y + 1) as f32 / (squares_per_col + 1) as f32;
+            for x in 0..squares_per_row {
+                let mut sx = x as f32 * square_side + square_side/2.0 + padding;
+                let mut sy = y as f32 * square_side + square_side/2.0 + padding;
+                if snap_to_pixels {
+                    sx = sx.round();
+                    sy = sy.round();
+                }
+
+                let r1 = jitter.sample(rng, factor);
+                let r2 = jitter.sample(rng, factor);
+                let r3 = jitter.sample(rng, factor);
+
+                // Only draw a fourth value when asked to, so that the classic
+                // output doesn't change.
+                let size = if scale_jitter {
+                    let r4 = jitter.sample(rng, factor);
+                    (square_side * (1.0 + r4)).max(0.0)
+                } else {
+                    square_side
+                };
+
+                let angle = r1;
+                let (dx, dy) = (r2 * square_side / 3.0, r3 * square_side / 3.0);
+                let corners = if snap_to_pixels {
+                    sx += dx.round();
+                    sy += dy.round();
+                    square_corners(sx as i32, sy as i32, size, angle)
+                } else {
+                    sx += dx;
+                    sy += dy;
+                    square_corners_f(sx, sy, size, angle)
+                };
+
+                // All of the random numbers for this square are drawn by now,
+                // so skipping it doesn't change any of the others.
+                if skip_offscreen && !self.overlaps_bounds(&corners) {
+                    continue;
+                }
+                let (cx, cy) = (sx.round() as i32, sy.round() as i32);
+                if mask.is_some_and(|mask| mask.get_pixel(cx, cy) == 0) {
+                    continue;
+                }
+                #[cfg(feature = "tracing")]
+                tracing::trace!(x, y, center_x = sx, center_y = sy, size, angle, "schotter square");
+                squares.push(corners);
+            }
+        }
+
+        Ok(squares)
+    }
+
+    /// Draw a symmetric "Schotter" by rendering one piece and mirroring it.
+    ///
+    /// `config` describes the piece, which is drawn in the top-left corner of
+    /// the canvas and reflected according to `axis`. The canvas must be big
+    /// enough for the whole mirrored image: twice the width of the piece when
+    /// mirroring horizontally, and twice the height when mirroring vertically.
+    pub fn draw_schotter_mirrored<R: Rng + ?Sized>(&mut self,
+                                                   axis: MirrorAxis,
+                                                   config: &SchotterConfig,
+                                                   rng: &mut R)
+        -> Result<(), CanvasError>
+    {
+        let (width, height) = schotter_dimensions(config.console_cols,
+                                                  config.squares_per_row,
+                                                  config.squares_per_col)?;
+        let (width, height) = (width as i32, height as i32);
+
+        let (copies_x, copies_y) = match axis {
+            MirrorAxis::Horizontal => (2, 1),
+            MirrorAxis::Vertical   => (1, 2),
+            MirrorAxis::Both       => (2, 2),
+        };
+        if width * copies_x > self.width || height * copies_y > self.height {
+            return Err(CanvasTooSmall {
+                needed_width:  width * copies_x,
+                needed_height: height * copies_y,
+                actual_width:  self.width,
+                actual_height: self.height,
+            });
+        }
+
+        let mut piece = Canvas::create(width as u32, height as u32)?;
+        piece.draw_schotter_with(config, rng)?;
+        self.blit(&piece, 0, 0);
+
+        if copies_x == 2 {
+            let mut mirrored = piece.clone();
+            mirrored.flip_horizontal();
+            self.blit(&mirrored, width, 0);
+        }
+        if copies_y == 2 {
+            piece.flip_vertical();
+            self.blit(&piece, 0, height);
+            if copies_x == 2 {
+                piece.flip_horizontal();
+                self.blit(&piece, width, height);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the canvas into a multi-line string. Pixels are either "on" or
+    /// "off".
+    ///
+    /// This string is designed to be seen by humans, and should not be used
+    /// in lieu of iterating over the pixels with `Canvas::get_pixel`.
+    /// On  pixels are rendered as a dot, or other dark, solid marking.
+    /// Off pixels are rendered as empty space or white space.
+    pub fn render(&self) -> String {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(cols = (self.width + 1) / 2, rows = (self.height + 3) / 4,
+                        "rendering braille cells");
+
+        #[cfg(feature = "rayon")]
+        {
+            if parallel::worth_it(self.pixel_count()) {
+                return self.render_parallel();
+            }
+        }
+
+        let mut out = String::with_capacity(self.pixel_count());
+        // Iterate over the range in 2x4 vertical blocks.
+        for y in (0..self.height).step_by(4) {
+            self.render_line(y, &mut out);
+        }
+        out
+    }
+
+    /// Render the line of braille for pixel rows `y` to `y + 3` onto `out`,
+    /// along with its newline.
+    fn render_line(&self, y: i32, out: &mut String) {
+        let width = self.width as usize;
+        // When the size isn't a multiple of 2x4, the cells along the right and
+        // bottom edges hang off of the canvas, and those pixels are read as
+        // off, like `get_pixel` does.
+        if y + 4 <= self.height {
+            // A whole band of 4 rows, so its cells can be read straight out
+            // of the rows.
+            let start = y as usize * width;
+            let px = |row: usize, x: usize| self.pixel_at(start + row * width + x) as u8;
+            for x in (0..width - width % 2).step_by(2) {
+                out.push(translate_pixels_group(pixels_group_byte([
+                    px(0, x), px(1, x), px(2, x),
+                    px(0, x+1), px(1, x+1), px(2, x+1),
+                    px(3, x), px(3, x+1),
+                ])));
+            }
+            if !width.is_multiple_of(2) {
+                out.push(self.braille_cell(self.width - 1, y));
+            }
+        } else {
+            for x in (0..self.width).step_by(2) {
+                out.push(self.braille_cell(x, y));
+            }
+        }
+        out.push('\n');
+    }
+
+    /// The braille character for the 2x4 cell with its top-left corner at
+    /// `(x, y)`, which may hang off of the canvas.
+    fn braille_cell(&self, x: i32, y: i32) -> char {
+        translate_pixels_group(pixels_group_byte([
+            self.get_pixel(x,   y),   self.get_pixel(x,   y+1), self.get_pixel(x,   y+2),
+            self.get_pixel(x+1, y),   self.get_pixel(x+1, y+1), self.get_pixel(x+1, y+2),
+            self.get_pixel(x,   y+3), self.get_pixel(x+1, y+3),
+        ]))
+    }
+
+    /// Render the canvas like `Canvas::render`, with coordinates labelled.
+    ///
+    /// Each line of braille is prefixed with the pixel row of its top edge,
+    /// and a header line labels the pixel column of every 10th character.
+    /// This makes it easy to match up parts of the output with coordinates.
+    pub fn render_with_coordinates(&self) -> String {
+        let label_width = (self.height - 1).max(0).to_string().len();
+        let cols = (self.width as usize).div_ceil(2);
+
+        let mut header = " ".repeat(label_width + 1);
+        let mut col = 0;
+        while col < cols {
+            let label = (2 * col).to_string();
+            if col + label.len() > cols {
+                break;
+            }
+            header += &format!("{:<10}", label);
+            col += 10;
+        }
+        let mut out = header.trim_end().to_string();
+        out.push('\n');
+
+        for (line, y) in self.render().lines().zip((0..).step_by(4)) {
+            out += &format!("{:>width$} {}\n", y, line, width = label_width);
+        }
+        out
+    }
+
+    /// Render the canvas with one character per pixel: `#` for on pixels and
+    /// a space for off pixels.
+    ///
+    /// Unlike `Canvas::render`, this only ever emits 7-bit ASCII.
+    pub fn render_ascii(&self) -> String {
+        self.render_with_chars('#', ' ')
+    }
+
+    /// Render the canvas with one character per pixel, using `on` and `off`
+    /// for on and off pixels respectively.
+    pub fn render_with_chars(&self, on: char, off: char) -> String {
+        let mut out = String::with_capacity(self.pixel_count() + self.height as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                out.push(if self.get_pixel(x, y) != 0 { on } else { off });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Count the pixels that are off and on, as `[off, on]`.
+    ///
+    /// Any nonzero color counts as on, the same as when rendering.
+    pub fn pixel_histogram(&self) -> [usize; 2] {
+        let on = bits::count(&self.pixels, 0..self.pixel_count());
+        [self.pixel_count() - on, on]
+    }
+
+    /// Each row of pixels, top to bottom, as its runs of equal pixels from
+    /// left to right: `(value, length)`, with 1 for on and 0 for off.
+    ///
+    /// The lengths in each row add up to the width, and neighboring runs
+    /// always have different values.
+    pub fn pixel_run_length(&self) -> Vec<Vec<(u8, usize)>> {
+        let width = self.width as usize;
+        (0..self.height as usize).map(|y| {
+            let mut runs: Vec<(u8, usize)> = vec![];
+            for i in y * width..(y + 1) * width {
+                let pixel = self.pixel_at(i) as u8;
+                match runs.last_mut() {
+                    Some(&mut (value, ref mut length)) if value == pixel => *length += 1,
+                    _ => runs.push((pixel, 1)),
+                }
+            }
+            runs
+        }).collect()
+    }
+
+    /// A short hash of the canvas's size and pixels, for checking that a
+    /// drawing comes out the same as it used to.
+    ///
+    /// This is the 64-bit FNV-1a hash of the width and then the height, each
+    /// as 4 little-endian bytes, followed by the bytes of `to_bitvec`. Any
+    /// nonzero color counts as on. It will only ever change in a release that
+    /// says so, as a breaking change.
+    pub fn fingerprint(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let size = [(self.width as u32).to_le_bytes(), (self.height as u32).to_le_bytes()];
+        size.iter().flatten()
+            .chain(self.to_bitvec().iter())
+            .fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+    }
+
+    /// `fingerprint`, as 16 hex digits.
+    pub fn fingerprint_hex(&self) -> String {
+        format!("{:016x}", self.fingerprint())
+    }
+
+    /// Measure the canvas and the size of its braille rendering, without
+    /// rendering it.
+    pub fn render_statistics(&self) -> RenderStats {
+        let [clear_pixels, set_pixels] = self.pixel_histogram();
+
+        // `render` uses a character for every 2x4 cell, even partial ones.
+        let rows = (self.height as usize).div_ceil(4);
+        let cols = (self.width as usize).div_ceil(2);
+        let density = if self.pixel_count() == 0 {
+            0.0
+        } else {
+            set_pixels as f32 / self.pixel_count() as f32
+        };
+
+        RenderStats {
+            set_pixels,
+            clear_pixels,
+            braille_chars: rows * cols,
+            rows,
+            density,
+        }
+    }
+
+    /// An escape sequence asking the terminal to resize itself to fit the
+    /// braille render exactly, one character for each 2x4 cell of pixels.
+    ///
+    /// That's `width / 2` columns by `height / 4` rows, rounded up for
+    /// partial cells. The sequence is xterm's window manipulation
+    /// `CSI 8 ; rows ; cols t`, which many terminals ignore, or only honor
+    /// when configured to, so treat it as a hint.
+    pub fn write_size_hint(&self) -> String {
+        let cols = (self.width as u32).div_ceil(2);
+        let rows = (self.height as u32).div_ceil(4);
+        format!("\x1b[8;{};{}t", rows, cols)
+    }
+
+    /// Time `iterations` calls to `render`, for profiling the render path.
+    ///
+    /// Each render is passed through `std::hint::black_box`, so the optimizer
+    /// can't skip the work just because the strings are thrown away.
+    #[cfg(feature = "std")]
+    pub fn benchmark_render(&self, iterations: u32) -> Duration {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            hint::black_box(hint::black_box(self).render());
+        }
+        start.elapsed()
+    }
+
+    /// Render the canvas using Unicode half blocks, with one character for
+    /// each 1x2 group of pixels.
+    ///
+    /// These are much more widely supported than braille, at the cost of
+    /// a quarter of the resolution.
+    pub fn render_blocks(&self) -> String {
+        let mut out = String::with_capacity(self.pixel_count());
+        for y in (0..self.height).step_by(2) {
+            for x in 0..self.width {
+                let top    = self.get_pixel(x, y)   != 0;
+                let bottom = self.get_pixel(x, y+1) != 0;
+                out.push(match (top, bottom) {
+                    (false, false) => ' ',
+                    (true,  false) => '▀',
+                    (false, true)  => '▄',
+                    (true,  true)  => '█',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Numbers describing a canvas and its braille rendering, from
+/// `Canvas::render_statistics`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderStats {
+    /// Pixels that are on.
+    pub set_pixels:    usize,
+    /// Pixels that are off.
+    pub clear_pixels:  usize,
+    /// Braille characters that `Canvas::render` emits, not counting newlines.
+    pub braille_chars: usize,
+    /// Lines of braille that `Canvas::render` emits.
+    pub rows:          usize,
+    /// The fraction of pixels that are on, or 0 for an empty canvas.
+    pub density:       f32,
+}
+
+/// How the random perturbations of each square in Schotter are distributed.
+///
+/// Each square is rotated and displaced by values drawn from this
+/// distribution, scaled by how far down the image the square's row is.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JitterDistribution {
+    /// Uniform in `[-factor, factor]`. This is what `lolwut5.c` does.
+    Uniform,
+
+    /// Normally distributed with a standard deviation of
+    /// `sigma_scale * factor`. Samples are clamped to 3 standard deviations
+    /// so that no square flies off absurdly far.
+    Normal {
+        sigma_scale: f32,
+    },
+}
+
+impl JitterDistribution {
+    /// Draw a single perturbation for a row with the given chaos `factor`.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, factor: f32) -> f32 {
+        match *self {
+            JitterDistribution::Uniform => {
+                let mut r: f32 = rng.gen::<f32>() * factor;
+                if rng.gen() { r = -r; }
+                r
+            }
+            JitterDistribution::Normal { sigma_scale } => {
+                // Box-Muller transform. `u1` must not be 0, since we take its
+                // log, so flip the usual [0, 1) range to (0, 1].
+                let u1: f32 = 1.0 - rng.gen::<f32>();
+                let u2: f32 = rng.gen::<f32>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+                let sigma = sigma_scale * factor;
+                (z * sigma).max(-3.0 * sigma).min(3.0 * sigma)
+            }
+        }
+    }
+}
+
+/// Which way `Canvas::draw_schotter_mirrored` reflects the artwork.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MirrorAxis {
+    /// The left half is reflected into the right half.
+    Horizontal,
+    /// The top half is reflected into the bottom half.
+    Vertical,
+    /// The top-left quadrant is reflected into all the others.
+    Both,
+}
+
+/// The parameters controlling how "Schotter" is drawn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SchotterConfig {
+    /// Width of the artwork in console columns. Each column is 2 pixels.
+    pub console_cols:    i32,
+    /// Number of squares in each row.
+    pub squares_per_row: i32,
+    /// Number of squares in each column.
+    pub squares_per_col: i32,
+    /// How the position and rotation of each square is perturbed.
+    pub jitter:          JitterDistribution,
+    /// Whether the size of each square is perturbed too. When set, each
+    /// square's side is scaled by `1.0 + r`, where `r` is drawn from
+    /// `jitter` just like the other perturbations.
+    pub scale_jitter:    bool,
+    /// Whether to draw the attribution into the canvas, below the artwork.
+    pub with_caption:    bool,
+    /// Whether to round each square's center and size to whole pixels before
+    /// drawing it, like the original does. With many small squares, this
+    /// makes most of them collapse into the same few shapes.
+    pub snap_to_pixels:  bool,
+}
+
+impl SchotterConfig {
+    /// Create a configuration with the classic uniform jitter.
+    pub fn new(console_cols: i32, squares_per_row: i32, squares_per_col: i32)
+        -> SchotterConfig
+    {
+        SchotterConfig {
+            console_cols,
+            squares_per_row,
+            squares_per_col,
+            jitter: JitterDistribution::Uniform,
+            scale_jitter: false,
+            with_caption: false,
+            snap_to_pixels: false,
+        }
+    }
+}
+
+impl Default for SchotterConfig {
+    /// The same parameters that Redis uses by default.
+    fn default() -> SchotterConfig {
+        SchotterConfig::new(66, 8, 12)
+    }
+}
+
+/// Clamp the pixels from `a` to `b`, inclusive and in either order, to
+/// `0..size`. Returns `None` if none of them are in range.
+fn clamp_span(a: i32, b: i32, size: i32) -> Option<Range<usize>> {
+    let start = a.min(b).max(0);
+    let end = a.max(b).min(size - 1);
+    if start <= end {
+        Some(start as usize..end as usize + 1)
+    } else {
+        None
+    }
+}
+
+/// Work out which pixels in `cols` and `rows` are inside the polygon with
+/// corners `points`, by the even-odd rule, row by row.
+fn polygon_mask(points: &[(i32, i32)], cols: Range<usize>, rows: Range<usize>) -> Vec<bool> {
+    let w = cols.len();
+    let mut mask = vec![false; w * rows.len()];
+    let mut crossings = Vec::new();
+    for (row, y) in rows.enumerate() {
+        // Where the row crosses the edges. Each edge counts from its top end
+        // but not its bottom one, so corners aren't counted twice.
+        let y = y as f32;
+        crossings.clear();
+        for (j, p) in points.iter().enumerate() {
+            let q = points[(j + 1) % points.len()];
+            let (p, q) = ((p.0 as f32, p.1 as f32), (q.0 as f32, q.1 as f32));
+            if (p.1 > y) != (q.1 > y) {
+                crossings.push(p.0 + (y - p.1) * (q.0 - p.0) / (q.1 - p.1));
+            }
+        }
+        crossings.sort_by(f32::total_cmp);
+
+        for span in crossings.chunks_exact(2) {
+            let start = (span[0].ceil() as i64).max(cols.start as i64);
+            let end = (span[1].floor() as i64 + 1).min(cols.end as i64);
+            for x in start..end {
+                mask[row * w + x as usize - cols.start] = true;
+            }
+        }
+    }
+    mask
+}
+
+/// Compute the corners of the square drawn by `Canvas::draw_square`.
+fn square_corners(x: i32, y: i32, size: f32, angle: f32) -> [(i32, i32); 4] {
+    // `size`, as passed into this function, represents the scaling of a
+    // unit square.
+    // We will operate on four equally spaced points on a unit circle that
+    // represent our square's corners.
+    // We must adjust this `size` by the ratio between our square's diagonal
+    // and the radius of the circle that encloses it to get the correct
+    // scaling in the final square.
+    // The square has unit side lengths, and thus has a diagonal of sqrt(2).
+    let size = ((size as f64) / SQRT_2).round() as f32;
+
+    // We construct the four corners of the square by using our parametric
+    // equations for the circle at four equally-spaced `k` values.
+    let mut points: [(i32, i32); 4] = Default::default();
+    // The first point of a non-rotated square is at t=PI/4. When we rotate
+    // the square, we just offset this initial radian value.
+    let mut k = PI/4.0 + angle;
+    for point in points.iter_mut() {
+        point.0 = (k.sin() * size + x as f32).round() as i32;
+        point.1 = (k.cos() * size + y as f32).round() as i32;
+        k += PI/2.0;
+    }
+    points
+}
+
+/// Compute the corners of the square drawn by `Canvas::draw_square_f`.
+///
+/// This is `square_corners` without rounding the center and radius first.
+fn square_corners_f(x: f32, y: f32, size: f32, angle: f32) -> [(i32, i32); 4] {
+    let radius = (size as f64 / SQRT_2) as f32;
+
+    let mut points: [(i32, i32); 4] = Default::default();
+    let mut k = PI/4.0 + angle;
+    for point in points.iter_mut() {
+        point.0 = (k.sin() * radius + x).round() as i32;
+        point.1 = (k.cos() * radius + y).round() as i32;
+        k += PI/2.0;
+    }
+    points
+}
+
+/// The sizing math shared by everything that lays out Schotter.
+struct SchotterLayout {
+    needed_width:  i32,
+    needed_height: i32,
+    padding:       f32,
+    square_side:   f32,
+}
+
+impl SchotterLayout {
+    fn compute(console_cols: i32, squares_per_row: i32, squares_per_col: i32)
+        -> Result<SchotterLayout, CanvasError>
+    {
+        if console_cols <= 0 {
+            return Err(InvalidParameter { name: "console_cols" });
+        }
+        if squares_per_row <= 0 {
+            return Err(InvalidParameter { name: "squares_per_row" });
+        }
+        if squares_per_col <= 0 {
+            return Err(InvalidParameter { name: "squares_per_col" });
+        }
+
+        // Each console column holds a braille character, which is 2 pixels
+        // wide. Tiny canvases don't get any padding - there's no room for it.
+        let needed_width:  i32 = 2 * console_cols;
+        let padding:       f32 = if needed_width > 4 { 2.0 } else { 0.0 };
+        let square_side:   f32 = (needed_width as f32 - 2.0 * padding)
+                                   / squares_per_row as f32;
+        let needed_height: i32 = (square_side * squares_per_col as f32
+                                   + 2.0 * padding).round() as i32;
+
+        Ok(SchotterLayout {
+            needed_width,
+            needed_height,
+            padding,
+            square_side,
+        })
+    }
+
+    /// Log the layout as a debug event, when the `tracing` feature is on.
+    fn trace(&self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(needed_width = self.needed_width,
+                        needed_height = self.needed_height,
+                        padding = self.padding,
+                        square_side = self.square_side,
+                        "schotter layout");
+    }
+}
+
+/// Compute the `(width, height)` in pixels of the canvas that
+/// `Canvas::draw_schotter` needs for the given arguments.
+///
+/// Use this to allocate your own canvas, or to lay out other content around
+/// the artwork. All arguments must be positive.
+pub fn schotter_dimensions(console_cols: i32,
+                           squares_per_row: i32,
+                           squares_per_col: i32)
+    -> Result<(u32, u32), CanvasError>
+{
+    let layout = SchotterLayout::compute(console_cols,
+                                         squares_per_row,
+                                         squares_per_col)?;
+    Ok((layout.needed_width as u32, layout.needed_height as u32))
+}
+
+/// Pack the pixels of a 2x4 cell into the byte `translate_pixels_group`
+/// takes, with any non-zero pixel as a set bit.
+///
+/// `pixels` are in bit order: down the left column, then down the right, and
+/// then the bottom row, which braille added later.
+fn pixels_group_byte(pixels: [u8; 8]) -> u8 {
+    pixels.iter()
+          .enumerate()
+          .fold(0, |byte, (bit, &pixel)| byte | (((pixel != 0) as u8) << bit))
+}
+
+/// Translate a group of 8 pixels (2x4 rectangle) into their corresponding
+/// braille character.
+///
+/// Each bit of `byte` should correspond to the pixels arranged as follows:
+/// ```norun
+/// [0] [3]
+/// [1] [4]
+/// [2] [5]
+/// [6] [7]
+/// ```
+pub fn translate_pixels_group(byte: u8) -> char {
+    // See: https://en.wikipedia.org/wiki/Braille_Patterns
+
+    // Braille has a standard 6-dot cell for conveying symbols, which was later
+    // expanded into the 8-cell. Each of the 8 cells (as labeled above) can be
+    // either raised or not, hence the obvious mapping to a byte.
+    // Note that some fonts may render these with hollow dots in place of space,
+    // and that will change the appearance of the script.
+
+    // Convert to unicode. This is in the U0800-UFFFF range,
+    // so we need to emit it like this in three bytes:
+    //      1110_xxxx 10xx_xxxx 10xx_xxxx
+    let code = 0x2800 + byte as u32;
+    let out: [u8; 3] = [
+        0xe0 | ((code >> 12) as u8),
         0x80 | ((code >>  6) as u8 & 0x3f),
         0x80 | ( code        as u8 & 0x3f),
     ];
     unsafe {
-        str::from_utf8_unchecked(&out).chars().nth(0).unwrap_or('☂')
+        str::from_utf8_unchecked(&out).chars().next().unwrap_or('☂')
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    /// A canvas `width` pixels wide, with the pixels `values`, row by row.
+    fn canvas_of(width: i32, values: &[u8]) -> Canvas {
+        let height = values.len() as i32 / width;
+        Canvas::from_fn(width as u32, height as u32, |x, y| values[(y * width + x) as usize])
+            .unwrap()
+    }
+
+    /// A `side` pixel square canvas with a tiling drawn in the middle of it
+    /// by `draw`, which is `Canvas::draw_penrose_tiling` or the like.
+    pub(crate) fn centered_tiling(draw: fn(&mut Canvas, i32, i32, f32, u32, u8),
+                                  side: u32,
+                                  size: f32,
+                                  generations: u32)
+        -> Canvas
+    {
+        let mut canvas = Canvas::create(side, side).unwrap();
+        let center = side as i32 / 2;
+        draw(&mut canvas, center, center, size, generations, 1);
+        canvas
+    }
+
+    /// The original `render`, a cell at a time through `get_pixel`.
+    fn render_by_pixel(canvas: &Canvas) -> String {
+        let mut out = String::new();
+        for y in (0..canvas.height).step_by(4) {
+            for x in (0..canvas.width).step_by(2) {
+                let mut byte: u8 = 0;
+                if canvas.get_pixel(x,   y)   != 0 { byte |= 1 << 0; }
+                if canvas.get_pixel(x,   y+1) != 0 { byte |= 1 << 1; }
+                if canvas.get_pixel(x,   y+2) != 0 { byte |= 1 << 2; }
+                if canvas.get_pixel(x+1, y)   != 0 { byte |= 1 << 3; }
+                if canvas.get_pixel(x+1, y+1) != 0 { byte |= 1 << 4; }
+                if canvas.get_pixel(x+1, y+2) != 0 { byte |= 1 << 5; }
+                if canvas.get_pixel(x,   y+3) != 0 { byte |= 1 << 6; }
+                if canvas.get_pixel(x+1, y+3) != 0 { byte |= 1 << 7; }
+                out.push(translate_pixels_group(byte));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    #[test]
+    fn check_render_matches_per_pixel() {
+        let mut rng = StdRng::seed_from_u64(53);
+        // Every combination of partial cells along the edges, and then some.
+        for width in 0..11 {
+            for height in 0..11 {
+                for _ in 0..4 {
+                    let canvas = Canvas::from_fn(width, height, |_, _| rng.gen_range(0, 3))
+                                        .unwrap();
+                    assert_eq!(canvas.render(), render_by_pixel(&canvas),
+                               "{}x{}", width, height);
+                }
+            }
+        }
+
+        let canvas = Canvas::create_and_render_schotter_with(&SchotterConfig::new(101, 7, 9),
+                                                             &mut rng)
+                         .unwrap();
+        assert_eq!(canvas.render(), render_by_pixel(&canvas));
+    }
+
+    #[test]
+    fn check_translate_pixels_group() {
+        let braille = [
+            '\u{2800}', // BRAILLE PATTERN BLANK
+            // 6-dot patterns
+                 '⠁', '⠂', '⠃', '⠄', '⠅', '⠆', '⠇',
+            '⠈', '⠉', '⠊', '⠋', '⠌', '⠍', '⠎', '⠏',
+            '⠐', '⠑', '⠒', '⠓', '⠔', '⠕', '⠖', '⠗',
+            '⠘', '⠙', '⠚', '⠛', '⠜', '⠝', '⠞', '⠟',
+            '⠠', '⠡', '⠢', '⠣', '⠤', '⠥', '⠦', '⠧',
+            '⠨', '⠩', '⠪', '⠫', '⠬', '⠭', '⠮', '⠯',
+            '⠰', '⠱', '⠲', '⠳', '⠴', '⠵', '⠶', '⠷',
+            '⠸', '⠹', '⠺', '⠻', '⠼', '⠽', '⠾', '⠿',
+            // 8-dot patterns
+            '⡀', '⡁', '⡂', '⡃', '⡄', '⡅', '⡆', '⡇',
+            '⡈', '⡉', '⡊', '⡋', '⡌', '⡍', '⡎', '⡏',
+            '⡐', '⡑', '⡒', '⡓', '⡔', '⡕', '⡖', '⡗',
+            '⡘', '⡙', '⡚', '⡛', '⡜', '⡝', '⡞', '⡟',
+            '⡠', '⡡', '⡢', '⡣', '⡤', '⡥', '⡦', '⡧',
+            '⡨', '⡩', '⡪', '⡫', '⡬', '⡭', '⡮', '⡯',
+            '⡰', '⡱', '⡲', '⡳', '⡴', '⡵', '⡶', '⡷',
+            '⡸', '⡹', '⡺', '⡻', '⡼', '⡽', '⡾', '⡿',
+            '⢀', '⢁', '⢂', '⢃', '⢄', '⢅', '⢆', '⢇',
+            '⢈', '⢉', '⢊', '⢋', '⢌', '⢍', '⢎', '⢏',
+            '⢐', '⢑', '⢒', '⢓', '⢔', '⢕', '⢖', '⢗',
+            '⢘', '⢙', '⢚', '⢛', '⢜', '⢝', '⢞', '⢟',
+            '⢠', '⢡', '⢢', '⢣', '⢤', '⢥', '⢦', '⢧',
+            '⢨', '⢩', '⢪', '⢫', '⢬', '⢭', '⢮', '⢯',
+            '⢰', '⢱', '⢲', '⢳', '⢴', '⢵', '⢶', '⢷',
+            '⢸', '⢹', '⢺', '⢻', '⢼', '⢽', '⢾', '⢿',
+            '⣀', '⣁', '⣂', '⣃', '⣄', '⣅', '⣆', '⣇',
+            '⣈', '⣉', '⣊', '⣋', '⣌', '⣍', '⣎', '⣏',
+            '⣐', '⣑', '⣒', '⣓', '⣔', '⣕', '⣖', '⣗',
+            '⣘', '⣙', '⣚', '⣛', '⣜', '⣝', '⣞', '⣟',
+            '⣠', '⣡', '⣢', '⣣', '⣤', '⣥', '⣦', '⣧',
+            '⣨', '⣩', '⣪', '⣫', '⣬', '⣭', '⣮', '⣯',
+            '⣰', '⣱', '⣲', '⣳', '⣴', '⣵', '⣶', '⣷',
+            '⣸', '⣹', '⣺', '⣻', '⣼', '⣽', '⣾', '⣿',
+        ];
+
+        // Exhaustively test every possible u8 value.
+        for hex in 0..0x100i32 {
+            let hex = hex as u8;
+            let c = braille[hex as usize];
+            let actual = translate_pixels_group(hex);
+            println!("0x{}, '{}' =? '{}'", hex, c, actual);
+            assert_eq!(c, actual,
+                       "'{}'!='{}' (0x{:x} != 0x{:x})",
+                       c, actual,
+                       c as i32, actual as i32);
+        }
+    }
+
+    /// `draw_schotter` as it was originally transcribed, before the jitter
+    /// was made configurable.
+    fn legacy_draw_schotter<R: Rng>(canvas: &mut Canvas,
+                                    console_cols:    i32,
+                                    squares_per_row: i32,
+                                    squares_per_col: i32,
+                                    rng: &mut R)
+    {
+        let needed_width:  i32 = 2 * console_cols;
+        let padding:       f32 = if needed_width > 4 { 2.0 } else { 0.0 };
+        let square_side:   f32 = (needed_width as f32 - 2.0 * padding)
+                                   / squares_per_row as f32;
+
+        for y in 0..squares_per_col {
+            let factor = (y + 1) as f32 / (squares_per_col + 1) as f32;
+            for x in 0..squares_per_row {
+                let mut sx = (x as f32 * square_side +
+                              square_side/2.0 + padding).round() as i32;
+                let mut sy = (y as f32 * square_side +
+                              square_side/2.0 + padding).round() as i32;
+
+                let mut r1: f32 = rng.gen::<f32>() * factor;
+                if rng.gen() { r1 = -r1; }
+
+                let mut r2: f32 = rng.gen::<f32>() * factor;
+                if rng.gen() { r2 = -r2; }
+
+                let mut r3: f32 = rng.gen::<f32>() * factor;
+                if rng.gen() { r3 = -r3; }
+
+                let angle = r1;
+                sx += (r2 * square_side / 3.0).round() as i32;
+                sy += (r3 * square_side / 3.0).round() as i32;
+                canvas.draw_square(sx, sy, square_side, angle);
+            }
+        }
+    }
+
+    #[test]
+    fn check_uniform_jitter_matches_legacy() {
+        for &(cols, per_row, per_col) in [(66, 8, 12), (40, 5, 7), (3, 2, 2)].iter() {
+            let (width, height) = schotter_dimensions(cols, per_row, per_col)
+                                      .unwrap();
+
+            let mut expected = Canvas::create(width, height).unwrap();
+            let mut rng = StdRng::seed_from_u64(0x5c07);
+            legacy_draw_schotter(&mut expected, cols, per_row, per_col, &mut rng);
+
+            let mut actual = Canvas::create(width, height).unwrap();
+            let mut rng = StdRng::seed_from_u64(0x5c07);
+            let config = SchotterConfig {
+                snap_to_pixels: true,
+                ..SchotterConfig::new(cols, per_row, per_col)
+            };
+            actual.draw_schotter_with(&config, &mut rng).unwrap();
+
+            assert_eq!(expected.pixels, actual.pixels);
+        }
+    }
+
+    /// Count the neighbours in the top row of `config` that are the exact
+    /// same shape, just moved over.
+    fn identical_neighbours(config: &SchotterConfig) -> usize {
+        let (width, height) = schotter_dimensions(config.console_cols,
+                                                  config.squares_per_row,
+                                                  config.squares_per_col)
+                                  .unwrap();
+        let canvas = Canvas::create(width, height).unwrap();
+        let mut rng = StdRng::seed_from_u64(0x5c07);
+        let squares = canvas.schotter_squares(config, 0..1, &mut rng, false, None)
+                            .unwrap();
+        let shape = |corners: &[(i32, i32); 4]| {
+            let (x0, y0) = corners[0];
+            corners.iter().map(|&(x, y)| (x - x0, y - y0)).collect::<Vec<_>>()
+        };
+        squares.windows(2).filter(|pair| shape(&pair[0]) == shape(&pair[1])).count()
+    }
+
+    #[test]
+    fn check_small_squares_keep_their_shape() {
+        let config = SchotterConfig::new(66, 24, 36);
+        let snapped = SchotterConfig { snap_to_pixels: true, ..config };
+        // Snapped to whole pixels, every square in the calm top row is the
+        // same shape...
+        assert_eq!(identical_neighbours(&snapped), 23);
+        // ...while sub-pixel placement lets most of them differ.
+        assert!(identical_neighbours(&config) < 12);
+    }
+
+    #[test]
+    fn check_normal_jitter_sigma() {
+        let mut rng = StdRng::seed_from_u64(1968);
+        for &sigma_scale in [0.25, 0.5, 1.0].iter() {
+            let jitter = JitterDistribution::Normal { sigma_scale };
+            for &factor in [0.5, 1.0].iter() {
+                let sigma = sigma_scale * factor;
+                let n = 100_000;
+                let samples: Vec<f32> = (0..n).map(|_| jitter.sample(&mut rng, factor))
+                                              .collect();
+                for &s in samples.iter() {
+                    assert!(s.abs() <= 3.0 * sigma);
+                }
+
+                let mean = samples.iter().sum::<f32>() / n as f32;
+                let var = samples.iter()
+                                 .map(|s| (s - mean) * (s - mean))
+                                 .sum::<f32>() / n as f32;
+                let std_dev = var.sqrt();
+                // Clamping to 3 sigma shaves a little over 1% off.
+                assert!((std_dev - sigma).abs() < 0.03 * sigma,
+                        "std_dev = {}, sigma = {}", std_dev, sigma);
+                assert!(mean.abs() < 0.02 * sigma, "mean = {}", mean);
+            }
+        }
+    }
+
+    #[test]
+    fn check_normal_jitter_draws_schotter() {
+        let config = SchotterConfig {
+            jitter: JitterDistribution::Normal { sigma_scale: 0.5 },
+            ..SchotterConfig::default()
+        };
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+        let mut canvas = Canvas::create(width, height).unwrap();
+        canvas.draw_schotter_with(&config, &mut rng).unwrap();
+        assert!(canvas.pixels.iter().any(|&p| p != 0));
+    }
+
+    #[test]
+    fn check_schotter_rows_split() {
+        let config = SchotterConfig::default();
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+
+        let mut whole = Canvas::create(width, height).unwrap();
+        let mut rng = StdRng::seed_from_u64(1234);
+        whole.draw_schotter_rows(&config, 0..12, &mut rng).unwrap();
+
+        for split in 0..=12 {
+            let mut parts = Canvas::create(width, height).unwrap();
+            let mut rng = StdRng::seed_from_u64(1234);
+            parts.draw_schotter_rows(&config, 0..split, &mut rng).unwrap();
+            parts.draw_schotter_rows(&config, split..12, &mut rng).unwrap();
+            assert!(whole.pixels == parts.pixels, "split = {}", split);
+        }
+
+        // Three pieces work just as well as two.
+        let mut parts = Canvas::create(width, height).unwrap();
+        let mut rng = StdRng::seed_from_u64(1234);
+        for rows in [0..3, 3..4, 4..12].iter() {
+            parts.draw_schotter_rows(&config, rows.clone(), &mut rng).unwrap();
+        }
+        assert!(whole.pixels == parts.pixels);
+    }
+
+    #[test]
+    fn check_schotter_rows_out_of_range() {
+        let config = SchotterConfig::default();
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+        let mut canvas = Canvas::create(width, height).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let bad = [-1..3, 0..13, 5..4, 12..13];
+        for rows in bad.iter() {
+            match canvas.draw_schotter_rows(&config, rows.clone(), &mut rng) {
+                Err(InvalidParameter { name: "rows" }) => {}
+                other => panic!("{:?}: expected an error, got {:?}", rows, other),
+            }
+        }
+        assert!(canvas.draw_schotter_rows(&config, 12..12, &mut rng).is_ok());
+    }
+
+    #[test]
+    fn check_schotter_layers() {
+        let config = SchotterConfig::default();
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+        let mut whole = Canvas::create(width, height).unwrap();
+        whole.draw_schotter_with(&config, &mut StdRng::seed_from_u64(99))
+             .unwrap();
+
+        for &count in [1, 3, 5, 12, 20].iter() {
+            let layers = Canvas::draw_schotter_layers(66, 8, 12, count, 99)
+                             .unwrap();
+            assert_eq!(layers.len(), count as usize);
+
+            let mut union = vec![0; whole.pixels.len()];
+            for layer in layers.iter() {
+                assert_eq!((layer.width, layer.height), (whole.width, whole.height));
+                for (u, &p) in union.iter_mut().zip(layer.pixels.iter()) {
+                    *u |= p;
+                }
+            }
+            assert!(union == whole.pixels, "count = {}", count);
+        }
+
+        // The calm layer is at the top, and the chaotic one at the bottom.
+        let layers = Canvas::draw_schotter_layers(66, 8, 12, 2, 99).unwrap();
+        let quarter = whole.pixels.len() / 4;
+        assert!(layers[0].pixels[3 * quarter..].iter().all(|&p| p == 0));
+        assert!(layers[1].pixels[..quarter].iter().all(|&p| p == 0));
+
+        assert!(Canvas::draw_schotter_layers(66, 8, 12, 0, 99).is_err());
+    }
+
+    #[test]
+    fn check_schotter_rgb() {
+        let config = SchotterConfig::default();
+        let single = |seed: u64| {
+            Canvas::create_and_render_schotter_with(&config, &mut StdRng::seed_from_u64(seed))
+                   .unwrap()
+        };
+
+        let (r, g, b) = Canvas::draw_schotter_rgb(66, 8, 12, [1, 2, 3]).unwrap();
+        assert!(r.pixels == single(1).pixels);
+        assert!(g.pixels == single(2).pixels);
+        assert!(b.pixels == single(3).pixels);
+        assert!(r.pixels != g.pixels && g.pixels != b.pixels);
+
+        assert!(Canvas::draw_schotter_rgb(0, 8, 12, [1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn check_schotter_caption() {
+        let plain = SchotterConfig::default();
+        let captioned = SchotterConfig { with_caption: true, ..plain };
+
+        let mut rng = StdRng::seed_from_u64(1968);
+        let a = Canvas::create_and_render_schotter_with(&plain, &mut rng).unwrap();
+        let mut rng = StdRng::seed_from_u64(1968);
+        let b = Canvas::create_and_render_schotter_with(&captioned, &mut rng)
+                    .unwrap();
+
+        assert_eq!(b.width, a.width);
+        assert_eq!(b.height, a.height + CAPTION_HEIGHT as i32);
+
+        // The artwork is untouched, and the caption is all in the new strip.
+        let (a, b) = (a.pixel_bytes(), b.pixel_bytes());
+        assert!(a[..] == b[..a.len()]);
+        assert!(b[a.len()..].iter().any(|&p| p != 0));
+    }
+
+    #[test]
+    fn check_clear_and_fill() {
+        let mut canvas = Canvas::create(3, 2).unwrap();
+        canvas.fill();
+        assert!(canvas.pixel_bytes().iter().all(|&p| p == 1));
+        canvas.clear();
+        assert!(canvas.pixels.iter().all(|&p| p == 0));
+
+        for &value in [0, 1, 7, 255].iter() {
+            canvas.fill_value(value);
+            for y in 0..2 {
+                for x in 0..3 {
+                    assert_eq!(canvas.get_pixel(x, y), (value != 0) as u8);
+                }
+            }
+        }
+
+        // An empty canvas has nothing to fill, which is fine.
+        let mut empty = Canvas::create(0, 0).unwrap();
+        empty.clear();
+        empty.fill();
+        empty.fill_value(3);
+        assert!(empty.pixels.is_empty());
+    }
+
+    #[test]
+    fn check_shift() {
+        let mut canvas = canvas_of(4, &[1, 0, 0, 1,
+                                        0, 1, 1, 0,
+                                        1, 1, 0, 0]);
+        let original = canvas.clone();
+
+        canvas.shift(1, 0, 2);
+        assert_eq!(canvas.pixel_bytes(), [1, 1, 0, 0,
+                                          1, 0, 1, 1,
+                                          1, 1, 1, 0]);
+        canvas.shift(-1, 0, 0);
+        for y in 0..3 {
+            for x in 0..3 {
+                assert_eq!(canvas.get_pixel(x, y), original.get_pixel(x, y));
+            }
+            assert_eq!(canvas.get_pixel(3, y), 0);
+        }
+
+        let mut canvas = original.clone();
+        canvas.shift(-2, 1, 0);
+        assert_eq!(canvas.pixel_bytes(), [0, 0, 0, 0,
+                                          0, 1, 0, 0,
+                                          1, 0, 0, 0]);
+        let mut canvas = original.clone();
+        canvas.shift(0, -2, 0);
+        assert_eq!(canvas.pixel_bytes(), [1, 1, 0, 0,
+                                          0, 0, 0, 0,
+                                          0, 0, 0, 0]);
+
+        for &(dx, dy) in [(4, 0), (0, -3), (i32::MIN, 0), (0, i32::MAX)].iter() {
+            let mut canvas = original.clone();
+            canvas.shift(dx, dy, 1);
+            assert!(canvas.pixel_bytes().iter().all(|&p| p == 1), "({}, {})", dx, dy);
+        }
+
+        let mut canvas = original.clone();
+        canvas.shift(0, 0, 1);
+        assert_eq!(canvas.pixels, original.pixels);
+        Canvas::create(0, 0).unwrap().shift(1, 1, 1);
+
+        // Rows that span words move the same as short ones.
+        let wide = Canvas::from_fn(150, 5, |x, y| ((x * 7 + y * 13 + x * y) % 3 == 0) as u8)
+                       .unwrap();
+        for &(dx, dy) in [(1, 0), (-70, 2), (65, -1), (-1, -4), (149, 0)].iter() {
+            let mut canvas = wide.clone();
+            canvas.shift(dx, dy, 1);
+            let expected = Canvas::from_fn(150, 5, |x, y| {
+                let (sx, sy) = (x - dx, y - dy);
+                let inside = (0..150).contains(&sx) && (0..5).contains(&sy);
+                if inside { wide.get_pixel(sx, sy) } else { 1 }
+            }).unwrap();
+            assert!(canvas.pixels == expected.pixels, "({}, {})", dx, dy);
+        }
+    }
+
+    #[test]
+    fn check_flips() {
+        let canvas = canvas_of(3, &[1, 0, 0,
+                                    1, 1, 0,
+                                    0, 0, 0]);
+        let mut h = canvas.clone();
+        h.flip_horizontal();
+        assert_eq!(h.pixel_bytes(), [0, 0, 1,
+                                     0, 1, 1,
+                                     0, 0, 0]);
+        let mut v = canvas.clone();
+        v.flip_vertical();
+        assert_eq!(v.pixel_bytes(), [0, 0, 0,
+                                     1, 1, 0,
+                                     1, 0, 0]);
+
+        v.flip_vertical();
+        assert_eq!(v.pixels, canvas.pixels);
+
+        // Rows that span words flip the same as short ones.
+        let pattern = |x: i32, y: i32| ((x * 7 + y * 13 + x * y) % 3 == 0) as u8;
+        let wide = Canvas::from_fn(150, 5, pattern).unwrap();
+        let mut h = wide.clone();
+        h.flip_horizontal();
+        assert!(h.pixels == Canvas::from_fn(150, 5, |x, y| pattern(149 - x, y)).unwrap().pixels);
+        let mut v = wide.clone();
+        v.flip_vertical();
+        assert!(v.pixels == Canvas::from_fn(150, 5, |x, y| pattern(x, 4 - y)).unwrap().pixels);
+    }
+
+    #[test]
+    fn check_rotate_in_place() {
+        // Rotating clockwise, the bottom-left corner ends up top-left.
+        fn rotated(canvas: &Canvas) -> Canvas {
+            let mut out = Canvas::create(canvas.height as u32, canvas.width as u32).unwrap();
+            for y in 0..canvas.height {
+                for x in 0..canvas.width {
+                    out.draw_pixel(canvas.height - 1 - y, x, canvas.get_pixel(x, y));
+                }
+            }
+            out
+        }
+
+        for &(width, height) in [(0, 0), (1, 1), (4, 4), (5, 5), (3, 5), (7, 2), (1, 9), (12, 8),
+                                 (65, 3), (40, 70)].iter() {
+            // No pattern that lines up with the rotation, so every pixel that
+            // lands in the wrong place shows.
+            let mut canvas = Canvas::from_fn(width, height, |x, y| {
+                ((x * 7 + y * 13 + x * y) % 3 == 0) as u8
+            }).unwrap();
+            let original = canvas.clone();
+
+            let expected = rotated(&canvas);
+            canvas.rotate_pixels_90_in_place();
+            assert_eq!((canvas.width, canvas.height), (expected.width, expected.height));
+            assert!(canvas.pixels == expected.pixels, "{}x{}", width, height);
+
+            for _ in 0..3 {
+                canvas.rotate_pixels_90_in_place();
+            }
+            assert!(canvas.pixels == original.pixels, "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn check_scaled() {
+        let canvas = canvas_of(3, &[1, 0, 1,
+                                    0, 1, 0]);
+
+        let big = canvas.scaled(2).unwrap();
+        assert_eq!((big.width, big.height), (6, 4));
+        assert_eq!(big.pixel_bytes(), [1, 1, 0, 0, 1, 1,
+                                       1, 1, 0, 0, 1, 1,
+                                       0, 0, 1, 1, 0, 0,
+                                       0, 0, 1, 1, 0, 0]);
+        assert!(canvas.scaled(1).unwrap().pixels == canvas.pixels);
+
+        for &factor in [0, u32::MAX, 1 << 20].iter() {
+            match canvas.scaled(factor) {
+                Err(InvalidParameter { name: "factor" }) => (),
+                other => panic!("Expected an error, got {:?}", other.map(|c| c.width)),
+            }
+        }
+    }
+
+    #[test]
+    fn check_tiled() {
+        let canvas = canvas_of(3, &[1, 0, 0,
+                                    1, 1, 0]);
+
+        let tiled = canvas.tiled(10, 7).unwrap();
+        assert_eq!((tiled.width, tiled.height), (10, 7));
+        for y in 0..7 {
+            for x in 0..10 {
+                assert_eq!(tiled.get_pixel(x, y), canvas.get_pixel(x % 3, y % 2),
+                           "({}, {})", x, y);
+            }
+        }
+
+        // Copies that span words line up too.
+        let pattern = |x: i32, y: i32| ((x * 7 + y * 13 + x * y) % 3 == 0) as u8;
+        let wide = Canvas::from_fn(70, 3, pattern).unwrap().tiled(200, 7).unwrap();
+        let expected = Canvas::from_fn(200, 7, |x, y| pattern(x % 70, y % 3)).unwrap();
+        assert!(wide.pixels == expected.pixels);
+
+        // Smaller than a single copy is just cropped.
+        assert_eq!(canvas.tiled(2, 1).unwrap().pixel_bytes(), [1, 0]);
+        assert!(canvas.tiled(0, 5).unwrap().pixels.is_empty());
+        assert!(canvas.tiled(5, 0).unwrap().pixels.is_empty());
+
+        match Canvas::create(0, 2).unwrap().tiled(4, 4) {
+            Err(CanvasTooSmall { actual_width: 0, .. }) => (),
+            other => panic!("Expected an error, got {:?}", other.map(|c| c.width)),
+        }
+        match canvas.tiled(1 << 16, 1 << 16) {
+            Err(InvalidParameter { name: "out_height" }) => (),
+            other => panic!("Expected an error, got {:?}", other.map(|c| c.width)),
+        }
+    }
+
+    #[test]
+    fn check_blit_clips() {
+        let mut src = Canvas::create(2, 2).unwrap();
+        src.fill();
+        let mut dst = Canvas::create(3, 3).unwrap();
+        dst.blit(&src, 2, -1);
+        assert_eq!(dst.pixel_bytes(), [0, 0, 1,
+                                       0, 0, 0,
+                                       0, 0, 0]);
+    }
+
+    #[test]
+    fn check_grid_schotter() {
+        let mut canvas = Canvas::create(90, 50).unwrap();
+        canvas.draw_grid_schotter(3, 2, 4, 10).unwrap();
+
+        // The cells are 30x25, so each artwork is 24 pixels square, centered.
+        let config = SchotterConfig::new(12, 4, 4);
+        for row in 0..2 {
+            for col in 0..3 {
+                let seed = 10 + (row * 3 + col) as u64;
+                let single = Canvas::create_and_render_schotter_with(
+                    &config, &mut StdRng::seed_from_u64(seed)).unwrap();
+                assert_eq!((single.width, single.height), (24, 24));
+
+                let (x0, y0) = (col * 30 + 3, row * 25);
+                for y in 0..24 {
+                    for x in 0..24 {
+                        assert_eq!(canvas.get_pixel(x0 + x, y0 + y), single.get_pixel(x, y),
+                                   "cell ({}, {}) at ({}, {})", col, row, x, y);
+                    }
+                }
+            }
+        }
+
+        // Different seeds make different cells.
+        let cell = |canvas: &Canvas, col: i32| {
+            (0..24).flat_map(|y| (0..24).map(move |x| (x, y)))
+                   .map(|(x, y)| canvas.get_pixel(col * 30 + 3 + x, y))
+                   .collect::<Vec<_>>()
+        };
+        assert_ne!(cell(&canvas, 0), cell(&canvas, 1));
+
+        assert!(canvas.draw_grid_schotter(0, 2, 4, 10).is_err());
+        assert!(canvas.draw_grid_schotter(3, 0, 4, 10).is_err());
+        assert!(canvas.draw_grid_schotter(3, 2, 0, 10).is_err());
+        match canvas.draw_grid_schotter(60, 2, 4, 10) {
+            Err(CanvasTooSmall { .. }) => (),
+            other => panic!("Expected CanvasTooSmall, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_schotter_mirrored() {
+        let config = SchotterConfig::new(20, 3, 4);
+        let (w, h) = schotter_dimensions(20, 3, 4).unwrap();
+        let (w, h) = (w as i32, h as i32);
+
+        for &axis in [MirrorAxis::Horizontal, MirrorAxis::Vertical, MirrorAxis::Both].iter() {
+            let mut canvas = Canvas::create(2 * w as u32, 2 * h as u32).unwrap();
+            let mut rng = StdRng::seed_from_u64(3);
+            canvas.draw_schotter_mirrored(axis, &config, &mut rng).unwrap();
+
+            for y in 0..h {
+                for x in 0..w {
+                    let p = canvas.get_pixel(x, y);
+                    let right = canvas.get_pixel(2 * w - 1 - x, y);
+                    let below = canvas.get_pixel(x, 2 * h - 1 - y);
+                    let diagonal = canvas.get_pixel(2 * w - 1 - x, 2 * h - 1 - y);
+                    match axis {
+                        MirrorAxis::Horizontal => {
+                            assert_eq!((right, below, diagonal), (p, 0, 0));
+                        }
+                        MirrorAxis::Vertical => {
+                            assert_eq!((right, below, diagonal), (0, p, 0));
+                        }
+                        MirrorAxis::Both => {
+                            assert_eq!((right, below, diagonal), (p, p, p));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut small = Canvas::create(2 * w as u32 - 1, h as u32).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(small.draw_schotter_mirrored(MirrorAxis::Horizontal, &config, &mut rng)
+                     .is_err());
+    }
+
+    #[test]
+    fn check_scale_jitter() {
+        let plain = SchotterConfig::default();
+        let scaled = SchotterConfig { scale_jitter: true, ..plain };
+
+        let render = |config: &SchotterConfig, rows: Range<i32>| {
+            let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+            let mut canvas = Canvas::create(width, height).unwrap();
+            let mut rng = StdRng::seed_from_u64(21);
+            canvas.draw_schotter_rows(config, rows, &mut rng).unwrap();
+            canvas
+        };
+
+        // Squares change size, and every square after the first is drawn with
+        // different random numbers.
+        assert!(render(&plain, 0..12).pixels != render(&scaled, 0..12).pixels);
+
+        // Size jitter still keeps row-by-row drawing consistent.
+        let mut rng = StdRng::seed_from_u64(21);
+        let mut parts = render(&scaled, 0..0);
+        parts.draw_schotter_rows(&scaled, 0..5, &mut rng).unwrap();
+        parts.draw_schotter_rows(&scaled, 5..12, &mut rng).unwrap();
+        assert!(parts.pixels == render(&scaled, 0..12).pixels);
+    }
+
+    #[test]
+    fn check_render_modes() {
+        let canvas = canvas_of(3, &[1, 0, 1,
+                                    1, 1, 0,
+                                    0, 1, 0]);
+        assert_eq!(canvas.render_ascii(), "# #\n## \n # \n");
+        assert_eq!(canvas.render_with_chars('x', '.'), "x.x\nxx.\n.x.\n");
+        assert_eq!(canvas.render_blocks(), "█▄▀\n ▀ \n");
+        assert_eq!(canvas.render(), "⠳⠁\n");
+    }
+
+    #[test]
+    fn check_overlaps_bounds() {
+        let canvas = Canvas::create(10, 10).unwrap();
+        assert!(canvas.overlaps_bounds(&[(2, 2), (5, 5)]));
+        assert!(canvas.overlaps_bounds(&[(-5, -5), (0, 0)]));
+        assert!(canvas.overlaps_bounds(&[(-5, 3), (20, 4)]));
+        assert!(canvas.overlaps_bounds(&[(9, 9), (12, 15)]));
+        assert!(!canvas.overlaps_bounds(&[(-5, -5), (-1, 5)]));
+        assert!(!canvas.overlaps_bounds(&[(10, 0), (15, 5)]));
+        assert!(!canvas.overlaps_bounds(&[(0, 10), (5, 12)]));
+        assert!(!canvas.overlaps_bounds(&[]));
+    }
+
+    #[test]
+    fn check_schotter_overlap_aware() {
+        // Wild jitter throws plenty of squares off of the canvas.
+        let config = SchotterConfig {
+            jitter: JitterDistribution::Normal { sigma_scale: 6.0 },
+            ..SchotterConfig::default()
+        };
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+
+        let mut plain = Canvas::create(width, height).unwrap();
+        plain.draw_schotter_with(&config, &mut StdRng::seed_from_u64(8)).unwrap();
+        let mut aware = Canvas::create(width, height).unwrap();
+        aware.draw_schotter_overlap_aware(&config, &mut StdRng::seed_from_u64(8))
+             .unwrap();
+        assert!(plain.pixels == aware.pixels);
+    }
+
+    #[test]
+    fn check_schotter_masked() {
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+        let draw = |mask: &Canvas| {
+            let mut canvas = Canvas::create(width, height).unwrap();
+            canvas.draw_schotter_masked(66, 8, 12, mask, &mut StdRng::seed_from_u64(3))
+                  .unwrap();
+            canvas
+        };
+
+        // A full mask changes nothing, and an empty one leaves a blank canvas.
+        let mut plain = Canvas::create(width, height).unwrap();
+        plain.draw_schotter_with(&SchotterConfig::new(66, 8, 12), &mut StdRng::seed_from_u64(3))
+             .unwrap();
+        let full = Canvas::from_fn(width, height, |_, _| 1).unwrap();
+        assert!(draw(&full).pixels == plain.pixels);
+        let empty = Canvas::create(width, height).unwrap();
+        assert!(draw(&empty).pixels.iter().all(|&px| px == 0));
+
+        // Masking off the right half keeps only squares on the left.
+        let half = Canvas::from_fn(width, height, |x, _| (x < width as i32 / 2) as u8).unwrap();
+        let left = draw(&half);
+        let right_edge = (width as i32 * 3 / 4..width as i32)
+            .flat_map(|x| (0..height as i32).map(move |y| (x, y)));
+        assert!(right_edge.clone().any(|(x, y)| plain.get_pixel(x, y) != 0));
+        assert!(right_edge.clone().all(|(x, y)| left.get_pixel(x, y) == 0));
+        assert!(left.pixels.iter().any(|&px| px != 0));
+
+        let small = Canvas::create(width - 1, height).unwrap();
+        let mut canvas = Canvas::create(width, height).unwrap();
+        match canvas.draw_schotter_masked(66, 8, 12, &small, &mut StdRng::seed_from_u64(3)) {
+            Err(InvalidParameter { name: "mask" }) => (),
+            other => panic!("Expected a mask error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_schotter_sine_row_offset() {
+        let (width, height) = schotter_dimensions(40, 8, 12).unwrap();
+        let draw = |config: &SchotterConfig, amplitude: f32| {
+            let mut canvas = Canvas::create(width, height).unwrap();
+            canvas.draw_schotter_sine_row_offset(config, amplitude, PI / 2.0,
+                                                 &mut StdRng::seed_from_u64(3))
+                  .unwrap();
+            canvas
+        };
+
+        // No wave is plain Schotter.
+        let config = SchotterConfig::new(40, 8, 12);
+        let mut plain = Canvas::create(width, height).unwrap();
+        plain.draw_schotter_with(&config, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert!(draw(&config, 0.0).pixels == plain.pixels);
+
+        // Without jitter, the middle of each row of squares is just its
+        // vertical sides, so the shift is easy to see: a quarter turn per row
+        // goes 0, 5, 0, -5 pixels.
+        let still = SchotterConfig {
+            jitter: JitterDistribution::Normal { sigma_scale: 0.0 },
+            ..config
+        };
+        let (plain, waved) = (draw(&still, 0.0), draw(&still, 5.0));
+        let layout = SchotterLayout::compute(40, 8, 12).unwrap();
+        for (row, &dx) in [0, 5, 0, -5, 0, 5].iter().enumerate() {
+            let y = (layout.padding + (row as f32 + 0.5) * layout.square_side) as i32;
+            for x in 0..width as i32 {
+                assert_eq!(waved.get_pixel(x, y), plain.get_pixel(x - dx, y),
+                           "row {}: ({}, {})", row, x, y);
+            }
+        }
+
+        let mut small = Canvas::create(width - 1, height).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(small.draw_schotter_sine_row_offset(&config, 1.0, 1.0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn check_size_hint() {
+        assert_eq!(Canvas::create(132, 96).unwrap().write_size_hint(), "\x1b[8;24;66t");
+        assert_eq!(Canvas::create(5, 5).unwrap().write_size_hint(), "\x1b[8;2;3t");
+
+        // It fits the render exactly.
+        let canvas = Canvas::create_and_render_schotter_with(&SchotterConfig::new(40, 8, 12),
+                                                             &mut StdRng::seed_from_u64(5))
+                         .unwrap();
+        let render = canvas.render();
+        let hint = format!("\x1b[8;{};{}t",
+                           render.lines().count(),
+                           render.lines().next().unwrap().chars().count());
+        assert_eq!(canvas.write_size_hint(), hint);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn check_benchmark_render() {
+        let canvas = Canvas::create_and_render_schotter(66, 8, 12).unwrap();
+        assert!(canvas.benchmark_render(10) > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn check_render_with_coordinates() {
+        let mut canvas = Canvas::create(50, 44).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(20, 40, 1);
+        let text = canvas.render_with_coordinates();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 1 + 11);
+        assert_eq!(lines[0], "   0         20        40");
+        assert_eq!(lines[1], format!(" 0 ⠁{}", "⠀".repeat(24)));
+        assert!(lines[2].starts_with(" 4 "));
+        assert_eq!(lines[11], format!("40 {}⠁{}", "⠀".repeat(10), "⠀".repeat(14)));
+
+        // The labels line up with the characters they name.
+        let column = lines[11].chars().position(|c| c == '⠁').unwrap();
+        assert_eq!(lines[0].find("20"), Some(column));
+    }
+
+    #[test]
+    fn check_hatching_fill() {
+        let square = [(2, 2), (17, 2), (17, 17), (2, 17)];
+        let mut canvas = Canvas::create(20, 20).unwrap();
+        canvas.draw_hatching_fill(&square, 4, 0.0, 1);
+        let mut outline = Canvas::create(20, 20).unwrap();
+        outline.draw_polygon(&square, 1);
+        for y in 0..20 {
+            for x in 0..20 {
+                // Every fourth row, but only inside the square.
+                let inside = x > 2 && x < 17 && y > 2 && y < 17;
+                let expected = outline.get_pixel(x, y) != 0 || (inside && y % 4 == 0);
+                assert_eq!(canvas.get_pixel(x, y), expected as u8, "({}, {})", x, y);
+            }
+        }
+
+        // Turned a quarter, the lines are columns instead.
+        canvas.clear();
+        canvas.draw_hatching_fill(&square, 4, 90.0, 1);
+        assert_eq!(canvas.get_pixel(8, 5), 1);
+        assert_eq!(canvas.get_pixel(9, 5), 0);
+        assert_eq!(canvas.get_pixel(20, 8), 0);
+
+        // A gap between the arms of a U is outside, and stays empty.
+        let u = [(0, 0), (5, 0), (5, 10), (14, 10), (14, 0), (19, 0), (19, 19), (0, 19)];
+        canvas.clear();
+        canvas.draw_hatching_fill(&u, 2, 0.0, 1);
+        assert_eq!(canvas.get_pixel(2, 4), 1);
+        assert_eq!(canvas.get_pixel(9, 4), 0);
+        assert_eq!(canvas.get_pixel(9, 14), 1);
+
+        // Lines at 45° go corner to corner of a square, with no gaps.
+        canvas.clear();
+        canvas.draw_hatching_fill(&[(0, 0), (19, 0), (19, 19), (0, 19)], 5, 45.0, 1);
+        for i in 1..19 {
+            assert_eq!(canvas.get_pixel(i, i), 1, "({}, {})", i, i);
+        }
+
+        // No spacing, or no inside, is just the outline.
+        for &(points, spacing) in [(&square[..], 0), (&square[..2], 4), (&[][..], 4)].iter() {
+            canvas.clear();
+            canvas.draw_hatching_fill(points, spacing, 0.0, 1);
+            outline.clear();
+            outline.draw_polygon(points, 1);
+            assert_eq!(canvas.pixels, outline.pixels);
+        }
+
+        // Off the canvas, or partly off, is fine.
+        canvas.draw_hatching_fill(&[(-50, -50), (-40, -50), (-40, -40)], 2, 30.0, 1);
+        canvas.draw_hatching_fill(&[(-5, -5), (i32::MAX, 10), (10, i32::MAX)], 3, 30.0, 1);
     }
-}
 
-#[cfg(test)]
-mod t {
-    use super::*;
+    #[test]
+    fn check_measure_line_length() {
+        let lines = [
+            (0, 0, 0, 0), (3, 5, 3, 40), (40, 7, 2, 7), (0, 0, 49, 49), (49, 0, 0, 49),
+            (1, 2, 48, 17), (30, 45, 4, 1), (10, 10, 11, 40), (5, 20, 44, 21),
+        ];
+        for &(x1, y1, x2, y2) in lines.iter() {
+            let mut canvas = Canvas::create(50, 50).unwrap();
+            canvas.draw_line(x1, y1, x2, y2, 1);
+            assert_eq!(Canvas::measure_line_length(x1, y1, x2, y2), canvas.pixel_histogram()[1],
+                       "({}, {}) to ({}, {})", x1, y1, x2, y2);
+            assert_eq!(Canvas::measure_line_length(x2, y2, x1, y1),
+                       Canvas::measure_line_length(x1, y1, x2, y2));
+        }
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(Canvas::measure_line_length(i32::MIN, 0, i32::MAX, 5), 1 << 32);
+    }
 
     #[test]
-    fn check_translate_pixels_group() {
-        let braille = [
-            '\u{2800}', // BRAILLE PATTERN BLANK
-            // 6-dot patterns
-                 '⠁', '⠂', '⠃', '⠄', '⠅', '⠆', '⠇',
-            '⠈', '⠉', '⠊', '⠋', '⠌', '⠍', '⠎', '⠏',
-            '⠐', '⠑', '⠒', '⠓', '⠔', '⠕', '⠖', '⠗',
-            '⠘', '⠙', '⠚', '⠛', '⠜', '⠝', '⠞', '⠟',
-            '⠠', '⠡', '⠢', '⠣', '⠤', '⠥', '⠦', '⠧',
-            '⠨', '⠩', '⠪', '⠫', '⠬', '⠭', '⠮', '⠯',
-            '⠰', '⠱', '⠲', '⠳', '⠴', '⠵', '⠶', '⠷',
-            '⠸', '⠹', '⠺', '⠻', '⠼', '⠽', '⠾', '⠿',
-            // 8-dot patterns
-            '⡀', '⡁', '⡂', '⡃', '⡄', '⡅', '⡆', '⡇',
-            '⡈', '⡉', '⡊', '⡋', '⡌', '⡍', '⡎', '⡏',
-            '⡐', '⡑', '⡒', '⡓', '⡔', '⡕', '⡖', '⡗',
-            '⡘', '⡙', '⡚', '⡛', '⡜', '⡝', '⡞', '⡟',
-            '⡠', '⡡', '⡢', '⡣', '⡤', '⡥', '⡦', '⡧',
-            '⡨', '⡩', '⡪', '⡫', '⡬', '⡭', '⡮', '⡯',
-            '⡰', '⡱', '⡲', '⡳', '⡴', '⡵', '⡶', '⡷',
-            '⡸', '⡹', '⡺', '⡻', '⡼', '⡽', '⡾', '⡿',
-            '⢀', '⢁', '⢂', '⢃', '⢄', '⢅', '⢆', '⢇',
-            '⢈', '⢉', '⢊', '⢋', '⢌', '⢍', '⢎', '⢏',
-            '⢐', '⢑', '⢒', '⢓', '⢔', '⢕', '⢖', '⢗',
-            '⢘', '⢙', '⢚', '⢛', '⢜', '⢝', '⢞', '⢟',
-            '⢠', '⢡', '⢢', '⢣', '⢤', '⢥', '⢦', '⢧',
-            '⢨', '⢩', '⢪', '⢫', '⢬', '⢭', '⢮', '⢯',
-            '⢰', '⢱', '⢲', '⢳', '⢴', '⢵', '⢶', '⢷',
-            '⢸', '⢹', '⢺', '⢻', '⢼', '⢽', '⢾', '⢿',
-            '⣀', '⣁', '⣂', '⣃', '⣄', '⣅', '⣆', '⣇',
-            '⣈', '⣉', '⣊', '⣋', '⣌', '⣍', '⣎', '⣏',
-            '⣐', '⣑', '⣒', '⣓', '⣔', '⣕', '⣖', '⣗',
-            '⣘', '⣙', '⣚', '⣛', '⣜', '⣝', '⣞', '⣟',
-            '⣠', '⣡', '⣢', '⣣', '⣤', '⣥', '⣦', '⣧',
-            '⣨', '⣩', '⣪', '⣫', '⣬', '⣭', '⣮', '⣯',
-            '⣰', '⣱', '⣲', '⣳', '⣴', '⣵', '⣶', '⣷',
-            '⣸', '⣹', '⣺', '⣻', '⣼', '⣽', '⣾', '⣿',
+    fn check_square_filled() {
+        let mut canvas = Canvas::create(40, 40).unwrap();
+        let mut outline = Canvas::create(40, 40).unwrap();
+        for &angle in [0.0, 0.3, 0.785, 1.2].iter() {
+            canvas.clear();
+            outline.clear();
+            canvas.draw_square_filled(20, 20, 20.0, angle);
+            outline.draw_square(20, 20, 20.0, angle);
+
+            // Everything in the outline is drawn, and each row is one solid run
+            // reaching out to the outline at both ends.
+            for y in 0..40 {
+                let filled: Vec<i32> = (0..40).filter(|&x| canvas.get_pixel(x, y) != 0).collect();
+                let edges: Vec<i32> = (0..40).filter(|&x| outline.get_pixel(x, y) != 0).collect();
+                assert!(edges.iter().all(|x| filled.contains(x)), "row {}", y);
+                if let (Some(&first), Some(&last)) = (filled.first(), filled.last()) {
+                    assert_eq!(filled.len() as i32, last - first + 1, "row {}", y);
+                    assert_eq!((first, last), (edges[0], edges[edges.len() - 1]), "row {}", y);
+                }
+            }
+        }
+
+        // Unrotated, it's the whole square.
+        canvas.clear();
+        canvas.draw_square_filled(20, 20, 20.0, 0.0);
+        let mut expected = Canvas::create(40, 40).unwrap();
+        expected.fill_region(10, 10, 21, 21);
+        assert!(canvas.pixels == expected.pixels);
+
+        // Squares hanging off the canvas, or far away, are clipped.
+        canvas.clear();
+        canvas.draw_square_filled(0, 0, 20.0, 0.0);
+        assert_eq!(canvas.pixel_histogram()[1], 11 * 11);
+        canvas.clear();
+        canvas.draw_square_filled(i32::MAX, i32::MIN, 1e9, 0.5);
+        canvas.draw_square_filled(20, 20, 1e12, 0.5);
+        assert_eq!(canvas.pixel_histogram()[1], 40 * 40);
+    }
+
+    #[test]
+    fn check_concentric_squares() {
+        let mut canvas = Canvas::create(40, 40).unwrap();
+        canvas.draw_concentric_squares(20, 20, 3, 8.0, 0.0, 1);
+
+        // Each square's right edge, from the smallest out, with gaps between.
+        let row: Vec<u8> = (20..34).map(|x| canvas.get_pixel(x, 20)).collect();
+        assert_eq!(row, [0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0]);
+
+        // Every square is the same as drawing it alone.
+        let mut expected = Canvas::create(40, 40).unwrap();
+        for &size in [8.0, 16.0, 24.0].iter() {
+            expected.draw_square(20, 20, size, 0.0);
+        }
+        assert!(canvas.pixels == expected.pixels);
+
+        // And they can be erased again.
+        canvas.draw_concentric_squares(20, 20, 3, 8.0, 0.0, 0);
+        assert!(canvas.pixels.iter().all(|&px| px == 0));
+
+        canvas.draw_concentric_squares(20, 20, 0, 8.0, 0.3, 1);
+        assert!(canvas.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_spiral_square() {
+        let mut canvas = Canvas::create(80, 80).unwrap();
+        canvas.draw_spiral_square(40, 40, 10.0, 1.5, 4, 0.2, 1);
+
+        // The same as drawing each square by hand.
+        let mut expected = Canvas::create(80, 80).unwrap();
+        for &(size, angle) in [(10.0, 0.0), (15.0, 0.2), (22.5, 0.4), (33.75, 0.6)].iter() {
+            expected.draw_square(40, 40, size, angle);
+        }
+        assert!(canvas.pixels == expected.pixels);
+
+        // Without growing or turning, every square lands on the first.
+        let mut single = Canvas::create(80, 80).unwrap();
+        single.draw_square(40, 40, 10.0, 0.0);
+        canvas.clear();
+        canvas.draw_spiral_square(40, 40, 10.0, 1.0, 5, 0.0, 1);
+        assert!(canvas.pixels == single.pixels);
+
+        // And it can be erased again.
+        canvas.draw_spiral_square(40, 40, 10.0, 1.0, 5, 0.0, 0);
+        assert_eq!(canvas.pixel_histogram()[1], 0);
+        canvas.draw_spiral_square(40, 40, 10.0, 1.5, 0, 0.2, 1);
+        assert_eq!(canvas.pixel_histogram()[1], 0);
+    }
+
+    #[test]
+    fn check_circle() {
+        let mut canvas = Canvas::create(11, 11).unwrap();
+        canvas.draw_circle(5, 5, 0, 1);
+        assert_eq!(canvas.pixel_histogram()[1], 1);
+        assert_eq!(canvas.get_pixel(5, 5), 1);
+
+        let mut canvas = Canvas::create(11, 11).unwrap();
+        canvas.draw_circle(5, 5, 4, 1);
+        for y in 0..11 {
+            for x in 0..11 {
+                // Every pixel of the outline is within about half a pixel of
+                // the true circle.
+                let distance = (((x - 5) * (x - 5) + (y - 5) * (y - 5)) as f32).sqrt();
+                if canvas.get_pixel(x, y) != 0 {
+                    assert!((distance - 4.0).abs() < 0.6, "({}, {})", x, y);
+                }
+            }
+        }
+        for &(x, y) in [(9, 5), (5, 9), (1, 5), (5, 1)].iter() {
+            assert_eq!(canvas.get_pixel(x, y), 1);
+        }
+        // And it's symmetric.
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_eq!(canvas.get_pixel(x, y), canvas.get_pixel(y, x));
+                assert_eq!(canvas.get_pixel(x, y), canvas.get_pixel(10 - x, y));
+            }
+        }
+
+        // Off-canvas parts are clipped.
+        let mut canvas = Canvas::create(4, 4).unwrap();
+        canvas.draw_circle(0, 0, 3, 1);
+        canvas.draw_circle(i32::MAX, i32::MIN, 100, 1);
+        canvas.draw_circle(2, 2, u32::MAX, 1);
+        assert_eq!(canvas.get_pixel(3, 0), 1);
+        assert_eq!(canvas.get_pixel(0, 3), 1);
+        assert_eq!(canvas.get_pixel(0, 0), 0);
+    }
+
+    #[test]
+    fn check_concentric_circles() {
+        let mut canvas = Canvas::create(41, 41).unwrap();
+        canvas.draw_concentric_circles(20, 20, 4, 5, 1);
+
+        let row: Vec<u8> = (20..41).map(|x| canvas.get_pixel(x, 20)).collect();
+        let on: Vec<usize> = row.iter().enumerate()
+                                .filter(|&(_, &px)| px != 0)
+                                .map(|(x, _)| x)
+                                .collect();
+        assert_eq!(on, [5, 10, 15, 20]);
+
+        canvas.draw_concentric_circles(20, 20, 4, 5, 0);
+        assert!(canvas.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn check_tracing_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{field, span, Event, Level, Metadata, Subscriber};
+
+        /// Keeps every event at `Level::DEBUG` or above as its level, then
+        /// its fields as `name=value`.
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        struct Fields(String);
+
+        impl field::Visit for Fields {
+            fn record_debug(&mut self, field: &field::Field, value: &dyn fmt::Debug) {
+                self.0 += &format!(" {}={:?}", field.name(), value);
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                *metadata.level() <= Level::DEBUG
+            }
+            fn new_span(&self, _: &span::Attributes) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event) {
+                let mut fields = Fields(event.metadata().level().to_string());
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0);
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let events = Arc::new(Mutex::new(vec![]));
+        tracing::subscriber::with_default(Recorder(events.clone()), || {
+            let canvas = Canvas::create_and_render_schotter_with(&SchotterConfig::new(8, 2, 3),
+                                                                 &mut StdRng::seed_from_u64(1))
+                             .unwrap();
+            canvas.render();
+        });
+        assert_eq!(*events.lock().unwrap(), [
+            "DEBUG message=schotter layout needed_width=16 needed_height=22 \
+             padding=2.0 square_side=6.0",
+            "DEBUG message=rendering braille cells cols=8 rows=6",
+        ]);
+    }
+
+    #[test]
+    fn check_pixel_run_length() {
+        let canvas = canvas_of(5, &[0, 0, 1, 1, 1,
+                                    1, 0, 1, 0, 1,
+                                    9, 9, 9, 9, 9]);
+        assert_eq!(canvas.pixel_run_length(), [
+            vec![(0, 2), (1, 3)],
+            vec![(1, 1), (0, 1), (1, 1), (0, 1), (1, 1)],
+            vec![(1, 5)],
+        ]);
+
+        // Runs carry on across the words the pixels are packed in.
+        let mut wide = Canvas::create(130, 2).unwrap();
+        wide.fill_region(60, 0, 10, 1);
+        assert_eq!(wide.pixel_run_length(), [vec![(0, 60), (1, 10), (0, 60)], vec![(0, 130)]]);
+
+        let narrow = Canvas::create(0, 3).unwrap();
+        assert_eq!(narrow.pixel_run_length(), [vec![], vec![], vec![]]);
+    }
+
+    #[test]
+    fn check_fingerprint() {
+        let canvas = canvas_of(3, &[0, 1, 0,
+                                    7, 0, 255]);
+        assert_eq!(canvas.fingerprint_hex(), "c8728069c7abcb04");
+        assert_eq!(canvas_of(3, &[0, 1, 0, 1, 0, 1]).fingerprint(), canvas.fingerprint());
+        // The same pixels, but a different shape.
+        assert_ne!(canvas_of(2, &[0, 1, 0, 1, 0, 1]).fingerprint(), canvas.fingerprint());
+        assert_eq!(Canvas::create(0, 0).unwrap().fingerprint_hex(), "a8c7f832281a39c5");
+
+        let schotter = Canvas::create_and_render_schotter_with(&SchotterConfig::new(40, 8, 12),
+                                                               &mut StdRng::seed_from_u64(1968))
+                           .unwrap();
+        assert_eq!(schotter.fingerprint_hex(), "d95e2f8434cd5648");
+    }
+
+    #[test]
+    fn check_error_display() {
+        let errors = [
+            (PixelBufferTooSmall { needed: 4, actual: 3 },
+             "pixel buffer has 3 bytes but needs at least 4"),
+            (CanvasTooSmall {
+                needed_width: 132,
+                actual_width: 100,
+                needed_height: 52,
+                actual_height: 40,
+             },
+             "canvas is 100x40 but needs to be at least 132x52"),
+            (InvalidDimensions { width: 70000, height: 70000 },
+             "a 70000x70000 canvas is too large to create"),
+            (OutOfBounds { x: -1, y: 7, width: 4, height: 8 },
+             "pixel (-1, 7) is outside of the 4x8 canvas"),
+            (SizeMismatch { width: 4, height: 8, other_width: 8, other_height: 4 },
+             "canvases are 4x8 and 8x4, but need to be the same size"),
+            (InvalidParameter { name: "console_cols" },
+             "invalid value for parameter `console_cols`"),
         ];
+        for (err, expected) in errors.iter() {
+            assert_eq!(err.to_string(), *expected);
+        }
 
-        // Exhaustively test every possible u8 value.
-        for hex in 0..0x100i32 {
-            let hex = hex as u8;
-            let c = braille[hex as usize];
-            let actual = translate_pixels_group(hex);
-            println!("0x{}, '{}' =? '{}'", hex, c, actual);
-            assert_eq!(c, actual,
-                       "'{}'!='{}' (0x{:x} != 0x{:x})",
-                       c, actual,
-                       c as i32, actual as i32);
+        let err = Canvas::create(10, 10).unwrap()
+                      .draw_schotter_with(&SchotterConfig::new(40, 8, 12),
+                                          &mut StdRng::seed_from_u64(1))
+                      .unwrap_err();
+        assert_eq!(err.to_string(), "canvas is 10x10 but needs to be at least 80x118");
+    }
+
+    #[test]
+    fn check_create_rejects_huge_dimensions() {
+        for &(width, height) in [(70000, 70000), (1 << 31, 1), (1, u32::MAX)].iter() {
+            match Canvas::create(width, height) {
+                Err(InvalidDimensions { width: w, height: h }) => assert_eq!((w, h), (width, height)),
+                other => panic!("Expected an error, got {:?}", other.map(|c| c.width)),
+            }
+        }
+        assert!(Canvas::create(1 << 16, 1 << 15).is_ok());
+    }
+
+    #[test]
+    fn check_from_fn() {
+        let canvas = Canvas::from_fn(3, 2, |x, y| (x + 10 * y) as u8).unwrap();
+        assert_eq!(canvas.pixel_bytes(), [0, 1, 1, 1, 1, 1]);
+
+        let mut calls = 0;
+        Canvas::from_fn(0, 5, |_, _| { calls += 1; 1 }).unwrap();
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn check_from_points() {
+        let points = [(0, 0), (2, 1), (-1, 0), (3, 0), (1, 5), (2, 1)];
+        let canvas = Canvas::from_points(3, 2, points.iter().cloned()).unwrap();
+        assert_eq!(canvas.pixel_bytes(), [1, 0, 0, 0, 0, 1]);
+        assert!(Canvas::from_points(1 << 31, 1, vec![]).is_err());
+
+        // Negative points are moved onto the canvas, not dropped.
+        let canvas = Canvas::from_points_auto_sized(vec![(-3, 4), (1, 2), (-1, 5)], 1).unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (7, 6));
+        for y in 0..6 {
+            for x in 0..7 {
+                let on = [(1, 3), (5, 1), (3, 4)].contains(&(x, y));
+                assert_eq!(canvas.get_pixel(x, y), on as u8, "({}, {})", x, y);
+            }
+        }
+
+        let single = Canvas::from_points_auto_sized(Some((i32::MIN, i32::MAX)), 0).unwrap();
+        assert_eq!((single.width(), single.height(), single.get_pixel(0, 0)), (1, 1, 1));
+
+        let empty = Canvas::from_points_auto_sized(None, 4).unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+
+        match Canvas::from_points_auto_sized(vec![(i32::MIN, 0), (i32::MAX, 0)], 0) {
+            Err(InvalidDimensions { .. }) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+
+    /// The original, step by step `draw_line`, to compare against.
+    fn draw_line_stepwise(canvas: &mut Canvas, x1: i32, y1: i32, x2: i32, y2: i32) {
+        let sx = if x1 < x2 { 1 } else { -1 };
+        let sy = if y1 < y2 { 1 } else { -1 };
+        let x2 = x2 as isize;
+        let y2 = y2 as isize;
+        let dx = (x2 - x1 as isize).abs();
+        let dy = (y2 - y1 as isize).abs();
+
+        let mut x = x1 as isize;
+        let mut y = y1 as isize;
+        let mut err = dx - dy;
+
+        loop {
+            canvas.draw_pixel(x as i32, y as i32, 1);
+            if x == x2 && y == y2 { break; }
+
+            let e2 = 2 * err;
+            if e2 > -dy {
+                err -= dy;
+                x += sx;
+            }
+            if e2 < dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    #[test]
+    fn check_draw_line_matches_stepwise() {
+        let points: Vec<i32> = vec![-13, -4, -1, 0, 1, 2, 5, 8, 9, 10, 17, 30];
+        for &x1 in points.iter() {
+            for &y1 in points.iter() {
+                for &x2 in points.iter() {
+                    for &y2 in points.iter() {
+                        let mut expected = Canvas::create(10, 9).unwrap();
+                        draw_line_stepwise(&mut expected, x1, y1, x2, y2);
+                        let mut actual = Canvas::create(10, 9).unwrap();
+                        actual.draw_line(x1, y1, x2, y2, 1);
+                        assert!(actual.pixels == expected.pixels,
+                                "({}, {}) to ({}, {})", x1, y1, x2, y2);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_clear_and_fill_region() {
+        let mut canvas = Canvas::create(6, 4).unwrap();
+        canvas.fill_region(1, 1, 3, 2);
+        assert_eq!(canvas.render_ascii(), "      \n ###  \n ###  \n      \n");
+        canvas.clear_region(2, -5, 1, 7);
+        assert_eq!(canvas.render_ascii(), "      \n # #  \n ###  \n      \n");
+
+        // Regions hanging off of any edge are clamped.
+        let mut canvas = Canvas::create(6, 4).unwrap();
+        canvas.fill_region(-3, 2, 5, u32::MAX);
+        canvas.fill_region(i32::MAX, 0, u32::MAX, u32::MAX);
+        canvas.fill_region(5, i32::MIN, 1, u32::MAX);
+        assert_eq!(canvas.render_ascii(), "     #\n     #\n##   #\n##   #\n");
+        canvas.fill_region(0, 0, 0, 4);
+        canvas.fill_region(9, 0, 2, 2);
+        assert_eq!(canvas.render_ascii(), "     #\n     #\n##   #\n##   #\n");
+
+        let mut region = canvas.clone();
+        region.clear_region(-1, -1, 100, 100);
+        canvas.clear();
+        assert!(region.pixels == canvas.pixels);
+    }
+
+    #[test]
+    fn check_axis_aligned_lines_match_bresenham() {
+        let points: Vec<i32> = vec![i32::MIN, -13, -1, 0, 1, 5, 8, 9, 10, 30, i32::MAX];
+        for &a in points.iter() {
+            for &b in points.iter() {
+                for &c in points.iter() {
+                    let mut expected = Canvas::create(10, 9).unwrap();
+                    expected.draw_line_bresenham(a, c, b, c, 1);
+                    expected.draw_line_bresenham(c, a, c, b, 2);
+                    let mut actual = Canvas::create(10, 9).unwrap();
+                    actual.draw_line(a, c, b, c, 1);
+                    actual.draw_line(c, a, c, b, 2);
+                    assert!(actual.pixels == expected.pixels, "{} {} {}", a, b, c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_draw_line_extremes() {
+        let (min, max) = (i32::MIN, i32::MAX);
+
+        let mut canvas = Canvas::create(4, 4).unwrap();
+        canvas.draw_line(min, 2, max, 2, 1);
+        canvas.draw_line(1, max, 1, min, 1);
+        assert_eq!(canvas.render_ascii(), " #  \n #  \n####\n #  \n");
+
+        // Exactly diagonal, so every step goes both ways.
+        let mut canvas = Canvas::create(4, 4).unwrap();
+        canvas.draw_line(min, min, max - 1, max - 1, 1);
+        assert_eq!(canvas.render_ascii(), "#   \n #  \n  # \n   #\n");
+
+        // Lines that never touch the canvas draw nothing.
+        let mut canvas = Canvas::create(4, 4).unwrap();
+        canvas.draw_line(min, min, max, min, 1);
+        canvas.draw_line(max, max, max, min, 1);
+        canvas.draw_line(min, max, max, max, 1);
+        canvas.draw_line(max, min, min, max, 1);
+        canvas.draw_line(min, min, min, min, 1);
+        assert!(canvas.pixels.iter().all(|&p| p == 0));
+
+        let mut empty = Canvas::create(0, 0).unwrap();
+        empty.draw_line(min, min, max, max, 1);
+    }
+
+    #[test]
+    fn check_render_statistics() {
+        let mut canvas = Canvas::create(5, 6).unwrap();
+        canvas.draw_line(0, 0, 4, 0, 1);
+        canvas.draw_pixel(2, 5, 1);
+
+        let stats = canvas.render_statistics();
+        assert_eq!(stats, RenderStats {
+            set_pixels:    6,
+            clear_pixels:  24,
+            braille_chars: 6,
+            rows:          2,
+            density:       0.2,
+        });
+
+        // The counts match what's actually rendered.
+        let rendered = canvas.render();
+        assert_eq!(rendered.lines().count(), stats.rows);
+        assert_eq!(rendered.chars().filter(|&c| c != '\n').count(), stats.braille_chars);
+
+        let empty = Canvas::create(0, 0).unwrap().render_statistics();
+        assert_eq!((empty.braille_chars, empty.density), (0, 0.0));
+    }
+
+    #[test]
+    fn check_pixel_histogram() {
+        let mut canvas = Canvas::create(5, 6).unwrap();
+        assert_eq!(canvas.pixel_count(), 30);
+        assert_eq!(canvas.pixel_histogram(), [30, 0]);
+
+        canvas.draw_line(0, 0, 4, 0, 1);
+        canvas.draw_pixel(2, 5, 7);
+        let histogram = canvas.pixel_histogram();
+        assert_eq!(histogram, [24, 6]);
+        assert_eq!(histogram[0] + histogram[1], canvas.pixel_count());
+
+        let schotter = Canvas::create_and_render_schotter_with(&SchotterConfig::default(),
+                                                               &mut StdRng::seed_from_u64(3))
+                           .unwrap();
+        let histogram = schotter.pixel_histogram();
+        assert_eq!(histogram[0] + histogram[1], schotter.pixel_count());
+        assert_eq!(histogram[1], schotter.render_statistics().set_pixels);
+
+        assert_eq!(Canvas::create(0, 0).unwrap().pixel_histogram(), [0, 0]);
+    }
+
+    #[test]
+    fn check_invert() {
+        let mut canvas = canvas_of(3, &[0, 1, 7]);
+        canvas.invert();
+        assert_eq!(canvas.pixel_bytes(), [1, 0, 0]);
+        canvas.invert();
+        assert_eq!(canvas.pixel_bytes(), [0, 1, 1]);
+        assert!(canvas.validate_strict().is_ok());
+    }
+
+    #[test]
+    fn check_sample_region() {
+        let canvas = canvas_of(4, &[1, 1, 0, 0,
+                                    1, 1, 0, 0,
+                                    0, 0, 0, 0,
+                                    0, 0, 0, 1]);
+        assert_eq!(canvas.sample_region(0, 0, 4, 4), 5.0 / 16.0);
+        assert_eq!(canvas.sample_region(0, 0, 2, 2), 1.0);
+        assert_eq!(canvas.sample_region(1, 1, 2, 2), 0.25);
+        assert_eq!(canvas.sample_region(2, 0, 2, 3), 0.0);
+
+        // Clamped to the canvas, so this is just the bottom-right pixel.
+        assert_eq!(canvas.sample_region(3, 3, 100, 100), 1.0);
+        assert_eq!(canvas.sample_region(-10, -10, 12, 12), 1.0);
+        assert_eq!(canvas.sample_region(i32::MAX, 0, u32::MAX, 1), 0.0);
+        assert_eq!(canvas.sample_region(4, 0, 1, 1), 0.0);
+        assert_eq!(canvas.sample_region(0, 0, 0, 4), 0.0);
+    }
+
+    #[test]
+    fn check_schotter_gets_denser_going_down() {
+        let config = SchotterConfig::default();
+        let (width, height) = schotter_dimensions(66, 8, 12).unwrap();
+        let mut canvas = Canvas::create(width, height).unwrap();
+        canvas.draw_schotter_with(&config, &mut StdRng::seed_from_u64(4)).unwrap();
+
+        // The orderly squares at the top line up their edges, so the chaotic
+        // squares at the bottom cover more pixels.
+        let half = height / 2;
+        let top = canvas.sample_region(0, 0, width, half);
+        let bottom = canvas.sample_region(0, half as i32, width, half);
+        assert!(top < bottom, "{} < {}", top, bottom);
+    }
+
+    #[test]
+    fn check_schotter_dimensions() {
+        // Small column counts are included so that we cover the switch to
+        // no padding at all.
+        for console_cols in [1, 2, 3, 4, 10, 33, 66, 100].iter().cloned() {
+            for squares_per_row in 1..10 {
+                for squares_per_col in 1..14 {
+                    let dims = schotter_dimensions(console_cols,
+                                                   squares_per_row,
+                                                   squares_per_col).unwrap();
+                    let config = SchotterConfig::new(console_cols,
+                                                     squares_per_row,
+                                                     squares_per_col);
+                    let canvas = Canvas::create_and_render_schotter_with(
+                        &config,
+                        &mut StdRng::seed_from_u64(1)).unwrap();
+                    assert_eq!(dims,
+                               (canvas.width as u32, canvas.height as u32),
+                               "{} {} {}",
+                               console_cols, squares_per_row, squares_per_col);
+                }
+            }
+        }
+
+        assert_eq!(schotter_dimensions(66, 8, 12).unwrap(), (132, 196));
+        assert_eq!(schotter_dimensions(2, 1, 1).unwrap(), (4, 4));
+    }
+
+    #[test]
+    fn check_schotter_dimensions_rejects_non_positive() {
+        let bad = [
+            ((0, 8, 12),  "console_cols"),
+            ((-1, 8, 12), "console_cols"),
+            ((66, 0, 12), "squares_per_row"),
+            ((66, 8, -3), "squares_per_col"),
+        ];
+        for &((cols, per_row, per_col), expected) in bad.iter() {
+            match schotter_dimensions(cols, per_row, per_col) {
+                Err(InvalidParameter { name }) => assert_eq!(name, expected),
+                other => panic!("Expected an error, got {:?}", other),
+            }
+            #[cfg(feature = "std")]
+            assert!(Canvas::create_and_render_schotter(cols, per_row, per_col)
+                        .is_err());
+            let config = SchotterConfig::new(cols, per_row, per_col);
+            assert!(Canvas::create_and_render_schotter_with(&config,
+                                                            &mut StdRng::seed_from_u64(1))
+                        .is_err());
+        }
+    }
+
+    #[test]
+    fn check_validate() {
+        let mut canvas = Canvas::create(5, 6).unwrap();
+        assert!(canvas.validate().is_ok());
+        assert!(Canvas::create(0, 0).unwrap().validate_strict().is_ok());
+
+        canvas.draw_pixel(2, 3, 7);
+        assert!(canvas.validate_strict().is_ok());
+        canvas.pixels[0] |= 1 << 40;
+        assert!(canvas.validate().is_ok());
+        match canvas.validate_strict() {
+            Err(InvalidParameter { name: "pixels" }) => (),
+            other => panic!("Expected an error, got {:?}", other),
+        }
+
+        let mut broken = Canvas::create(5, 6).unwrap();
+        broken.pixels.pop();
+        match broken.validate() {
+            Err(InvalidParameter { name: "pixels" }) => (),
+            other => panic!("Expected an error, got {:?}", other),
+        }
+        broken.height = -1;
+        match broken.validate() {
+            Err(InvalidParameter { name: "height" }) => (),
+            other => panic!("Expected an error, got {:?}", other),
+        }
+    }
+
+    /// A drawing operation, for throwing at canvases in property tests.
+    /// Coordinates reach well past the edges of the small canvases they're
+    /// drawn on.
+    #[derive(Clone, Debug)]
+    enum Op {
+        Pixel(i32, i32, u8),
+        Line(i32, i32, i32, i32),
+        FillRegion(i32, i32, u32, u32),
+        ClearRegion(i32, i32, u32, u32),
+        Circle(i32, i32, u32),
+        Square(f32, f32, f32, f32),
+        Polygon(Vec<(i32, i32)>),
+        Text(i32, i32, String),
+        Invert,
+        FlipHorizontal,
+        FlipVertical,
+        Rotate,
+    }
+
+    impl quickcheck::Arbitrary for Op {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Op {
+            let coord = |g: &mut quickcheck::Gen| i32::arbitrary(g) % 60;
+            let size = |g: &mut quickcheck::Gen| u32::arbitrary(g) % 40;
+            match u8::arbitrary(g) % 12 {
+                0  => Op::Pixel(coord(g), coord(g), u8::arbitrary(g) % 2),
+                1  => Op::Line(coord(g), coord(g), coord(g), coord(g)),
+                2  => Op::FillRegion(coord(g), coord(g), size(g), size(g)),
+                3  => Op::ClearRegion(coord(g), coord(g), size(g), size(g)),
+                4  => Op::Circle(coord(g), coord(g), size(g)),
+                5  => Op::Square(coord(g) as f32, coord(g) as f32, size(g) as f32,
+                                 f32::arbitrary(g) % 7.0),
+                6  => {
+                    let points = (0..u8::arbitrary(g) % 6).map(|_| (coord(g), coord(g)));
+                    Op::Polygon(points.collect())
+                }
+                7  => Op::Text(coord(g), coord(g), String::arbitrary(g)),
+                8  => Op::Invert,
+                9  => Op::FlipHorizontal,
+                10 => Op::FlipVertical,
+                _  => Op::Rotate,
+            }
+        }
+    }
+
+    impl Op {
+        fn apply(&self, canvas: &mut Canvas) {
+            match *self {
+                Op::Pixel(x, y, color)          => canvas.draw_pixel(x, y, color),
+                Op::Line(x1, y1, x2, y2)        => canvas.draw_line(x1, y1, x2, y2, 1),
+                Op::FillRegion(x, y, w, h)      => canvas.fill_region(x, y, w, h),
+                Op::ClearRegion(x, y, w, h)     => canvas.clear_region(x, y, w, h),
+                Op::Circle(x, y, radius)        => canvas.draw_circle(x, y, radius, 1),
+                Op::Square(x, y, size, angle)   => canvas.draw_square_f(x, y, size, angle),
+                Op::Polygon(ref points)         => canvas.draw_polygon(points, 1),
+                Op::Text(x, y, ref text)        => canvas.draw_text(x, y, text, 1),
+                Op::Invert                      => canvas.invert(),
+                Op::FlipHorizontal              => canvas.flip_horizontal(),
+                Op::FlipVertical                => canvas.flip_vertical(),
+                Op::Rotate                      => canvas.rotate_pixels_90_in_place(),
+            }
+        }
+    }
+
+    #[test]
+    fn check_random_drawing_stays_valid() {
+        fn prop(width: u8, height: u8, ops: Vec<Op>) -> bool {
+            let mut canvas = Canvas::create(width as u32 % 40, height as u32 % 40).unwrap();
+            for op in &ops {
+                op.apply(&mut canvas);
+                if canvas.validate_strict().is_err() {
+                    return false;
+                }
+            }
+            let lines = canvas.render().lines().count();
+            lines == (canvas.height as usize).div_ceil(4)
+        }
+        quickcheck::quickcheck(prop as fn(u8, u8, Vec<Op>) -> bool);
+    }
+
+    #[test]
+    fn check_pixel_round_trip() {
+        fn prop(width: u8, height: u8, x: u8, y: u8, color: bool) -> bool {
+            let (width, height) = (width as u32 % 40 + 1, height as u32 % 40 + 1);
+            let (x, y) = ((x as u32 % width) as i32, (y as u32 % height) as i32);
+            let mut canvas = Canvas::create(width, height).unwrap();
+            canvas.draw_pixel(x, y, color as u8);
+            let others = canvas.pixel_histogram()[1] - color as usize;
+            canvas.get_pixel(x, y) == color as u8 && others == 0
         }
+        quickcheck::quickcheck(prop as fn(u8, u8, u8, u8, bool) -> bool);
     }
 }
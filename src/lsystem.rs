@@ -0,0 +1,177 @@
+//! Lindenmayer systems, drawn with turtle graphics.
+//!
+//! An L-system starts with an axiom string and rewrites every character of it
+//! by a set of rules, over and over. A handful of rules grow into dragon
+//! curves, ferns, space-filling curves, and plenty of other fractals.
+
+use alloc::{string::{String, ToString}, vec, vec::Vec};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{Canvas, CanvasError, CanvasError::*};
+
+/// The most commands an L-system may expand into. Each generation can
+/// multiply the length, so a few too many iterations would otherwise eat all
+/// of memory.
+const MAX_LSYSTEM_LEN: usize = 1 << 22;
+
+impl Canvas {
+    /// Expand an L-system for `iterations` generations and draw the result
+    /// with a turtle.
+    ///
+    /// Each rule replaces a single character with a string, all at once, and
+    /// characters without a rule are kept as they are. The turtle starts at
+    /// `start`, facing `start_angle`, and then follows the expanded string:
+    ///
+    /// - `F` moves forward `step` pixels, drawing a line.
+    /// - `+` turns left by `angle`, and `-` turns right.
+    /// - `[` saves the position and heading, and `]` returns to the last one
+    ///   saved.
+    ///
+    /// Everything else is ignored, so extra characters can steer the rules.
+    /// Angles are in radians. Like `Canvas::draw_arc`, 0 faces right and y
+    /// grows downwards, so π/2 faces down the canvas.
+    ///
+    /// Returns `InvalidParameter` when a rule doesn't replace exactly one
+    /// character, when a `]` has no matching `[`, or when the expansion grows
+    /// too large to draw.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_lsystem(&mut self,
+                        axiom: &str,
+                        rules: &[(&str, &str)],
+                        angle: f32,
+                        step: f32,
+                        iterations: u32,
+                        start: (i32, i32),
+                        start_angle: f32,
+                        color: u8)
+        -> Result<(), CanvasError>
+    {
+        let commands = expand_lsystem(axiom, rules, iterations)?;
+
+        // The turtle keeps its position in floats, so rounding doesn't pile up
+        // over thousands of steps.
+        let mut x = start.0 as f32;
+        let mut y = start.1 as f32;
+        let mut heading = start_angle;
+        let mut saved = vec![];
+        for command in commands.chars() {
+            match command {
+                'F' => {
+                    let (nx, ny) = (x + step * heading.cos(), y + step * heading.sin());
+                    self.draw_line(x.round() as i32, y.round() as i32,
+                                   nx.round() as i32, ny.round() as i32,
+                                   color);
+                    x = nx;
+                    y = ny;
+                }
+                // Turning left is counterclockwise, against y growing downwards.
+                '+' => heading -= angle,
+                '-' => heading += angle,
+                '[' => saved.push((x, y, heading)),
+                ']' => {
+                    let last = saved.pop().ok_or(InvalidParameter { name: "axiom" })?;
+                    x = last.0;
+                    y = last.1;
+                    heading = last.2;
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rewrite `axiom` by `rules` for `iterations` generations.
+fn expand_lsystem(axiom: &str, rules: &[(&str, &str)], iterations: u32)
+    -> Result<String, CanvasError>
+{
+    let mut table = Vec::with_capacity(rules.len());
+    for &(from, to) in rules {
+        let mut chars = from.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => table.push((c, to)),
+            _ => return Err(InvalidParameter { name: "rules" }),
+        }
+    }
+
+    let mut current = axiom.to_string();
+    for _ in 0..iterations {
+        let mut next = String::with_capacity(current.len());
+        for c in current.chars() {
+            match table.iter().find(|&&(from, _)| from == c) {
+                Some(&(_, to)) => next += to,
+                None => next.push(c),
+            }
+            if next.len() > MAX_LSYSTEM_LEN {
+                return Err(InvalidParameter { name: "iterations" });
+            }
+        }
+        current = next;
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod t {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn check_expand_lsystem() {
+        let algae = [("A", "AB"), ("B", "A")];
+        assert_eq!(expand_lsystem("A", &algae, 0).unwrap(), "A");
+        assert_eq!(expand_lsystem("A", &algae, 4).unwrap(), "ABAABABA");
+
+        // Rules apply to the old string all at once, not one after another.
+        let swap = [("F", "G"), ("G", "F")];
+        assert_eq!(expand_lsystem("FG+", &swap, 1).unwrap(), "GF+");
+
+        assert!(expand_lsystem("F", &[("FF", "F")], 1).is_err());
+        assert!(expand_lsystem("F", &[("", "F")], 1).is_err());
+        match expand_lsystem("F", &[("F", "FF")], 64) {
+            Err(InvalidParameter { name: "iterations" }) => (),
+            other => panic!("Expected the expansion to be too long, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_lsystem_square() {
+        // Four sides and four left turns, starting to the right.
+        let mut canvas = Canvas::create(6, 6).unwrap();
+        canvas.draw_lsystem("F+F+F+F", &[], FRAC_PI_2, 4.0, 0, (1, 5), 0.0, 1).unwrap();
+        assert_eq!(canvas.render_ascii(), "      \n\
+                                           \x20#####\n\
+                                           \x20#   #\n\
+                                           \x20#   #\n\
+                                           \x20#   #\n\
+                                           \x20#####\n");
+    }
+
+    #[test]
+    fn check_lsystem_branches() {
+        // A trunk going up, with a branch to the left that doesn't move the
+        // rest of the trunk.
+        let mut canvas = Canvas::create(5, 5).unwrap();
+        canvas.draw_lsystem("F[+F]F", &[], FRAC_PI_2, 2.0, 0, (4, 4), -FRAC_PI_2, 1).unwrap();
+        assert_eq!(canvas.render_ascii(), "    #\n    #\n  ###\n    #\n    #\n");
+
+        assert!(canvas.draw_lsystem("F]", &[], FRAC_PI_2, 2.0, 0, (0, 0), 0.0, 1).is_err());
+    }
+
+    #[test]
+    fn check_lsystem_dragon() {
+        // The Heighway dragon never draws the same segment twice, so every
+        // step of it lands on a new stretch of the canvas.
+        let rules = [("X", "X+YF+"), ("Y", "-FX-Y")];
+        let mut canvas = Canvas::create(200, 200).unwrap();
+        canvas.draw_lsystem("FX", &rules, FRAC_PI_2, 2.0, 10, (100, 100), 0.0, 1).unwrap();
+
+        let set = canvas.render_statistics().set_pixels;
+        assert!(set > 1024, "{}", set);
+        assert!(set <= 1024 * 2 + 1, "{}", set);
+    }
+}
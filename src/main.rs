@@ -1,43 +1,2585 @@
-use lolwut::Canvas;
+use lolwut::{Canvas, CanvasError, Color, ParseParamsError, SchotterConfig, SchotterParams};
+use lolwut::term::{cursor_up, CLEAR_BELOW, HIDE_CURSOR, SHOW_CURSOR};
+
+use rand::prelude::*;
 
 use std::error;
 use std::env::args;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The encodings the art can be written in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Braille,
+    Ascii,
+    Blocks,
+    Pbm,
+    /// See `to_json` for the schema.
+    Json,
+}
+
+impl Format {
+    const ALL: [Format; 5] = [Format::Braille, Format::Ascii, Format::Blocks, Format::Pbm,
+                              Format::Json];
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Braille => "braille",
+            Format::Ascii   => "ascii",
+            Format::Blocks  => "blocks",
+            Format::Pbm     => "pbm",
+            Format::Json    => "json",
+        }
+    }
+
+    /// Binary formats are not meant for humans, and make a mess of terminals.
+    fn is_binary(self) -> bool {
+        match self {
+            Format::Braille | Format::Ascii | Format::Blocks | Format::Json => false,
+            Format::Pbm                                                     => true,
+        }
+    }
+
+    /// Whether the format draws the art in characters, which can be shown and
+    /// redrawn in a terminal.
+    fn is_picture(self) -> bool {
+        match self {
+            Format::Braille | Format::Ascii | Format::Blocks => true,
+            Format::Pbm | Format::Json                       => false,
+        }
+    }
+
+    fn names() -> String {
+        Format::ALL.iter().map(|f| f.name()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// How many pixels wide each console column is.
+    fn pixels_per_col(self) -> i32 {
+        match self {
+            Format::Braille                => 2,
+            Format::Ascii | Format::Blocks => 1,
+            // These aren't shown in a console, so they're as big as braille.
+            Format::Pbm | Format::Json     => 2,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        Format::ALL.iter()
+                   .cloned()
+                   .find(|f| f.name() == s)
+                   .ok_or_else(|| format!("unknown format `{}`, expected one of: {}",
+                                          s, Format::names()))
+    }
+}
+
+/// What the user asked for with `--color`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ColorChoice {
+    /// Color with `AUTO_COLOR`, but only when it's appropriate.
+    Auto,
+    Never,
+    Always(Color),
+}
+
+/// The color `--color auto` uses: blue, like plotter ink.
+const AUTO_COLOR: Color = Color::Named(4);
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ColorChoice, String> {
+        match s {
+            "auto"  => Ok(ColorChoice::Auto),
+            "never" => Ok(ColorChoice::Never),
+            _ => s.parse()
+                  .map(ColorChoice::Always)
+                  .map_err(|e| format!("{}, `auto`, or `never`", e)),
+        }
+    }
+}
+
+/// Decide which color, if any, to draw in.
+///
+/// Output that isn't going to a terminal is never colored, so pipes and files
+/// get plain text. `auto` also respects the `NO_COLOR` convention, but an
+/// explicit color is used on any terminal.
+fn resolve_color(choice: ColorChoice, to_tty: bool, no_color: bool) -> Option<Color> {
+    match choice {
+        _ if !to_tty                           => None,
+        ColorChoice::Auto if !no_color         => Some(AUTO_COLOR),
+        ColorChoice::Auto | ColorChoice::Never => None,
+        ColorChoice::Always(color)             => Some(color),
+    }
+}
+
+/// Check the `NO_COLOR` environment variable. See https://no-color.org.
+fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// A generator that the binary can draw.
+struct Pattern {
+    name:        &'static str,
+    description: &'static str,
+    /// Names and defaults of the numbers this pattern takes after the
+    /// common positional arguments.
+    args:        &'static [(&'static str, f64)],
+    /// Draw onto `canvas`, a blank canvas sized by `config`. `args` always
+    /// has one value for each of the pattern's `args`.
+    draw:        fn(&mut Canvas, &SchotterConfig, &[f64], &mut StdRng) -> Result<(), CanvasError>,
+}
+
+/// Every pattern, in the order they're listed. The first is the default.
+const PATTERNS: &[Pattern] = &[
+    Pattern {
+        name:        "schotter",
+        description: "Georg Nees' squares, growing more chaotic going down",
+        args:        &[],
+        draw:        |canvas, config, _, rng| canvas.draw_schotter_with(config, rng),
+    },
+    Pattern {
+        name:        "noise",
+        description: "cloud-like value noise in blobs about SCALE pixels across",
+        args:        &[("SCALE", 12.0), ("THRESHOLD", 0.5)],
+        draw:        |canvas, _, args, rng| {
+            canvas.noise_fill(args[0] as f32, args[1] as f32, rng.gen());
+            Ok(())
+        },
+    },
+    Pattern {
+        name:        "spiral",
+        description: "an Archimedean spiral with arms SPACING pixels apart",
+        args:        &[("SPACING", 4.0)],
+        draw:        |canvas, _, args, _| {
+            let (width, height) = (canvas.width() as f32, canvas.height() as f32);
+            let spacing = args[0] as f32;
+            let turns = width.min(height) / 2.0 / spacing;
+            canvas.draw_archimedean_spiral((width / 2.0) as i32,
+                                           (height / 2.0) as i32,
+                                           spacing,
+                                           turns,
+                                           (turns * 64.0).ceil() as u32,
+                                           1);
+            Ok(())
+        },
+    },
+    Pattern {
+        name:        "squares",
+        description: "concentric squares, SPACING pixels larger each time",
+        args:        &[("SPACING", 6.0)],
+        draw:        |canvas, _, args, _| {
+            let (width, height) = (canvas.width(), canvas.height());
+            let spacing = args[0] as f32;
+            // Enough squares to reach the corners, even when rotated.
+            let count = (width.max(height) as f32 * std::f32::consts::SQRT_2 / spacing).ceil();
+            canvas.draw_concentric_squares(width / 2, height / 2, count as u32, spacing, 0.0, 1);
+            Ok(())
+        },
+    },
+    Pattern {
+        name:        "circles",
+        description: "concentric circles, SPACING pixels apart",
+        args:        &[("SPACING", 6.0)],
+        draw:        |canvas, _, args, _| {
+            let (width, height) = (canvas.width(), canvas.height());
+            let spacing = (args[0].round() as u32).max(1);
+            let reach = ((width * width + height * height) as f64).sqrt() / 2.0;
+            let count = (reach / spacing as f64).ceil() as u32;
+            canvas.draw_concentric_circles(width / 2, height / 2, count, spacing, 1);
+            Ok(())
+        },
+    },
+];
+
+/// A blank canvas the size that Schotter would be drawn at with `config`.
+fn blank_canvas(config: &SchotterConfig) -> Result<Canvas, CanvasError> {
+    let (width, height) = lolwut::schotter_dimensions(config.console_cols,
+                                                      config.squares_per_row,
+                                                      config.squares_per_col)?;
+    Canvas::create(width, height)
+}
+
+fn find_pattern(name: &str) -> Result<&'static Pattern, String> {
+    PATTERNS.iter()
+            .find(|pattern| pattern.name == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = PATTERNS.iter().map(|pattern| pattern.name).collect();
+                format!("unknown pattern `{}`, expected one of: {}", name, names.join(", "))
+            })
+}
+
+/// The text printed by `--list-patterns`.
+fn list_patterns() -> String {
+    let mut text = String::new();
+    for pattern in PATTERNS {
+        text += &format!("{:<10}{}\n", pattern.name, pattern.description);
+        for (name, default) in pattern.args {
+            text += &format!("{:<10}  {} [default: {}]\n", "", name, default);
+        }
+    }
+    text
+}
+
+/// The optional Cargo features this binary was built with, as listed by
+/// `build.rs`.
+const FEATURES: &[&str] = include!(concat!(env!("OUT_DIR"), "/features.rs"));
+
+/// The text printed by `--version`.
+fn version() -> String {
+    let features = if FEATURES.is_empty() { "none".to_string() } else { FEATURES.join(", ") };
+    format!("{} {} (features: {})", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), features)
+}
+
+/// Columns of output when they aren't given and can't be detected.
+const DEFAULT_COLS: i32 = 66;
+
+/// Never shrink the art below this many columns to fit a terminal.
+const MIN_DETECTED_COLS: i32 = 20;
+
+/// Ask the terminal attached to stdout how many columns wide it is.
+#[cfg(unix)]
+fn detect_cols() -> Option<u16> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // TIOCGWINSZ only writes into `size`, which is a valid winsize.
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_col > 0 {
+        Some(size.ws_col)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn detect_cols() -> Option<u16> {
+    None
+}
+
+/// Choose how many columns of output to draw.
+///
+/// An explicit number always wins. Otherwise, when writing to a terminal we
+/// fit its width with a little room to spare, after the art is scaled up by
+/// `scale`, and fall back to `DEFAULT_COLS` when that doesn't work out.
+fn resolve_cols<F>(explicit: Option<i32>, to_tty: bool, scale: i32, detect: F) -> i32
+    where F: FnOnce() -> Option<u16>
+{
+    if let Some(cols) = explicit {
+        return cols;
+    }
+    if !to_tty {
+        return DEFAULT_COLS;
+    }
+    match detect() {
+        Some(cols) => ((cols as i32 - 2) / scale).max(MIN_DETECTED_COLS),
+        None       => DEFAULT_COLS,
+    }
+}
+
+/// Everything that can be set from the command line.
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    /// `None` means to fit the terminal, see `resolve_cols`.
+    console_cols:    Option<i32>,
+    squares_per_row: i32,
+    squares_per_col: i32,
+    seed:            Option<u64>,
+    output:          Option<String>,
+    format:          Format,
+    invert:          bool,
+    color:           ColorChoice,
+    /// Frames per second, when animating.
+    animate:         Option<f64>,
+    /// Wait for keys and redraw, see `interact`.
+    interactive:     bool,
+    /// The name of one of `PATTERNS`.
+    pattern:         &'static str,
+    /// The numbers after the common positional arguments, which belong to
+    /// `pattern`. Any that are missing take the pattern's defaults.
+    pattern_args:    Vec<f64>,
+    list_patterns:   bool,
+    version:         bool,
+    /// How many times larger to draw each pixel.
+    scale:           i32,
+    /// Time this many renders instead of writing any art.
+    bench:           Option<i32>,
+    /// The line under text formats, with `{seed}` standing in for the seed.
+    /// `None` leaves it off.
+    caption:         Option<String>,
+    /// Characters for on and off pixels in ascii, when not the defaults.
+    on_char:         Option<char>,
+    off_char:        Option<char>,
+    help:            bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            console_cols:    None,
+            squares_per_row: 8,
+            squares_per_col: 12,
+            seed:            None,
+            output:          None,
+            format:          Format::Braille,
+            invert:          false,
+            color:           ColorChoice::Never,
+            animate:         None,
+            interactive:     false,
+            pattern:         PATTERNS[0].name,
+            pattern_args:    vec![],
+            list_patterns:   false,
+            version:         false,
+            scale:           1,
+            bench:           None,
+            caption:         Some(lolwut::ATTRIBUTION.to_string()),
+            on_char:         None,
+            off_char:        None,
+            help:            false,
+        }
+    }
+}
+
+fn usage(program_name: &str) -> String {
+    let defaults = Options::default();
+    let mut text = String::new();
+    text += &format!("Usage: {} [OPTIONS] [COLS [SQUARES_PER_ROW [SQUARES_PER_COL [PATTERN_ARGS...]]]]\n",
+                     program_name);
+    text += &format!("  e.g. {} 66 8 12\n", program_name);
+    text += &format!("       {} convert [OPTIONS] IMAGE    (see `{} convert --help`)\n",
+                     program_name, program_name);
+    text += "\n";
+    text += "Options:\n";
+    text += "  -c, --cols N             columns of output in the console window\n";
+    text += &format!("                           [default: fit the terminal, or {}]\n", DEFAULT_COLS);
+    text += &format!("      --squares-per-row N  squares per row (wide) [default: {}]\n",
+                     defaults.squares_per_row);
+    text += &format!("      --squares-per-col N  squares per column (tall) [default: {}]\n",
+                     defaults.squares_per_col);
+    text += "      --rows N             same as --squares-per-col\n";
+    text += "      --seed N             seed for the random number generator, in decimal or\n";
+    text += "                           0x-prefixed hex [default: random, reported on stderr]\n";
+    text += "  -o, --output FILE        write the art to FILE instead of stdout, `-` means stdout\n";
+    text += "                           [default: -]\n";
+    text += &format!("  -f, --format FORMAT      one of: {} [default: {}]\n",
+                     Format::names(), defaults.format);
+    text += "                           binary formats are not written to a terminal\n";
+    text += "      --charset CHARSET    the same as --format, but only braille, ascii, or blocks\n";
+    text += "      --json               the same as --format json: the sizes, seed, options, and\n";
+    text += "                           run-length encoded rows of pixels, for programs\n";
+    text += &format!("      --on-char C          with ascii, the character for on pixels [default: {:?}]\n",
+                     DEFAULT_ON_CHAR);
+    text += &format!("      --off-char C         with ascii, the character for off pixels [default: {:?}]\n",
+                     DEFAULT_OFF_CHAR);
+    text += "      --invert             swap on and off pixels, for light backgrounds\n";
+    text += &format!("      --scale N            draw every pixel N times larger [default: {}]\n",
+                     defaults.scale);
+    text += "      --color COLOR        a color name (red, bright-blue, ...), #rrggbb, auto, or\n";
+    text += "                           never. auto colors unless NO_COLOR is set. Pipes and\n";
+    text += "                           files are never colored [default: never]\n";
+    text += &format!("      --animate [FPS]      redraw new art in place until Ctrl-C [default: {}]\n",
+                     DEFAULT_FPS);
+    text += "                           with --seed, the art builds up row by row instead\n";
+    text += "  -i, --interactive        wait for keys after drawing: space or enter for new\n";
+    text += "                           art, s to show the seed, + or - to change the squares\n";
+    text += "                           per row, q to quit\n";
+    text += "      --caption TEXT       the line under the art, where {seed} is replaced by the\n";
+    text += "                           seed [default: the attribution to Georg Nees]\n";
+    text += "      --no-caption         leave the line under the art off\n";
+    text += "      --bench N            time N renders, stage by stage, and report on stderr\n";
+    text += "                           instead of writing the art\n";
+    text += &format!("  -p, --pattern NAME       what to draw [default: {}]\n", defaults.pattern);
+    text += "      --list-patterns      list the patterns and the PATTERN_ARGS they take\n";
+    text += "  -V, --version            print the version and optional features built in\n";
+    text += "  -h, --help               print this help\n";
+    text += "\n";
+    text += "Positional arguments are the same as --cols, --squares-per-row, and\n";
+    text += "--squares-per-col, in that order. A number right after --animate is taken\n";
+    text += "as its FPS, so give positional arguments before it. All three can be given\n";
+    text += "at once as COLSxPER_ROWxPER_COL[@SEED], like 66x8x12, or 66x8x12@42 with a\n";
+    text += "decimal seed on the end.\n";
+    text += "\n";
+    text += "Environment:\n";
+    text += "  SCHOTTER_COLS, SCHOTTER_ROWS_X, SCHOTTER_ROWS_Y, SCHOTTER_SEED, SCHOTTER_FORMAT\n";
+    text += "      the same as --cols, --squares-per-row, --squares-per-col, --seed, and\n";
+    text += "      --format, but overridden by any arguments. Bad values are ignored.\n";
+    text
+}
+
+/// Parse one of the sizes of the artwork, which must be at least 1.
+fn parse_number(name: &str, value: &str) -> Result<i32, String> {
+    match value.parse::<i32>() {
+        Ok(number) if number > 0 => Ok(number),
+        Ok(_)  => Err(format!("invalid value for {}: `{}` must be at least 1", name, value)),
+        Err(_) => Err(format!("invalid value for {}: `{}` is not a number", name, value)),
+    }
+}
+
+fn parse_seed(name: &str, value: &str) -> Result<u64, String> {
+    let parsed = if value.starts_with("0x") || value.starts_with("0X") {
+        u64::from_str_radix(&value[2..], 16)
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|_| format!("invalid value for {}: `{}` is not a decimal or 0x-prefixed hex number",
+                               name, value))
+}
+
+/// Frames per second for `--animate` without a value.
+const DEFAULT_FPS: f64 = 4.0;
+
+fn parse_fps(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(fps) if fps.is_finite() && fps > 0.0 => Ok(fps),
+        _ => Err(format!("invalid value for --animate: `{}` is not a positive number", value)),
+    }
+}
+
+/// Characters for pixels in ascii, matching `Canvas::render_ascii`.
+const DEFAULT_ON_CHAR: char = '#';
+const DEFAULT_OFF_CHAR: char = ' ';
+
+fn parse_charset(value: &str) -> Result<Format, String> {
+    match value.parse::<Format>() {
+        Ok(format) if format.is_picture() => Ok(format),
+        _ => Err(format!("unknown charset `{}`, expected one of: braille, ascii, blocks", value)),
+    }
+}
+
+/// Parse a pixel character for ascii, which has to stay 7-bit and printable.
+fn parse_pixel_char(name: &str, value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c == ' ' || c.is_ascii_graphic() => Ok(c),
+        _ => Err(format!("invalid value for {}: `{}` is not a single printable ASCII character",
+                         name, value)),
+    }
+}
+
+/// Take the value of `flag`, either from `--flag=value` or the next argument.
+fn flag_value<'a, I>(flag: &str, inline_value: Option<&'a str>, rest: &mut I)
+    -> Result<&'a str, String>
+    where I: Iterator<Item = &'a String>
+{
+    match inline_value {
+        Some(value) => Ok(value),
+        None => rest.next()
+                    .map(|value| value.as_str())
+                    .ok_or_else(|| format!("missing value for {}", flag)),
+    }
+}
+
+/// Work out the options from the command line arguments, not including the
+/// program name, and the environment variables `env` looks up.
+///
+/// Arguments take precedence over the environment, which takes precedence
+/// over the defaults. Bad arguments are an error, but bad environment
+/// variables are only warned about, in the returned list, and ignored.
+fn resolve_options<E>(args: &[String], env: &E) -> Result<(Options, Vec<String>), String>
+    where E: Fn(&str) -> Option<String>
+{
+    let (defaults, warnings) = env_options(env);
+    Ok((parse_args_over(args, defaults)?, warnings))
+}
+
+/// The defaults, with any settings from `SCHOTTER_*` environment variables
+/// applied, and warnings about those that couldn't be used.
+fn env_options<E>(env: &E) -> (Options, Vec<String>)
+    where E: Fn(&str) -> Option<String>
+{
+    let mut options = Options::default();
+    let mut warnings = vec![];
+    // An empty variable is the same as an unset one.
+    let var = |name: &str| env(name).filter(|value| !value.is_empty());
+    let mut apply = |result: Result<(), String>| {
+        if let Err(message) = result {
+            warnings.push(format!("{}, ignoring it", message));
+        }
+    };
+
+    if let Some(value) = var("SCHOTTER_COLS") {
+        apply(parse_number("SCHOTTER_COLS", &value).map(|cols| options.console_cols = Some(cols)));
+    }
+    if let Some(value) = var("SCHOTTER_ROWS_X") {
+        apply(parse_number("SCHOTTER_ROWS_X", &value).map(|n| options.squares_per_row = n));
+    }
+    if let Some(value) = var("SCHOTTER_ROWS_Y") {
+        apply(parse_number("SCHOTTER_ROWS_Y", &value).map(|n| options.squares_per_col = n));
+    }
+    if let Some(value) = var("SCHOTTER_SEED") {
+        apply(parse_seed("SCHOTTER_SEED", &value).map(|seed| options.seed = Some(seed)));
+    }
+    if let Some(value) = var("SCHOTTER_FORMAT") {
+        apply(value.parse()
+                   .map(|format| options.format = format)
+                   .map_err(|e| format!("invalid value for SCHOTTER_FORMAT: {}", e)));
+    }
+
+    (options, warnings)
+}
+
+/// Parse the command line arguments, not including the program name.
+#[cfg(test)]
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    parse_args_over(args, Options::default())
+}
+
+/// Parse the command line arguments over the top of `defaults`.
+fn parse_args_over(args: &[String], defaults: Options) -> Result<Options, String> {
+    const POSITIONAL_NAMES: [&str; 3] = ["--cols", "--squares-per-row", "--squares-per-col"];
+
+    let mut options = defaults;
+    // Tracks which of the three numbers have been given, so that passing one
+    // twice - by flag and by position, say - is an error rather than a guess.
+    let mut given: [Option<i32>; 3] = [None; 3];
+    let mut seed_given = false;
+    let mut positionals = vec![];
+
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(i) if arg.starts_with("--") => (&arg[..i], Some(&arg[i+1..])),
+            _                                => (arg.as_str(), None),
+        };
+
+        let slot = match flag {
+            "help" | "h" | "-h" | "--help" => {
+                options.help = true;
+                continue;
+            }
+            "-c" | "--cols"                  => 0,
+            "--squares-per-row"              => 1,
+            "--rows" | "--squares-per-col"   => 2,
+            "--seed" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.seed = Some(parse_seed(flag, value)?);
+                seed_given = true;
+                continue;
+            }
+            "--color" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.color = value.parse()?;
+                continue;
+            }
+            "--invert" => {
+                options.invert = true;
+                continue;
+            }
+            "-p" | "--pattern" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.pattern = find_pattern(value)?.name;
+                continue;
+            }
+            "--caption" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.caption = Some(value.to_string());
+                continue;
+            }
+            "--scale" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.scale = parse_number(flag, value)?;
+                continue;
+            }
+            "--bench" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.bench = Some(parse_number(flag, value)?);
+                continue;
+            }
+            "--no-caption" => {
+                options.caption = None;
+                continue;
+            }
+            "--list-patterns" => {
+                options.list_patterns = true;
+                continue;
+            }
+            "-V" | "--version" => {
+                options.version = true;
+                continue;
+            }
+            "-i" | "--interactive" => {
+                options.interactive = true;
+                continue;
+            }
+            "--animate" => {
+                // The value is optional, so only a number is taken as one.
+                let value = match inline_value {
+                    Some(value) => Some(value),
+                    None => iter.next_if(|next| next.parse::<f64>().is_ok())
+                                .map(|next| next.as_str()),
+                };
+                options.animate = Some(match value {
+                    Some(value) => parse_fps(value)?,
+                    None        => DEFAULT_FPS,
+                });
+                continue;
+            }
+            "-f" | "--format" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.format = value.parse()?;
+                continue;
+            }
+            "--json" => {
+                options.format = Format::Json;
+                continue;
+            }
+            "--charset" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.format = parse_charset(value)?;
+                continue;
+            }
+            "--on-char" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.on_char = Some(parse_pixel_char(flag, value)?);
+                continue;
+            }
+            "--off-char" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.off_char = Some(parse_pixel_char(flag, value)?);
+                continue;
+            }
+            "-o" | "--output" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                options.output = Some(value.to_string());
+                continue;
+            }
+            _ if flag.starts_with('-') && flag.parse::<i32>().is_err() => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ => {
+                positionals.push(arg.as_str());
+                continue;
+            }
+        };
+
+        let value = flag_value(flag, inline_value, &mut iter)?;
+        if given[slot].is_some() {
+            return Err(format!("{} given more than once", POSITIONAL_NAMES[slot]));
+        }
+        given[slot] = Some(parse_number(flag, value)?);
+    }
+
+    // A first positional like `66x8x12@42` stands in for all three numbers,
+    // and the seed.
+    let mut common = positionals.len().min(given.len());
+    if let Some(first) = positionals.first().filter(|first| first.contains(['x', '@'])) {
+        let params: SchotterParams = first.parse().map_err(|err: ParseParamsError| err.to_string())?;
+        let values = [params.console_cols, params.squares_per_row, params.squares_per_col];
+        for (slot, &value) in values.iter().enumerate() {
+            if given[slot].is_some() {
+                return Err(format!("{} given more than once", POSITIONAL_NAMES[slot]));
+            }
+            given[slot] = Some(value);
+        }
+        if let Some(seed) = params.seed {
+            if seed_given {
+                return Err("--seed given more than once".to_string());
+            }
+            options.seed = Some(seed);
+        }
+        positionals.remove(0);
+        common = 0;
+    }
+
+    // Anything after the common positional arguments belongs to the pattern.
+    let pattern = find_pattern(options.pattern)?;
+    let extra = positionals.split_off(common);
+    if extra.len() > pattern.args.len() {
+        return Err(format!("unexpected argument `{}`, the {} pattern takes {} extra argument(s)",
+                           extra[pattern.args.len()], pattern.name, pattern.args.len()));
+    }
+    for (value, (name, _)) in extra.into_iter().zip(pattern.args) {
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() && number > 0.0 => options.pattern_args.push(number),
+            _ => return Err(format!("invalid value for {} {}: `{}` is not a positive number",
+                                    pattern.name, name, value)),
+        }
+    }
+
+    for (slot, value) in positionals.into_iter().enumerate() {
+        if given[slot].is_some() {
+            return Err(format!("{} given more than once", POSITIONAL_NAMES[slot]));
+        }
+        given[slot] = Some(parse_number(POSITIONAL_NAMES[slot], value)?);
+    }
+
+    if let Some(cols) = given[0]    { options.console_cols    = Some(cols); }
+    if let Some(per_row) = given[1] { options.squares_per_row = per_row; }
+    if let Some(per_col) = given[2] { options.squares_per_col = per_col; }
+
+    if options.bench.is_some() && options.animate.is_some() {
+        return Err("--bench and --animate can't be used together".to_string());
+    }
+    if options.interactive && (options.bench.is_some() || options.animate.is_some()) {
+        return Err("--interactive can't be used with --bench or --animate".to_string());
+    }
+    if options.interactive && options.output.is_some() {
+        return Err("--interactive draws on the terminal, so it can't be used with --output"
+                   .to_string());
+    }
+    if (options.on_char.is_some() || options.off_char.is_some()) && options.format != Format::Ascii {
+        return Err(format!("--on-char and --off-char only apply to ascii, not {}", options.format));
+    }
+
+    Ok(options)
+}
+
+fn main() {
+    let program_name: String = args().next().unwrap_or_else(|| "lolwut".into());
+    let arguments: Vec<String> = args().skip(1).collect();
+
+    if arguments.first().map(String::as_str) == Some("convert") {
+        convert_main(&program_name, &arguments[1..]);
+        return;
+    }
+
+    let options = match resolve_options(&arguments, &|name| std::env::var(name).ok()) {
+        Ok((options, warnings)) => {
+            for warning in warnings {
+                eprintln!("warning: {}", warning);
+            }
+            options
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!();
+            eprint!("{}", usage(&program_name));
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        print!("{}", usage(&program_name));
+        return;
+    }
+    if options.version {
+        println!("{}", version());
+        return;
+    }
+    if options.list_patterns {
+        print!("{}", list_patterns());
+        return;
+    }
+
+    let OutputMode { to_tty, color } = match output_mode(&options, Terminals::detect(),
+                                                         no_color_env()) {
+        Ok(mode) => mode,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        }
+    };
+    let options = Options {
+        console_cols: Some(resolve_cols(options.console_cols, to_tty, options.scale, detect_cols)),
+        ..options
+    };
+
+    if let Some(iterations) = options.bench {
+        if let Err(e) = bench(&options, color, iterations as u32, &mut io::stderr()) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if options.interactive {
+        if let Err(e) = run_interactive(&options, color) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let result = open_output(options.output.as_deref()).and_then(|mut out| {
+        match options.animate {
+            Some(fps) => {
+                install_interrupt_handler();
+                // Without it, a Windows console prints the escapes as text.
+                // There's no console to enable when writing to a file.
+                let _ = lolwut::term::enable_virtual_terminal();
+                animate(&options, color, fps, &mut out, &mut || !INTERRUPTED.load(Ordering::SeqCst))
+            }
+            None => run(&options, color, &mut out, &mut io::stderr()),
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Options for `convert`, which renders an image file instead of a pattern.
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, PartialEq)]
+struct ConvertOptions {
+    path: String,
+    dither: bool,
+    threshold: u8,
+    /// Columns of output, or fit the terminal when `None`.
+    width: Option<i32>,
+    help: bool,
+}
 
-fn print_help() {
-    let program_name: String = args().nth(0).unwrap();
-    eprintln!("Usage: {} 66 8 12", program_name);
-    eprintln!("  66 columns of output in the console window");
-    eprintln!("  8 squares per row (wide)");
-    eprintln!("  12 squares per column (tall)");
+#[cfg(feature = "image")]
+fn convert_usage(program_name: &str) -> String {
+    let mut text = String::new();
+    text += &format!("Usage: {} convert [OPTIONS] IMAGE\n", program_name);
+    text += "\n";
+    text += "Render a PNG or JPEG image in braille, dark parts as dots.\n";
+    text += "\n";
+    text += "Options:\n";
+    text += "  -w, --width N            columns of output\n";
+    text += &format!("                           [default: fit the terminal, or {}]\n", DEFAULT_COLS);
+    text += "      --threshold N        pixels darker than N, from 0 to 255, are dots\n";
+    text += "                           [default: 128]\n";
+    text += "      --dither             dither around the threshold to show shades of gray\n";
+    text += "  -h, --help               print this help\n";
+    text
 }
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    if let Some(arg) = args().nth(1) {
-        match arg.as_str() {
-            "help" |
-            "--help" |
-            "-h" |
-            "h" => {
-                print_help();
-                return Ok(());
+#[cfg(feature = "image")]
+fn parse_convert_args(args: &[String]) -> Result<ConvertOptions, String> {
+    let mut path = None;
+    let mut options = ConvertOptions {
+        path: String::new(),
+        dither: false,
+        threshold: 128,
+        width: None,
+        help: false,
+    };
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(i) if arg.starts_with("--") => (&arg[..i], Some(&arg[i + 1..])),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "-h" | "--help" => options.help = true,
+            "--dither" => options.dither = true,
+            "-w" | "--width" => {
+                let value = flag_value(flag, inline_value, &mut rest)?;
+                options.width = Some(parse_number(flag, value)?);
+            }
+            "--threshold" => {
+                let value = flag_value(flag, inline_value, &mut rest)?;
+                options.threshold = value.parse().map_err(|_| {
+                    format!("invalid value for --threshold: `{}` is not a number from 0 to 255",
+                            value)
+                })?;
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option `{}`", arg));
             }
-            _ => {}
+            _ if path.is_none() => path = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    match path {
+        Some(path) => Ok(ConvertOptions { path, ..options }),
+        None if options.help => Ok(options),
+        None => Err("missing the IMAGE to convert".to_string()),
+    }
+}
+
+#[cfg(feature = "image")]
+fn convert_main(program_name: &str, args: &[String]) {
+    let options = match parse_convert_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!();
+            eprint!("{}", convert_usage(program_name));
+            process::exit(2);
+        }
+    };
+    if options.help {
+        print!("{}", convert_usage(program_name));
+        return;
+    }
+
+    let cols = resolve_cols(options.width, io::stdout().is_terminal(), 1, detect_cols);
+    if let Err(e) = convert(&options, cols, &mut io::stdout()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "image"))]
+fn convert_main(_program_name: &str, _args: &[String]) {
+    eprintln!("error: convert needs the `image` feature, rebuild with `--features image`");
+    process::exit(2);
+}
+
+/// Render the image at `options.path` in braille, `cols` characters wide.
+#[cfg(feature = "image")]
+fn convert(options: &ConvertOptions, cols: i32, out: &mut dyn Write)
+    -> Result<(), Box<dyn error::Error>>
+{
+    use image::imageops::FilterType;
+    use lolwut::Halftone;
+
+    let image = image::open(&options.path)
+                      .map_err(|e| format!("could not read `{}`: {}", options.path, e))?
+                      .to_luma8();
+
+    // A braille character is 2 dots wide and 4 tall, in a cell about twice as
+    // tall as it is wide, so its dots are roughly square and the image can be
+    // scaled by the same amount both ways.
+    let width = cols as u32 * 2;
+    let height = (image.height() as u64 * width as u64 / image.width().max(1) as u64).max(1);
+    let height = height.min(u32::MAX as u64) as u32;
+    let image = image::imageops::resize(&image, width, height, FilterType::Triangle);
+
+    let halftone = if options.dither {
+        Halftone::Dither(options.threshold)
+    } else {
+        Halftone::Threshold(options.threshold)
+    };
+    let canvas = Canvas::from_grayscale(width, height, image.as_raw(), halftone)?;
+
+    out.write_all(canvas.render().as_bytes())?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Whether each of the standard streams is attached to a terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Terminals {
+    stdin:  bool,
+    stdout: bool,
+}
+
+impl Terminals {
+    fn detect() -> Terminals {
+        Terminals {
+            stdin:  io::stdin().is_terminal(),
+            stdout: io::stdout().is_terminal(),
+        }
+    }
+}
+
+/// How the art is written, see `output_mode`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct OutputMode {
+    /// Whether the art goes to a terminal, rather than a pipe or a file.
+    /// Only then is the terminal's width worth detecting.
+    to_tty: bool,
+    color:  Option<Color>,
+}
+
+/// Decide how to write the art described by `options`, given which streams
+/// are `terminals`, or explain why it can't be written at all.
+///
+/// Art going to a terminal can be colored and redrawn in place. Art going to
+/// a pipe or a file gets no escape sequences: no color, whatever `--color`
+/// says, and no cursor movement, so `--animate` and `--interactive` are
+/// errors.
+fn output_mode(options: &Options, terminals: Terminals, no_color: bool)
+    -> Result<OutputMode, String>
+{
+    check_destination(options, terminals.stdout)?;
+
+    let to_tty = terminals.stdout && matches!(options.output.as_deref(), None | Some("-"));
+    if options.animate.is_some() && !to_tty {
+        return Err("--animate redraws in place, so it needs to write to a terminal".to_string());
+    }
+    if options.interactive && !(to_tty && terminals.stdin) {
+        return Err("--interactive needs a terminal for both input and output".to_string());
+    }
+
+    Ok(OutputMode {
+        to_tty,
+        color: resolve_color(options.color, to_tty, no_color),
+    })
+}
+
+/// Refuse to dump binary formats into a terminal, unless the user explicitly
+/// asked for it with `--output`.
+fn check_destination(options: &Options, stdout_is_tty: bool) -> Result<(), String> {
+    let writes_art = options.bench.is_none();
+    if writes_art && options.format.is_binary() && options.output.is_none() && stdout_is_tty {
+        return Err(format!("refusing to write {} data to a terminal, use --output", options.format));
+    }
+    if !options.format.is_picture() && options.animate.is_some() {
+        return Err(format!("--animate can't redraw {} data, use a text format", options.format));
+    }
+    if !options.format.is_picture() && options.interactive {
+        return Err(format!("--interactive can't redraw {} data, use a text format",
+                           options.format));
+    }
+    Ok(())
+}
+
+/// Open the destination for the art: a file at `path`, or stdout when there
+/// is no path or it's `-`. Files are created, or truncated if they exist.
+fn open_output(path: Option<&str>) -> Result<Box<dyn Write>, Box<dyn error::Error>> {
+    match path {
+        None | Some("-") => Ok(Box::new(io::stdout())),
+        Some(path) => {
+            let file = File::create(path)
+                           .map_err(|e| format!("could not create `{}`: {}", path, e))?;
+            Ok(Box::new(BufWriter::new(file)))
         }
     }
+}
+
+/// Generate the artwork described by `options` and write it to `out`.
+/// Diagnostics, like the randomly chosen seed, go to `err`.
+fn run(options: &Options, color: Option<Color>, out: &mut dyn Write, err: &mut dyn Write)
+    -> Result<(), Box<dyn error::Error>>
+{
+    let seed = match options.seed {
+        Some(seed) => seed,
+        None => {
+            // Report the seed so that a happy accident can be reproduced.
+            let seed = random();
+            writeln!(err, "seed: {}", seed)?;
+            seed
+        }
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let canvas = finish(options, draw_pattern(options, &mut rng)?)?;
+
+    out.write_all(&encode(options, &canvas, color, seed))?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn config(options: &Options) -> SchotterConfig {
+    // The library measures width in braille columns, 2 pixels each, so
+    // narrower characters need fewer of them to fill the same console.
+    let cols = options.console_cols.unwrap_or(DEFAULT_COLS);
+    let cols = (cols * options.format.pixels_per_col() / 2).max(1);
+    SchotterConfig::new(cols, options.squares_per_row, options.squares_per_col)
+}
+
+/// Draw the pattern `options` asks for, with its extra arguments.
+fn draw_pattern(options: &Options, rng: &mut StdRng) -> Result<Canvas, CanvasError> {
+    let mut canvas = blank_canvas(&config(options))?;
+    draw_pattern_on(&mut canvas, options, rng)?;
+    Ok(canvas)
+}
+
+/// Draw the pattern `options` asks for onto `canvas`, which should be blank
+/// and the size `blank_canvas` makes.
+fn draw_pattern_on(canvas: &mut Canvas, options: &Options, rng: &mut StdRng)
+    -> Result<(), CanvasError>
+{
+    let pattern = find_pattern(options.pattern).expect("patterns are checked when parsing");
+    let mut args = options.pattern_args.clone();
+    args.extend(pattern.args[args.len()..].iter().map(|&(_, default)| default));
+    (pattern.draw)(canvas, &config(options), &args, rng)
+}
 
-    if args().len() > 4 {
-        print_help();
-        return Ok(());
+/// Apply the options that change a drawing after it's drawn.
+fn finish(options: &Options, mut canvas: Canvas) -> Result<Canvas, CanvasError> {
+    // Inverting the canvas itself, rather than any one rendering of it, means
+    // every output format respects it.
+    if options.invert {
+        canvas.invert();
     }
+    if options.scale > 1 {
+        canvas = canvas.scaled(options.scale as u32)?;
+    }
+    Ok(canvas)
+}
 
-    let console_cols    = args().nth(1).and_then(|s| s.parse().ok()).unwrap_or(66);
-    let squares_per_row = args().nth(2).and_then(|s| s.parse().ok()).unwrap_or(8);
-    let squares_per_col = args().nth(3).and_then(|s| s.parse().ok()).unwrap_or(12);
+/// Time generating the artwork `iterations` times, stage by stage, and
+/// write a table of the results to `err`. Nothing is written anywhere else.
+fn bench(options: &Options, color: Option<Color>, iterations: u32, err: &mut dyn Write)
+    -> Result<(), Box<dyn error::Error>>
+{
+    let config = config(options);
+    let mut rng = StdRng::seed_from_u64(options.seed.unwrap_or_else(random));
+    let mut stages = [("allocate", Duration::ZERO),
+                      ("draw",     Duration::ZERO),
+                      ("render",   Duration::ZERO)];
+    let mut bytes = 0;
 
-    let canvas = Canvas::create_and_render_schotter(console_cols, squares_per_row, squares_per_col)?;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let mut canvas = blank_canvas(&config)?;
+        let allocated = Instant::now();
+        draw_pattern_on(&mut canvas, options, &mut rng)?;
+        let canvas = finish(options, canvas)?;
+        let drawn = Instant::now();
+        // Keep the output around, so rendering can't be optimized away.
+        bytes += encode(options, &canvas, color, 0).len();
+        let rendered = Instant::now();
+
+        stages[0].1 += allocated - start;
+        stages[1].1 += drawn - allocated;
+        stages[2].1 += rendered - drawn;
+    }
 
-    print!("{}", canvas.render());
-    println!("Georg Nees - schotter, plotter on paper, 1968");
+    let total: Duration = stages.iter().map(|&(_, time)| time).sum();
+    let ms = |time: Duration| time.as_secs_f64() * 1000.0;
+    writeln!(err, "{} iterations of {} at {}x{}x{}, {} bytes of {} each",
+             iterations, options.pattern, config.console_cols, config.squares_per_row,
+             config.squares_per_col, bytes / iterations as usize, options.format)?;
+    writeln!(err, "{:<10}{:>12}{:>12}", "stage", "total ms", "each ms")?;
+    for &(name, time) in stages.iter().chain(Some(("total", total)).iter()) {
+        writeln!(err, "{:<10}{:>12.3}{:>12.3}", name, ms(time), ms(time) / iterations as f64)?;
+    }
 
     Ok(())
 }
+
+/// Encode `canvas`, drawn from `seed`, in the format `options` asks for.
+/// Text formats are followed by the caption, if there is one.
+fn encode(options: &Options, canvas: &Canvas, color: Option<Color>, seed: u64) -> Vec<u8> {
+    let text = match options.format {
+        Format::Braille => match color {
+            Some(color) => canvas.render_ansi(color),
+            None        => canvas.render(),
+        },
+        Format::Ascii   => canvas.render_with_chars(options.on_char.unwrap_or(DEFAULT_ON_CHAR),
+                                                    options.off_char.unwrap_or(DEFAULT_OFF_CHAR)),
+        Format::Blocks  => canvas.render_blocks(),
+        Format::Pbm     => return canvas.to_pbm(),
+        Format::Json    => return to_json(options, canvas, seed).into_bytes(),
+    };
+    let text = match (color, options.format) {
+        (Some(color), Format::Ascii) | (Some(color), Format::Blocks) => color.paint(&text),
+        _ => text,
+    };
+    match options.caption {
+        Some(ref caption) => format!("{}{}\n", text, caption.replace("{seed}", &seed.to_string())),
+        None              => text,
+    }.into_bytes()
+}
+
+/// Describe the art as JSON, for programs rather than people.
+///
+/// Fields may be added, but these won't change:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "width": <pixels>,
+///   "height": <pixels>,
+///   "seed": "<decimal>",
+///   "pattern": "<name>",
+///   "params": {
+///     "cols": <columns>,
+///     "squares_per_row": <number>,
+///     "squares_per_col": <number>,
+///     "scale": <number>,
+///     "invert": <bool>,
+///     "args": { "<pattern argument>": <number>, ... }
+///   },
+///   "rows": [
+///     [[<pixel value>, <count>], ...],
+///     ...
+///   ]
+/// }
+/// ```
+///
+/// The seed is a string, since not every JSON parser can hold a whole u64 in
+/// a number. Each row lists its runs of equal pixels from left to right, and
+/// their counts add up to the width. Pixel values are 1 for on and 0 for off.
+/// Colors and the caption are left out.
+fn to_json(options: &Options, canvas: &Canvas, seed: u64) -> String {
+    let pattern = find_pattern(options.pattern).expect("patterns are checked when parsing");
+    let args: Vec<String> = pattern.args.iter().enumerate().map(|(i, &(name, default))| {
+        format!("{}: {}", json_string(name), options.pattern_args.get(i).unwrap_or(&default))
+    }).collect();
+
+    let rows: Vec<String> = canvas.pixel_run_length().iter().map(|runs| {
+        let runs: Vec<String> = runs.iter()
+                                    .map(|&(value, count)| format!("[{}, {}]", value, count))
+                                    .collect();
+        format!("    [{}]", runs.join(", "))
+    }).collect();
+
+    let mut json = String::from("{\n");
+    json += "  \"version\": 1,\n";
+    json += &format!("  \"width\": {},\n", canvas.width());
+    json += &format!("  \"height\": {},\n", canvas.height());
+    json += &format!("  \"seed\": \"{}\",\n", seed);
+    json += &format!("  \"pattern\": {},\n", json_string(pattern.name));
+    json += "  \"params\": {\n";
+    json += &format!("    \"cols\": {},\n", options.console_cols.unwrap_or(DEFAULT_COLS));
+    json += &format!("    \"squares_per_row\": {},\n", options.squares_per_row);
+    json += &format!("    \"squares_per_col\": {},\n", options.squares_per_col);
+    json += &format!("    \"scale\": {},\n", options.scale);
+    json += &format!("    \"invert\": {},\n", options.invert);
+    json += &format!("    \"args\": {{{}}}\n", args.join(", "));
+    json += "  },\n";
+    json += &format!("  \"rows\": [\n{}\n  ]\n", rows.join(",\n"));
+    json += "}\n";
+    json
+}
+
+/// Quote `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"'  => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// How many lines the cursor moves down while `frame` is written.
+fn line_count(frame: &[u8]) -> usize {
+    frame.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+/// Set by the SIGINT handler to ask the animation to stop.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Catch Ctrl-C, so the animation can stop cleanly and restore the cursor.
+#[cfg(unix)]
+fn install_interrupt_handler() {
+    extern "C" fn on_interrupt(_signal: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    let handler: extern "C" fn(libc::c_int) = on_interrupt;
+    // The handler only stores to an atomic, which is async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_interrupt_handler() {}
+
+/// Shows the cursor again when dropped, however the animation ends.
+struct CursorGuard<'a> {
+    out: &'a mut dyn Write,
+}
+
+impl<'a> Drop for CursorGuard<'a> {
+    fn drop(&mut self) {
+        // There's nowhere left to report a failure to.
+        let _ = self.out.write_all(SHOW_CURSOR.as_bytes());
+        let _ = self.out.flush();
+    }
+}
+
+/// Draw frames to `out` at roughly `fps`, each over the last, for as long as
+/// `keep_going` says to.
+///
+/// Without a seed every frame is new artwork. With one, each frame of
+/// Schotter draws one more row of the same artwork, so the chaos visibly
+/// ramps up, and then it starts over. Other patterns just hold still.
+fn animate(options: &Options,
+           color: Option<Color>,
+           fps: f64,
+           out: &mut dyn Write,
+           keep_going: &mut dyn FnMut() -> bool)
+    -> Result<(), Box<dyn error::Error>>
+{
+    let config = config(options);
+    let frame_time = Duration::from_secs_f64(1.0 / fps);
+
+    let guard = CursorGuard { out };
+    guard.out.write_all(HIDE_CURSOR.as_bytes())?;
+
+    let mut last_lines = 0;
+    let mut frame_number = 0;
+    while keep_going() {
+        let start = Instant::now();
+
+        let seed = options.seed.unwrap_or_else(random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let canvas = if options.seed.is_some() && options.pattern == "schotter" {
+            let mut canvas = blank_canvas(&config)?;
+            let rows = frame_number % config.squares_per_col + 1;
+            canvas.draw_schotter_rows(&config, 0..rows, &mut rng)?;
+            canvas
+        } else {
+            draw_pattern(options, &mut rng)?
+        };
+        let canvas = finish(options, canvas)?;
+
+        let frame = encode(options, &canvas, color, seed);
+        guard.out.write_all(cursor_up(last_lines).as_bytes())?;
+        guard.out.write_all(&frame)?;
+        guard.out.flush()?;
+        last_lines = line_count(&frame);
+        frame_number += 1;
+
+        // Sleep off whatever rendering didn't use, in short naps so that
+        // Ctrl-C is noticed promptly.
+        while let Some(left) = frame_time.checked_sub(start.elapsed()) {
+            if left.is_zero() || !keep_going() {
+                break;
+            }
+            thread::sleep(left.min(Duration::from_millis(50)));
+        }
+    }
+
+    Ok(())
+}
+
+/// What a key does in `--interactive` mode.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Action {
+    Regenerate,
+    ShowSeed,
+    MoreSquares,
+    FewerSquares,
+    Quit,
+}
+
+/// The action for `key`, if it has one.
+fn key_action(key: u8) -> Option<Action> {
+    match key {
+        b' ' | b'\r' | b'\n' => Some(Action::Regenerate),
+        b's' | b'S'         => Some(Action::ShowSeed),
+        b'+' | b'='         => Some(Action::MoreSquares),
+        b'-' | b'_'         => Some(Action::FewerSquares),
+        b'q' | b'Q'         => Some(Action::Quit),
+        _                   => None,
+    }
+}
+
+/// What an interactive session is showing.
+#[derive(Debug, Clone, PartialEq)]
+struct Session {
+    /// The seed of the artwork on screen.
+    seed:            u64,
+    squares_per_row: i32,
+    /// Whether the line under the artwork shows the seed, or the keys.
+    show_seed:       bool,
+}
+
+impl Session {
+    /// Update the session for `action`, with `new_seed` for new artwork.
+    /// Returns false when the session is over.
+    fn apply(&mut self, action: Action, new_seed: &mut dyn FnMut() -> u64) -> bool {
+        match action {
+            Action::Regenerate => {
+                self.seed = new_seed();
+                self.show_seed = false;
+            }
+            Action::ShowSeed => self.show_seed = true,
+            Action::MoreSquares => {
+                self.squares_per_row = self.squares_per_row.saturating_add(1);
+            }
+            Action::FewerSquares => {
+                self.squares_per_row = (self.squares_per_row - 1).max(1);
+            }
+            Action::Quit => return false,
+        }
+        true
+    }
+
+    /// The line under the artwork.
+    fn status(&self) -> String {
+        if self.show_seed {
+            format!("seed: {}", self.seed)
+        } else {
+            "space: new art  s: seed  +/-: squares per row  q: quit".to_string()
+        }
+    }
+}
+
+/// Draw artwork to `out`, then redraw it in place for each of `keys` that
+/// asks for it, until `q` or the keys run out.
+fn interact(options: &Options,
+            color: Option<Color>,
+            keys: &mut dyn Iterator<Item = u8>,
+            out: &mut dyn Write,
+            new_seed: &mut dyn FnMut() -> u64)
+    -> Result<(), Box<dyn error::Error>>
+{
+    let guard = CursorGuard { out };
+    guard.out.write_all(HIDE_CURSOR.as_bytes())?;
+
+    let mut session = Session {
+        seed: options.seed.unwrap_or_else(&mut *new_seed),
+        squares_per_row: options.squares_per_row,
+        show_seed: false,
+    };
+    let mut last_lines = 0;
+    loop {
+        let options = Options {
+            seed: Some(session.seed),
+            squares_per_row: session.squares_per_row,
+            ..options.clone()
+        };
+        let mut rng = StdRng::seed_from_u64(session.seed);
+        let canvas = finish(&options, draw_pattern(&options, &mut rng)?)?;
+
+        let mut frame = encode(&options, &canvas, color, session.seed);
+        frame.extend_from_slice(session.status().as_bytes());
+        frame.push(b'\n');
+
+        // The new artwork may be shorter than the last, so clear what's left.
+        guard.out.write_all(cursor_up(last_lines).as_bytes())?;
+        guard.out.write_all(CLEAR_BELOW.as_bytes())?;
+        guard.out.write_all(&frame)?;
+        guard.out.flush()?;
+        last_lines = line_count(&frame);
+
+        let action = loop {
+            match keys.next() {
+                Some(key) => if let Some(action) = key_action(key) { break action },
+                None => return Ok(()),
+            }
+        };
+        if !session.apply(action, &mut *new_seed) {
+            return Ok(());
+        }
+    }
+}
+
+/// Puts the terminal back the way it was when dropped, even when unwinding
+/// from a panic.
+#[cfg(unix)]
+struct RawMode {
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    /// Deliver keys from stdin as they're pressed, without echoing them.
+    ///
+    /// Reads give up after a tenth of a second without a key, so Ctrl-C can be
+    /// noticed. Output is left alone, so newlines still return the carriage.
+    fn enable() -> io::Result<RawMode> {
+        // tcgetattr fills in the whole struct before it's read.
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 1;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(RawMode { original })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Run `interact` on the terminal.
+#[cfg(unix)]
+fn run_interactive(options: &Options, color: Option<Color>) -> Result<(), Box<dyn error::Error>> {
+    install_interrupt_handler();
+    let _raw_mode = RawMode::enable()
+                           .map_err(|e| format!("could not set up the terminal: {}", e))?;
+
+    let mut stdin = io::stdin();
+    let mut keys = std::iter::from_fn(|| {
+        let mut key = [0];
+        while !INTERRUPTED.load(Ordering::SeqCst) {
+            match stdin.read(&mut key) {
+                Ok(1) => return Some(key[0]),
+                // Nothing was pressed in time.
+                Ok(_) => continue,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return None,
+            }
+        }
+        None
+    });
+    let _ = lolwut::term::enable_virtual_terminal();
+    interact(options, color, &mut keys, &mut io::stdout(), &mut random)
+}
+
+#[cfg(not(unix))]
+fn run_interactive(_options: &Options, _color: Option<Color>)
+    -> Result<(), Box<dyn error::Error>>
+{
+    Err("--interactive isn't supported on this platform yet".into())
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    fn options(console_cols: i32, squares_per_row: i32, squares_per_col: i32) -> Options {
+        Options {
+            console_cols: Some(console_cols),
+            squares_per_row,
+            squares_per_col,
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn check_valid_args() {
+        assert_eq!(parse(&[]), Ok(Options::default()));
+        assert_eq!(parse(&["100"]), Ok(options(100, 8, 12)));
+        assert_eq!(parse(&["100", "4", "6"]), Ok(options(100, 4, 6)));
+        assert_eq!(parse(&["--cols", "100"]), Ok(options(100, 8, 12)));
+        assert_eq!(parse(&["--cols=100", "--rows", "3"]), Ok(options(100, 8, 3)));
+        assert_eq!(parse(&["--squares-per-row", "2", "--squares-per-col=5", "-c", "9"]),
+                   Ok(options(9, 2, 5)));
+
+        for help in ["-h", "--help", "help", "h"].iter() {
+            assert!(parse(&[help]).unwrap().help);
+        }
+    }
+
+    #[test]
+    fn check_mixed_args() {
+        assert_eq!(parse(&["100", "--squares-per-col", "3"]), Ok(options(100, 8, 3)));
+        assert_eq!(parse(&["--squares-per-col", "3", "100", "5"]), Ok(options(100, 5, 3)));
+
+        assert!(parse(&["--cols", "50", "100"]).is_err());
+        assert!(parse(&["100", "4", "--squares-per-row", "4"]).is_err());
+    }
+
+    #[test]
+    fn check_invalid_args() {
+        let errors = [
+            (&["66", "eight", "12"][..], "not a number"),
+            (&["--cols"][..],            "missing value"),
+            (&["--cols", "wide"][..],    "not a number"),
+            (&["--frobnicate"][..],      "unknown option"),
+            (&["-x", "66"][..],          "unknown option"),
+            (&["1", "2", "3", "4"][..],  "unexpected argument"),
+            (&["--rows=2", "--rows=3"][..], "more than once"),
+            (&["abc", "def", "ghi"][..], "--cols: `abc` is not a number"),
+            (&["66", "8", "12", "extra"][..], "unexpected argument `extra`"),
+            (&["0"][..],                 "--cols: `0` must be at least 1"),
+            // Negative numbers aren't mistaken for flags, they're just too small.
+            (&["-4"][..],                "--cols: `-4` must be at least 1"),
+            (&["66", "-8"][..],          "--squares-per-row: `-8` must be at least 1"),
+            (&["--rows=0"][..],          "--rows: `0` must be at least 1"),
+            (&["--squares-per-col", "-1"][..], "must be at least 1"),
+        ];
+        for &(args, expected) in errors.iter() {
+            let message = parse(args).unwrap_err();
+            assert!(message.contains(expected), "{:?}: {}", args, message);
+        }
+    }
+
+    #[test]
+    fn check_seed_args() {
+        assert_eq!(parse(&["--seed", "1968"]).unwrap().seed, Some(1968));
+        assert_eq!(parse(&["--seed=0x7b0"]).unwrap().seed, Some(1968));
+        assert_eq!(parse(&["--seed", "0XFFFFFFFFFFFFFFFF"]).unwrap().seed, Some(u64::MAX));
+        assert_eq!(parse(&["20", "--seed", "5", "3"]).unwrap(),
+                   Options { seed: Some(5), ..options(20, 3, 12) });
+
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--seed", "0x"]).is_err());
+        assert!(parse(&["--seed", "0xg"]).is_err());
+        assert!(parse(&["--seed"]).is_err());
+    }
+
+    #[test]
+    fn check_compact_args() {
+        assert_eq!(parse(&["40x4x6"]), Ok(options(40, 4, 6)));
+        assert_eq!(parse(&["66x8x12@42"]), Ok(Options { seed: Some(42), ..options(66, 8, 12) }));
+        assert_eq!(parse(&["--seed", "7", "66x8x12"]).unwrap().seed, Some(7));
+        let spiral = parse(&["-p", "spiral", "40x8x12", "2.5"]).unwrap();
+        assert_eq!((spiral.console_cols, spiral.pattern_args), (Some(40), vec![2.5]));
+
+        let errors = [
+            (&["66x8"][..],                   "squares_per_col is missing"),
+            (&["66x0x12"][..],                "squares_per_row `0` is not a number of at least 1"),
+            (&["66x8x12@"][..],               "seed is missing"),
+            (&["--cols", "50", "66x8x12"][..], "--cols given more than once"),
+            (&["--seed", "1", "66x8x12@2"][..], "--seed given more than once"),
+            (&["66x8x12", "4"][..],           "the schotter pattern takes 0 extra argument(s)"),
+        ];
+        for &(args, expected) in errors.iter() {
+            let message = parse(args).unwrap_err();
+            assert!(message.contains(expected), "{:?}: {}", args, message);
+        }
+    }
+
+    fn run_to_strings(options: &Options) -> (String, String) {
+        let mut out = vec![];
+        let mut err = vec![];
+        run(options, None, &mut out, &mut err).unwrap();
+        (String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn check_seed_is_reproducible() {
+        let seeded = Options { seed: Some(0x5eed), ..Options::default() };
+        let (first, first_err) = run_to_strings(&seeded);
+        let (second, _) = run_to_strings(&seeded);
+        assert_eq!(first, second);
+        assert!(first.ends_with(&format!("{}\n", lolwut::ATTRIBUTION)));
+        assert_eq!(first_err, "");
+
+        let other = Options { seed: Some(0x5eee), ..Options::default() };
+        assert_ne!(first, run_to_strings(&other).0);
+    }
+
+    #[test]
+    fn check_random_seed_is_reported() {
+        let (out, err) = run_to_strings(&Options::default());
+        let seed: u64 = err.trim()
+                           .strip_prefix("seed: ")
+                           .expect("the seed should be reported")
+                           .parse()
+                           .unwrap();
+
+        // ... and the reported seed reproduces the same artwork.
+        let seeded = Options { seed: Some(seed), ..Options::default() };
+        assert_eq!(run_to_strings(&seeded).0, out);
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("lolwut-{}-{}", process::id(), name))
+    }
+
+    #[test]
+    fn check_output_to_file() {
+        assert_eq!(parse(&["-o", "art.txt"]).unwrap().output.as_deref(), Some("art.txt"));
+        assert_eq!(parse(&["--output=-"]).unwrap().output.as_deref(), Some("-"));
+
+        let path = temp_path("art.txt");
+        std::fs::write(&path, "this should be truncated away").unwrap();
+
+        let options = Options { seed: Some(42), ..Options::default() };
+        {
+            let mut out = open_output(path.to_str()).unwrap();
+            run(&options, None, &mut out, &mut io::sink()).unwrap();
+        }
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The attribution goes into the file too.
+        assert_eq!(written, run_to_strings(&options).0);
+        assert!(written.ends_with(&format!("{}\n", lolwut::ATTRIBUTION)));
+    }
+
+    #[test]
+    fn check_output_dash_is_stdout() {
+        assert!(open_output(None).is_ok());
+        assert!(open_output(Some("-")).is_ok());
+        assert!(!std::path::Path::new("-").exists());
+    }
+
+    #[test]
+    fn check_output_unwritable() {
+        let path = temp_path("no-such-dir").join("art.txt");
+        let path = path.to_str().unwrap();
+        let message = open_output(Some(path)).err().unwrap().to_string();
+        assert!(message.contains(path), "{}", message);
+    }
+
+    #[test]
+    fn check_format_parsing() {
+        assert_eq!("braille".parse(), Ok(Format::Braille));
+        assert_eq!("ascii".parse(), Ok(Format::Ascii));
+        assert_eq!("blocks".parse(), Ok(Format::Blocks));
+        assert_eq!("pbm".parse(), Ok(Format::Pbm));
+        for &format in Format::ALL.iter() {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+
+        let message = "png".parse::<Format>().unwrap_err();
+        assert!(message.contains("braille, ascii, blocks, pbm, json"), "{}", message);
+
+        assert_eq!(parse(&["--format", "ascii"]).unwrap().format, Format::Ascii);
+        assert_eq!(parse(&["-f", "pbm"]).unwrap().format, Format::Pbm);
+        assert!(parse(&["--format=gif"]).is_err());
+    }
+
+    #[test]
+    fn check_binary_formats_avoid_ttys() {
+        let pbm = Options { format: Format::Pbm, ..Options::default() };
+        assert!(check_destination(&pbm, true).is_err());
+        assert!(check_destination(&pbm, false).is_ok());
+
+        let to_file = Options { output: Some("art.pbm".into()), ..pbm.clone() };
+        assert!(check_destination(&to_file, true).is_ok());
+
+        for &format in [Format::Braille, Format::Ascii, Format::Blocks].iter() {
+            let text = Options { format, ..Options::default() };
+            assert!(check_destination(&text, true).is_ok());
+        }
+    }
+
+    #[test]
+    fn check_formats_render() {
+        let options = |format| Options { format, seed: Some(1), ..options(10, 2, 2) };
+
+        let (ascii, _) = run_to_strings(&options(Format::Ascii));
+        assert!(ascii.lines().next().unwrap().chars().all(|c| c == '#' || c == ' '));
+
+        let mut pbm = vec![];
+        run(&options(Format::Pbm), None, &mut pbm, &mut io::sink()).unwrap();
+        assert!(pbm.starts_with(b"P4\n20 "));
+    }
+
+    /// Just enough of a JSON parser to read back `to_json`.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Json {
+        Bool(bool),
+        Number(f64),
+        Str(String),
+        Array(Vec<Json>),
+        Object(Vec<(String, Json)>),
+    }
+
+    impl Json {
+        fn parse(text: &str) -> Json {
+            let mut chars = text.chars().peekable();
+            let value = Json::parse_value(&mut chars);
+            assert!(chars.all(char::is_whitespace), "trailing characters");
+            value
+        }
+
+        fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Json {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match chars.next().expect("a value") {
+                '{' => {
+                    let mut fields = vec![];
+                    loop {
+                        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                        if chars.next_if_eq(&'}').is_some() {
+                            return Json::Object(fields);
+                        }
+                        chars.next_if_eq(&',');
+                        let key = match Json::parse_value(chars) {
+                            Json::Str(key) => key,
+                            other => panic!("expected a key, found {:?}", other),
+                        };
+                        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                        assert_eq!(chars.next(), Some(':'));
+                        fields.push((key, Json::parse_value(chars)));
+                    }
+                }
+                '[' => {
+                    let mut items = vec![];
+                    loop {
+                        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                        if chars.next_if_eq(&']').is_some() {
+                            return Json::Array(items);
+                        }
+                        chars.next_if_eq(&',');
+                        items.push(Json::parse_value(chars));
+                    }
+                }
+                '"' => {
+                    let mut s = String::new();
+                    loop {
+                        match chars.next().expect("a closing quote") {
+                            '"' => return Json::Str(s),
+                            '\\' => s.push(chars.next().expect("an escaped character")),
+                            c => s.push(c),
+                        }
+                    }
+                }
+                c => {
+                    let mut word = c.to_string();
+                    while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || ".-".contains(*c)) {
+                        word.push(c);
+                    }
+                    match word.as_str() {
+                        "true"  => Json::Bool(true),
+                        "false" => Json::Bool(false),
+                        _ => Json::Number(word.parse().expect("a number")),
+                    }
+                }
+            }
+        }
+
+        fn get(&self, key: &str) -> &Json {
+            match self {
+                Json::Object(fields) => &fields.iter().find(|(k, _)| k == key).unwrap().1,
+                _ => panic!("{:?} is not an object", self),
+            }
+        }
+
+        fn number(&self) -> f64 {
+            match *self {
+                Json::Number(n) => n,
+                _ => panic!("{:?} is not a number", self),
+            }
+        }
+
+        fn items(&self) -> &[Json] {
+            match self {
+                Json::Array(items) => items,
+                _ => panic!("{:?} is not an array", self),
+            }
+        }
+    }
+
+    #[test]
+    fn check_json() {
+        assert_eq!(parse(&["--json"]).unwrap().format, Format::Json);
+        assert!(parse(&["--charset", "json"]).is_err());
+        let animated = Options { format: Format::Json, animate: Some(1.0), ..Options::default() };
+        assert!(check_destination(&animated, true).is_err());
+
+        let options = Options {
+            format: Format::Json,
+            seed: Some(u64::MAX),
+            pattern: "spiral",
+            pattern_args: vec![3.0],
+            ..options(10, 2, 3)
+        };
+        let (json, _) = run_to_strings(&options);
+        let canvas = finish(&options, draw_pattern(&options, &mut StdRng::seed_from_u64(u64::MAX))
+                                          .unwrap())
+                         .unwrap();
+        let json = Json::parse(&json);
+
+        assert_eq!(json.get("version"), &Json::Number(1.0));
+        assert_eq!(json.get("seed"), &Json::Str(u64::MAX.to_string()));
+        assert_eq!(json.get("pattern"), &Json::Str("spiral".into()));
+        let params = json.get("params");
+        assert_eq!(params.get("cols").number(), 10.0);
+        assert_eq!(params.get("squares_per_row").number(), 2.0);
+        assert_eq!(params.get("squares_per_col").number(), 3.0);
+        assert_eq!(params.get("invert"), &Json::Bool(false));
+        let pattern = find_pattern("spiral").unwrap();
+        assert_eq!(params.get("args").get(pattern.args[0].0).number(), 3.0);
+
+        // The rows decode back into the same pixels.
+        let width = json.get("width").number() as i32;
+        let height = json.get("height").number() as i32;
+        assert_eq!((width, height), (canvas.width(), canvas.height()));
+        let rows = json.get("rows").items();
+        assert_eq!(rows.len(), height as usize);
+        for (y, row) in rows.iter().enumerate() {
+            let mut x = 0;
+            for run in row.items() {
+                let (value, count) = (run.items()[0].number(), run.items()[1].number());
+                for _ in 0..count as i32 {
+                    assert_eq!(canvas.get_pixel(x, y as i32) as f64, value, "({}, {})", x, y);
+                    x += 1;
+                }
+            }
+            assert_eq!(x, width);
+        }
+        assert!(canvas.render_statistics().set_pixels > 0);
+    }
+
+    #[test]
+    fn check_json_strings() {
+        assert_eq!(json_string("spiral"), "\"spiral\"");
+        assert_eq!(json_string("a \"b\" \\ c\n"), "\"a \\\"b\\\" \\\\ c\\u000a\"");
+    }
+
+    #[test]
+    fn check_charset_args() {
+        assert_eq!(parse(&["--charset", "ascii"]).unwrap().format, Format::Ascii);
+        assert_eq!(parse(&["--charset=blocks"]).unwrap().format, Format::Blocks);
+        assert_eq!(parse(&["--charset", "braille"]).unwrap().format, Format::Braille);
+        assert!(parse(&["--charset", "pbm"]).is_err());
+        assert!(parse(&["--charset", "ebcdic"]).is_err());
+
+        let chars = parse(&["--charset=ascii", "--on-char", "@", "--off-char=."]).unwrap();
+        assert_eq!((chars.on_char, chars.off_char), (Some('@'), Some('.')));
+        assert!(parse(&["--charset=ascii", "--on-char", "##"]).is_err());
+        assert!(parse(&["--charset=ascii", "--on-char", "█"]).is_err());
+        assert!(parse(&["--charset=ascii", "--off-char", "\t"]).is_err());
+        assert!(parse(&["--on-char", "@"]).is_err());
+    }
+
+    #[test]
+    fn check_ascii_fits_the_console() {
+        for &cols in [20, 21, 66, 81].iter() {
+            for &format in [Format::Ascii, Format::Blocks].iter() {
+                let options = Options { format, seed: Some(2), ..options(cols, 8, 12) };
+                let (out, _) = run_to_strings(&options);
+                let art = out.lines().take_while(|line| *line != lolwut::ATTRIBUTION);
+                let widest = art.map(|line| line.chars().count()).max().unwrap();
+                assert!(widest <= cols as usize, "{} at {} cols is {} wide", format, cols, widest);
+                assert!(widest >= cols as usize - 1, "{} at {} cols is {} wide", format, cols, widest);
+
+                if format == Format::Ascii {
+                    assert!(out.bytes().all(|b| b < 0x80));
+                }
+            }
+        }
+
+        let options = Options {
+            format: Format::Ascii,
+            seed: Some(2),
+            on_char: Some('@'),
+            off_char: Some('.'),
+            ..options(20, 2, 2)
+        };
+        let (out, _) = run_to_strings(&options);
+        let art = &out[..out.len() - lolwut::ATTRIBUTION.len() - 1];
+        assert!(art.chars().all(|c| c == '@' || c == '.' || c == '\n'));
+        assert!(art.contains('@'));
+    }
+
+    #[test]
+    fn check_pattern_args() {
+        assert_eq!(parse(&[]).unwrap().pattern, "schotter");
+        assert_eq!(parse(&["--pattern", "noise"]).unwrap().pattern, "noise");
+
+        let noise = parse(&["40", "2", "3", "8", "--pattern=noise"]).unwrap();
+        assert_eq!((noise.pattern, noise.pattern_args), ("noise", vec![8.0]));
+        assert_eq!(noise.console_cols, Some(40));
+        let spiral = parse(&["-p", "spiral", "40", "8", "12", "2.5"]).unwrap();
+        assert_eq!(spiral.pattern_args, [2.5]);
+
+        let errors = [
+            (&["--pattern", "truchet"][..], "unknown pattern `truchet`, expected one of: schotter, noise"),
+            (&["66", "8", "12", "4"][..], "the schotter pattern takes 0 extra argument(s)"),
+            (&["-p", "spiral", "66", "8", "12", "4", "4"][..], "unexpected argument `4`"),
+            (&["-p", "spiral", "66", "8", "12", "wide"][..], "spiral SPACING: `wide`"),
+            (&["-p", "circles", "66", "8", "12", "0"][..], "is not a positive number"),
+        ];
+        for &(args, expected) in errors.iter() {
+            let message = parse(args).unwrap_err();
+            assert!(message.contains(expected), "{:?}: {}", args, message);
+        }
+
+        assert!(parse(&["--list-patterns"]).unwrap().list_patterns);
+        let list = list_patterns();
+        for pattern in PATTERNS {
+            assert!(list.contains(pattern.description));
+        }
+        assert!(list.contains("THRESHOLD [default: 0.5]"));
+    }
+
+    #[test]
+    fn check_pattern_dispatch() {
+        let canvas = |args: &[&str]| {
+            let options = parse(args).unwrap();
+            draw_pattern(&options, &mut StdRng::seed_from_u64(5)).unwrap()
+        };
+
+        // Schotter is the same as it always was.
+        let schotter = canvas(&["20", "2", "3"]);
+        let expected = Canvas::create_and_render_schotter_with(&SchotterConfig::new(20, 2, 3),
+                                                                &mut StdRng::seed_from_u64(5))
+                              .unwrap();
+        assert_eq!(schotter.render(), expected.render());
+
+        // Every pattern draws something on the same size canvas, and their
+        // arguments make a difference.
+        for pattern in PATTERNS {
+            let drawn = canvas(&["20", "2", "3", "-p", pattern.name]);
+            assert_eq!((drawn.width(), drawn.height()), (schotter.width(), schotter.height()));
+            assert!(drawn.render().chars().any(|c| c != '⠀' && c != '\n'), "{}", pattern.name);
+
+            if !pattern.args.is_empty() {
+                let other = canvas(&["20", "2", "3", "-p", pattern.name, "9"]);
+                assert_ne!(drawn.render(), other.render(), "{}", pattern.name);
+            }
+        }
+    }
+
+    #[test]
+    fn check_version() {
+        assert!(parse(&["--version"]).unwrap().version);
+        assert!(parse(&["-V"]).unwrap().version);
+
+        let version = version();
+        assert!(version.starts_with(&format!("lolwut {} (features: ", env!("CARGO_PKG_VERSION"))),
+                "{}", version);
+        assert!(!version.contains('\n'));
+        if cfg!(feature = "image") {
+            assert!(version.contains("image"), "{}", version);
+        }
+        if cfg!(feature = "rayon") {
+            assert!(version.contains("rayon"), "{}", version);
+        }
+        if !cfg!(feature = "image") && !cfg!(feature = "rayon") {
+            assert!(version.ends_with("(features: none)"), "{}", version);
+        }
+    }
+
+    fn resolve(args: &[&str], vars: &[(&str, &str)]) -> (Options, Vec<String>) {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let env = |name: &str| vars.iter()
+                                   .find(|&&(var, _)| var == name)
+                                   .map(|&(_, value)| value.to_string());
+        resolve_options(&args, &env).unwrap()
+    }
+
+    #[test]
+    fn check_env_options() {
+        let vars = [
+            ("SCHOTTER_COLS",   "40"),
+            ("SCHOTTER_ROWS_X", "4"),
+            ("SCHOTTER_ROWS_Y", "6"),
+            ("SCHOTTER_SEED",   "0x10"),
+            ("SCHOTTER_FORMAT", "ascii"),
+        ];
+
+        // Nothing set: the defaults.
+        assert_eq!(resolve(&[], &[]), (Options::default(), vec![]));
+
+        // The environment beats the defaults...
+        let (env, warnings) = resolve(&[], &vars);
+        assert!(warnings.is_empty());
+        assert_eq!(env, Options { seed: Some(16), format: Format::Ascii, ..options(40, 4, 6) });
+
+        // ... and arguments beat the environment, whether flags or positional.
+        let (args, _) = resolve(&["50", "--rows", "2", "--seed=3", "-f", "blocks"], &vars);
+        assert_eq!(args, Options { seed: Some(3), format: Format::Blocks, ..options(50, 4, 2) });
+        let (args, _) = resolve(&["50", "5", "7"], &vars);
+        assert_eq!(args, Options { seed: Some(16), format: Format::Ascii, ..options(50, 5, 7) });
+
+        // Empty variables are the same as unset ones.
+        assert_eq!(resolve(&[], &[("SCHOTTER_COLS", "")]), (Options::default(), vec![]));
+
+        // Arguments are still checked strictly.
+        let args = vec!["0".to_string()];
+        assert!(resolve_options(&args, &|_: &str| None).is_err());
+    }
+
+    #[test]
+    fn check_bad_env_options_warn() {
+        let vars = [
+            ("SCHOTTER_COLS",   "wide"),
+            ("SCHOTTER_ROWS_X", "0"),
+            ("SCHOTTER_ROWS_Y", "3"),
+            ("SCHOTTER_SEED",   "0xzz"),
+            ("SCHOTTER_FORMAT", "gif"),
+        ];
+        let (options, warnings) = resolve(&[], &vars);
+
+        // Only the good one is used, and the rest fall back to the defaults.
+        assert_eq!(options, Options { squares_per_col: 3, ..Options::default() });
+        assert_eq!(warnings.len(), 4);
+        for (warning, name) in warnings.iter().zip(["SCHOTTER_COLS", "SCHOTTER_ROWS_X",
+                                                    "SCHOTTER_SEED", "SCHOTTER_FORMAT"].iter()) {
+            assert!(warning.contains(name), "{}", warning);
+            assert!(warning.ends_with(", ignoring it"), "{}", warning);
+        }
+    }
+
+    #[test]
+    fn check_bench() {
+        assert_eq!(parse(&["--bench", "3"]).unwrap().bench, Some(3));
+        assert!(parse(&["--bench=0"]).is_err());
+        assert!(parse(&["--bench", "3", "--animate"]).is_err());
+
+        // Benchmarking doesn't write the art, so binary formats are fine.
+        let options = Options { bench: Some(3), format: Format::Pbm, ..options(10, 2, 2) };
+        assert!(check_destination(&options, true).is_ok());
+
+        let mut err = vec![];
+        bench(&options, None, 3, &mut err).unwrap();
+        let err = String::from_utf8(err).unwrap();
+        let lines: Vec<&str> = err.lines().collect();
+        assert!(lines[0].starts_with("3 iterations of schotter at 10x2x2"), "{}", err);
+        let stages: Vec<&str> = lines[2..].iter()
+                                          .map(|line| line.split_whitespace().next().unwrap())
+                                          .collect();
+        assert_eq!(stages, ["allocate", "draw", "render", "total"]);
+    }
+
+    #[test]
+    fn check_scale() {
+        assert_eq!(parse(&[]).unwrap().scale, 1);
+        assert_eq!(parse(&["--scale", "3"]).unwrap().scale, 3);
+        assert!(parse(&["--scale=0"]).is_err());
+        assert!(parse(&["--scale", "big"]).is_err());
+
+        // 66x8x12 is 196 pixels tall, so every braille cell is complete at
+        // any scale.
+        let art = |scale| {
+            let options = Options { scale, seed: Some(4), caption: None, ..options(66, 8, 12) };
+            let (out, _) = run_to_strings(&options);
+            let widths: Vec<usize> = out.lines().map(|line| line.chars().count()).collect();
+            (widths.len(), widths[0])
+        };
+        let (lines, width) = art(1);
+        assert_eq!((lines, width), (49, 66));
+        for &scale in [2, 3].iter() {
+            assert_eq!(art(scale), (lines * scale as usize, width * scale as usize));
+        }
+
+        // Fitting the terminal leaves room for the scaling.
+        assert_eq!(resolve_cols(None, true, 2, || Some(242)), 120);
+        assert_eq!(resolve_cols(Some(50), true, 2, || Some(242)), 50);
+    }
+
+    #[test]
+    fn check_resolve_cols() {
+        let never = || -> Option<u16> { panic!("detection should be skipped") };
+
+        // Explicit arguments always win.
+        assert_eq!(resolve_cols(Some(100), true, 1, never), 100);
+        assert_eq!(resolve_cols(Some(100), false, 1, never), 100);
+
+        // Don't bother detecting when we're not writing to a terminal.
+        assert_eq!(resolve_cols(None, false, 1, never), DEFAULT_COLS);
+
+        assert_eq!(resolve_cols(None, true, 1, || Some(240)), 238);
+        assert_eq!(resolve_cols(None, true, 1, || Some(60)), 58);
+        assert_eq!(resolve_cols(None, true, 1, || None), DEFAULT_COLS);
+
+        // Tiny terminals are clamped.
+        assert_eq!(resolve_cols(None, true, 1, || Some(22)), 20);
+        assert_eq!(resolve_cols(None, true, 1, || Some(10)), MIN_DETECTED_COLS);
+        assert_eq!(resolve_cols(None, true, 1, || Some(1)), MIN_DETECTED_COLS);
+    }
+
+    #[test]
+    fn check_invert() {
+        assert!(!parse(&[]).unwrap().invert);
+        assert!(parse(&["--invert", "40"]).unwrap().invert);
+
+        // 66x8x12 is 196 pixels tall, so every braille cell is complete.
+        let normal = Options { seed: Some(17), ..options(66, 8, 12) };
+        let inverted = Options { invert: true, ..normal.clone() };
+        let (normal, _) = run_to_strings(&normal);
+        let (inverted, _) = run_to_strings(&inverted);
+
+        let mut normal_lines = normal.lines();
+        let mut inverted_lines = inverted.lines();
+        assert_eq!(normal_lines.next_back(), inverted_lines.next_back());
+        assert_eq!(normal_lines.clone().count(), 49);
+        for (n, i) in normal_lines.zip(inverted_lines) {
+            for (n, i) in n.chars().zip(i.chars()) {
+                assert_eq!(i as u32, 0x28FF - (n as u32 - 0x2800));
+            }
+        }
+    }
+
+    #[test]
+    fn check_color_choice() {
+        assert_eq!("auto".parse(), Ok(ColorChoice::Auto));
+        assert_eq!("never".parse(), Ok(ColorChoice::Never));
+        assert_eq!("red".parse(), Ok(ColorChoice::Always(Color::Named(1))));
+        assert_eq!("#102030".parse(), Ok(ColorChoice::Always(Color::Rgb(0x10, 0x20, 0x30))));
+
+        let message = "sometimes".parse::<ColorChoice>().unwrap_err();
+        assert!(message.contains("#ff8800"), "{}", message);
+        assert!(message.contains("auto"), "{}", message);
+
+        assert_eq!(parse(&["--color", "green"]).unwrap().color,
+                   ColorChoice::Always(Color::Named(2)));
+        assert!(parse(&["--color=chartreuse"]).is_err());
+    }
+
+    #[test]
+    fn check_resolve_color() {
+        let red = Color::Named(1);
+
+        // auto needs a terminal, and respects NO_COLOR.
+        assert_eq!(resolve_color(ColorChoice::Auto, true, false), Some(AUTO_COLOR));
+        assert_eq!(resolve_color(ColorChoice::Auto, true, true), None);
+        assert_eq!(resolve_color(ColorChoice::Auto, false, false), None);
+
+        // never means never, and explicit colors are used on any terminal,
+        // but nowhere else.
+        for &no_color in [true, false].iter() {
+            for &tty in [true, false].iter() {
+                assert_eq!(resolve_color(ColorChoice::Never, tty, no_color), None);
+            }
+            assert_eq!(resolve_color(ColorChoice::Always(red), true, no_color), Some(red));
+            assert_eq!(resolve_color(ColorChoice::Always(red), false, no_color), None);
+        }
+    }
+
+    #[test]
+    fn check_output_mode() {
+        let red = Color::Named(1);
+        let both = Terminals { stdin: true, stdout: true };
+        let piped = Terminals { stdin: true, stdout: false };
+        let no_stdin = Terminals { stdin: false, stdout: true };
+
+        // Only a terminal gets color, and only it is measured for columns.
+        let colored = Options { color: ColorChoice::Always(red), ..Options::default() };
+        assert_eq!(output_mode(&colored, both, false),
+                   Ok(OutputMode { to_tty: true, color: Some(red) }));
+        assert_eq!(output_mode(&colored, no_stdin, false),
+                   Ok(OutputMode { to_tty: true, color: Some(red) }));
+        assert_eq!(output_mode(&colored, piped, false),
+                   Ok(OutputMode { to_tty: false, color: None }));
+        let to_file = Options { output: Some("art.txt".into()), ..colored.clone() };
+        assert_eq!(output_mode(&to_file, both, false),
+                   Ok(OutputMode { to_tty: false, color: None }));
+        let to_dash = Options { output: Some("-".into()), ..colored.clone() };
+        assert_eq!(output_mode(&to_dash, both, false),
+                   Ok(OutputMode { to_tty: true, color: Some(red) }));
+        let auto = Options { color: ColorChoice::Auto, ..Options::default() };
+        assert_eq!(output_mode(&auto, both, true), Ok(OutputMode { to_tty: true, color: None }));
+
+        // Cursor movement needs a terminal, and --interactive needs one to
+        // read keys from too.
+        let animated = Options { animate: Some(1.0), ..Options::default() };
+        assert!(output_mode(&animated, both, false).is_ok());
+        assert!(output_mode(&animated, no_stdin, false).is_ok());
+        assert!(output_mode(&animated, piped, false).unwrap_err().contains("terminal"));
+        let animated_to_file = Options { output: Some("art.txt".into()), ..animated };
+        assert!(output_mode(&animated_to_file, both, false).is_err());
+
+        let interactive = Options { interactive: true, ..Options::default() };
+        assert!(output_mode(&interactive, both, false).is_ok());
+        assert!(output_mode(&interactive, piped, false).is_err());
+        assert!(output_mode(&interactive, no_stdin, false).is_err());
+
+        // Binary data is still kept off of terminals.
+        let pbm = Options { format: Format::Pbm, ..Options::default() };
+        assert!(output_mode(&pbm, both, false).is_err());
+        assert_eq!(output_mode(&pbm, piped, false), Ok(OutputMode { to_tty: false, color: None }));
+    }
+
+    #[test]
+    fn check_colored_output() {
+        let red = Color::Named(1);
+        for &format in [Format::Braille, Format::Ascii, Format::Blocks].iter() {
+            let options = Options { format, seed: Some(3), ..options(10, 2, 2) };
+            let (plain, _) = run_to_strings(&options);
+
+            let mut colored = vec![];
+            run(&options, Some(red), &mut colored, &mut io::sink()).unwrap();
+            let colored = String::from_utf8(colored).unwrap();
+
+            let (art, attribution) = plain.split_at(plain.len() - lolwut::ATTRIBUTION.len() - 1);
+            assert_eq!(colored, red.paint(art) + attribution);
+        }
+    }
+
+    #[test]
+    fn check_animate_args() {
+        assert_eq!(parse(&[]).unwrap().animate, None);
+        assert_eq!(parse(&["--animate"]).unwrap().animate, Some(DEFAULT_FPS));
+        assert_eq!(parse(&["--animate", "10"]).unwrap().animate, Some(10.0));
+        assert_eq!(parse(&["--animate=0.5"]).unwrap().animate, Some(0.5));
+
+        // A non-number after --animate is left alone.
+        let parsed = parse(&["--animate", "--seed", "1"]).unwrap();
+        assert_eq!((parsed.animate, parsed.seed), (Some(DEFAULT_FPS), Some(1)));
+
+        assert!(parse(&["--animate=0"]).is_err());
+        assert!(parse(&["--animate=-2"]).is_err());
+        assert!(parse(&["--animate=inf"]).is_err());
+        assert!(parse(&["--animate=fast"]).is_err());
+
+        let pbm = Options { animate: Some(1.0), output: Some("art.pbm".into()), ..options(10, 2, 2) };
+        assert!(check_destination(&Options { format: Format::Pbm, ..pbm }, false).is_err());
+    }
+
+    #[test]
+    fn check_interactive_args() {
+        assert!(!parse(&[]).unwrap().interactive);
+        assert!(parse(&["--interactive"]).unwrap().interactive);
+        assert!(parse(&["-i", "20"]).unwrap().interactive);
+
+        assert!(parse(&["-i", "--animate"]).is_err());
+        assert!(parse(&["-i", "--bench", "2"]).is_err());
+        assert!(parse(&["-i", "--output", "art.txt"]).is_err());
+        let pbm = Options { interactive: true, format: Format::Pbm, ..options(10, 2, 2) };
+        assert!(check_destination(&pbm, false).is_err());
+    }
+
+    #[test]
+    fn check_key_actions() {
+        assert_eq!(key_action(b' '), Some(Action::Regenerate));
+        assert_eq!(key_action(b'\n'), Some(Action::Regenerate));
+        assert_eq!(key_action(b'\r'), Some(Action::Regenerate));
+        assert_eq!(key_action(b's'), Some(Action::ShowSeed));
+        assert_eq!(key_action(b'+'), Some(Action::MoreSquares));
+        assert_eq!(key_action(b'-'), Some(Action::FewerSquares));
+        assert_eq!(key_action(b'q'), Some(Action::Quit));
+        assert_eq!(key_action(b'x'), None);
+        assert_eq!(key_action(0x1b), None);
+
+        let mut seeds = 100..;
+        let mut new_seed = || seeds.next().unwrap();
+        let mut session = Session { seed: 7, squares_per_row: 2, show_seed: false };
+
+        assert!(session.apply(Action::ShowSeed, &mut new_seed));
+        assert_eq!(session, Session { seed: 7, squares_per_row: 2, show_seed: true });
+        assert!(session.status().contains("seed: 7"));
+
+        // Changing the squares keeps the seed, and new art hides the old one.
+        assert!(session.apply(Action::MoreSquares, &mut new_seed));
+        assert_eq!(session, Session { seed: 7, squares_per_row: 3, show_seed: true });
+        assert!(session.apply(Action::Regenerate, &mut new_seed));
+        assert_eq!(session, Session { seed: 100, squares_per_row: 3, show_seed: false });
+
+        // There's always at least one square per row.
+        for _ in 0..5 {
+            assert!(session.apply(Action::FewerSquares, &mut new_seed));
+        }
+        assert_eq!(session.squares_per_row, 1);
+
+        assert!(!session.apply(Action::Quit, &mut new_seed));
+    }
+
+    fn interact_frames(options: &Options, keys: &[u8]) -> Vec<String> {
+        let mut out = vec![];
+        let mut seeds = 100..;
+        interact(options, None, &mut keys.iter().cloned(), &mut out, &mut || seeds.next().unwrap())
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with(HIDE_CURSOR) && out.ends_with(SHOW_CURSOR), "{:?}", out);
+
+        // Every frame starts by clearing whatever's left of the last one.
+        let body = &out[HIDE_CURSOR.len()..out.len() - SHOW_CURSOR.len()];
+        body.split(CLEAR_BELOW).skip(1).map(|frame| {
+            // Cut off the cursor movement that leads into the next frame.
+            frame.rsplit_once('\n').map_or(frame, |(frame, _)| frame).to_string()
+        }).collect()
+    }
+
+    #[test]
+    fn check_interactive() {
+        let seeded = Options { seed: Some(9), ..options(20, 3, 5) };
+        let (art, _) = run_to_strings(&seeded);
+
+        // Unknown keys are ignored, and q quits.
+        let frames = interact_frames(&seeded, b"xs q +");
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].starts_with(art.trim_end()), "{}", frames[0]);
+        assert!(frames[0].ends_with("q: quit"), "{}", frames[0]);
+        assert!(frames[1].starts_with(art.trim_end()));
+        assert!(frames[1].ends_with("seed: 9"), "{}", frames[1]);
+
+        // Space draws new artwork from a new seed, reported by s.
+        let (new_art, _) = run_to_strings(&Options { seed: Some(100), ..seeded.clone() });
+        assert!(frames[2].starts_with(new_art.trim_end()));
+        assert_ne!(art, new_art);
+
+        // + redraws the same seed with more squares, and running out of keys
+        // ends the session too.
+        let frames = interact_frames(&seeded, b"+");
+        let (wider, _) = run_to_strings(&Options { seed: Some(9), ..options(20, 4, 5) });
+        assert_eq!(frames.len(), 2);
+        assert!(frames[1].starts_with(wider.trim_end()));
+
+        // Without a seed, the first one comes from the same place as the rest.
+        let frames = interact_frames(&options(20, 3, 5), b"s");
+        assert!(frames[1].ends_with("seed: 100"), "{}", frames[1]);
+    }
+
+    #[test]
+    fn check_frame_line_count() {
+        assert_eq!(cursor_up(0), "");
+        assert_eq!(cursor_up(7), "\x1b[7A");
+
+        for &format in [Format::Braille, Format::Ascii, Format::Blocks].iter() {
+            for &color in [None, Some(Color::Rgb(1, 2, 3))].iter() {
+                let options = Options { format, ..options(20, 3, 5) };
+                let canvas = Canvas::create_and_render_schotter(20, 3, 5).unwrap();
+                let frame = encode(&options, &canvas, color, 0);
+                let text = String::from_utf8(frame.clone()).unwrap();
+
+                // Every line of art, and the attribution, ends in a newline.
+                assert!(text.ends_with('\n'));
+                assert_eq!(line_count(&frame), text.lines().count());
+                assert!(text.lines().last().unwrap().ends_with(lolwut::ATTRIBUTION));
+            }
+        }
+    }
+
+    #[test]
+    fn check_caption() {
+        assert_eq!(parse(&["--no-caption"]).unwrap().caption, None);
+        assert_eq!(parse(&["--caption", "hi"]).unwrap().caption.as_deref(), Some("hi"));
+        assert_eq!(parse(&["--no-caption", "--caption=back"]).unwrap().caption.as_deref(),
+                   Some("back"));
+
+        let seeded = Options { seed: Some(1968), ..options(20, 3, 5) };
+        let (art, _) = run_to_strings(&Options { caption: None, ..seeded.clone() });
+        let (captioned, _) = run_to_strings(&seeded);
+        assert_eq!(captioned, format!("{}{}\n", art, lolwut::ATTRIBUTION));
+        assert!(!art.contains(lolwut::ATTRIBUTION));
+
+        let custom = Options { caption: Some("seed {seed}, again {seed}".into()), ..seeded.clone() };
+        let (custom, _) = run_to_strings(&custom);
+        assert_eq!(custom, format!("{}seed 1968, again 1968\n", art));
+
+        // A random seed shows up in the caption just as it's reported.
+        let random = Options { seed: None, caption: Some("{seed}".into()), ..seeded };
+        let (out, err) = run_to_strings(&random);
+        assert_eq!(out.lines().last().unwrap(), err.trim().strip_prefix("seed: ").unwrap());
+    }
+
+    #[test]
+    fn check_animation_without_caption() {
+        let options = Options { seed: Some(9), caption: None, ..options(20, 3, 5) };
+        let lines = Canvas::create_and_render_schotter(20, 3, 5).unwrap().render().lines().count();
+        let out = animate_frames(&options, 2);
+        assert!(out.contains(&cursor_up(lines)));
+        assert!(!out.contains(&cursor_up(lines + 1)));
+    }
+
+    fn animate_frames(options: &Options, frames: usize) -> String {
+        let mut out = vec![];
+        let mut drawn = 0;
+        // No time between frames, so `keep_going` is only asked once per frame.
+        animate(options, None, f64::MAX, &mut out, &mut || {
+            drawn += 1;
+            drawn <= frames
+        }).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn check_animation() {
+        let options = Options { seed: Some(9), ..options(20, 3, 5) };
+        let out = animate_frames(&options, 3);
+        assert!(out.starts_with(HIDE_CURSOR));
+        assert!(out.ends_with(SHOW_CURSOR));
+
+        // Each frame after the first moves back up over the one before it.
+        let body = &out[HIDE_CURSOR.len()..out.len() - SHOW_CURSOR.len()];
+        let up = cursor_up(Canvas::create_and_render_schotter(20, 3, 5).unwrap()
+                                 .render()
+                                 .lines()
+                                 .count() + 1);
+        let frames: Vec<&str> = body.split(up.as_str()).collect();
+        assert_eq!(frames.len(), 3);
+
+        // With a seed, the frames build up the same artwork row by row.
+        let (whole, _) = run_to_strings(&options);
+        assert_ne!(frames[0], frames[1]);
+        assert!(frames.iter().all(|frame| frame.len() == whole.len()));
+        assert_eq!(animate_frames(&options, 5).rsplit(up.as_str()).next().unwrap(),
+                   whole + SHOW_CURSOR);
+    }
+
+    #[test]
+    fn check_usage_lists_defaults() {
+        let text = usage("lolwut");
+        for option in ["--cols", "--squares-per-row", "--squares-per-col", "--rows", "--help"].iter() {
+            assert!(text.contains(option), "{}", option);
+        }
+        assert!(text.contains("or 66]"));
+        assert!(text.contains("[default: 8]"));
+        assert!(text.contains("[default: 12]"));
+    }
+
+    #[cfg(feature = "image")]
+    fn convert_args(args: &[&str]) -> Result<ConvertOptions, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_convert_args(&args)
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_convert_args() {
+        let options = convert_args(&["cat.png"]).unwrap();
+        assert_eq!(options, ConvertOptions {
+            path: "cat.png".into(),
+            dither: false,
+            threshold: 128,
+            width: None,
+            help: false,
+        });
+
+        let options = convert_args(&["--dither", "-w", "40", "cat.png", "--threshold=200"]).unwrap();
+        assert!(options.dither);
+        assert_eq!((options.width, options.threshold), (Some(40), 200));
+        assert!(convert_args(&["--help"]).unwrap().help);
+
+        let errors = [
+            (&[][..],                             "missing the IMAGE"),
+            (&["a.png", "b.png"][..],             "unexpected argument `b.png`"),
+            (&["--width", "0", "a.png"][..],      "must be at least 1"),
+            (&["--threshold", "256", "a.png"][..], "from 0 to 255"),
+            (&["--frobnicate", "a.png"][..],      "unknown option"),
+        ];
+        for &(args, expected) in errors.iter() {
+            let message = convert_args(args).unwrap_err();
+            assert!(message.contains(expected), "{:?}: {}", args, message);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_convert() {
+        // A gradient from black on the left to white on the right, twice as
+        // wide as it is tall.
+        let path = temp_path("gradient.png");
+        image::GrayImage::from_fn(200, 100, |x, _| image::Luma([(x * 255 / 199) as u8]))
+            .save(&path)
+            .unwrap();
+
+        for &dither in [false, true].iter() {
+            let options = ConvertOptions {
+                path: path.to_str().unwrap().into(),
+                dither,
+                threshold: 128,
+                width: None,
+                help: false,
+            };
+            let mut out = vec![];
+            convert(&options, 40, &mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+
+            // 80x40 pixels fill 40 columns by 10 rows.
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines.len(), 10, "{}", text);
+            assert!(lines.iter().all(|line| line.chars().count() == 40), "{}", text);
+
+            // Columns get no denser going from black to white.
+            let dots = |col: usize| -> u32 {
+                lines.iter()
+                     .map(|line| (line.chars().nth(col).unwrap() as u32 - 0x2800).count_ones())
+                     .sum()
+            };
+            let densities: Vec<u32> = (0..40).step_by(5)
+                                             .map(|col| (col..col + 5).map(dots).sum())
+                                             .collect();
+            assert!(densities.windows(2).all(|w| w[0] >= w[1]), "{:?}", densities);
+            assert!(densities[0] > densities[7], "{:?}", densities);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_convert_errors() {
+        let mut out = vec![];
+        let missing = ConvertOptions {
+            path: "no-such-image.png".into(),
+            dither: false,
+            threshold: 128,
+            width: None,
+            help: false,
+        };
+        let message = convert(&missing, 40, &mut out).unwrap_err().to_string();
+        assert!(message.contains("could not read `no-such-image.png`"), "{}", message);
+
+        let path = temp_path("not-an-image.txt");
+        std::fs::write(&path, "hello").unwrap();
+        let unsupported = ConvertOptions { path: path.to_str().unwrap().into(), ..missing };
+        let message = convert(&unsupported, 40, &mut out).unwrap_err().to_string();
+        assert!(message.contains(path.to_str().unwrap()), "{}", message);
+        std::fs::remove_file(&path).unwrap();
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+//! The floating point functions that `std` adds to `f32` and `f64`, for
+//! `no_std` builds.
+//!
+//! With `std`, the inherent methods are used and this module isn't compiled.
+//! Without it, importing `Float` lets the same method calls go to `libm`,
+//! which the `libm` feature brings in. Tests always link `std`, so they get
+//! the inherent methods too, and can check `libm`'s answers against them.
+
+pub(crate) trait Float: Sized {
+    fn ceil(self) -> Self;
+    fn floor(self) -> Self;
+    fn round(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn powf(self, n: Self) -> Self;
+
+    fn sin_cos(self) -> (Self, Self);
+}
+
+macro_rules! impl_float {
+    ($t:ty, $ceil:ident, $floor:ident, $round:ident, $sqrt:ident,
+     $ln:ident, $sin:ident, $cos:ident, $hypot:ident, $pow:ident) => {
+        impl Float for $t {
+            fn ceil(self) -> Self { libm::$ceil(self) }
+            fn floor(self) -> Self { libm::$floor(self) }
+            fn round(self) -> Self { libm::$round(self) }
+            fn sqrt(self) -> Self { libm::$sqrt(self) }
+            fn ln(self) -> Self { libm::$ln(self) }
+            fn sin(self) -> Self { libm::$sin(self) }
+            fn cos(self) -> Self { libm::$cos(self) }
+            fn hypot(self, other: Self) -> Self { libm::$hypot(self, other) }
+            fn powf(self, n: Self) -> Self { libm::$pow(self, n) }
+
+            fn sin_cos(self) -> (Self, Self) {
+                (Float::sin(self), Float::cos(self))
+            }
+        }
+    };
+}
+
+impl_float!(f32, ceilf, floorf, roundf, sqrtf, logf, sinf, cosf, hypotf, powf);
+impl_float!(f64, ceil, floor, round, sqrt, log, sin, cos, hypot, pow);
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_float_matches_std() {
+        for i in -100..=100 {
+            let x = i as f32 * 0.37;
+            let close = |a: f32, b: f32| (a - b).abs() <= 1e-6 * b.abs().max(1.0);
+            assert!(close(Float::sin(x), x.sin()), "sin {}", x);
+            assert!(close(Float::cos(x), x.cos()), "cos {}", x);
+            assert!(close(Float::sqrt(x.abs()), x.abs().sqrt()), "sqrt {}", x);
+            assert!(close(Float::ln(x.abs() + 1.0), (x.abs() + 1.0).ln()), "ln {}", x);
+            assert!(close(Float::hypot(x, 2.0), x.hypot(2.0)), "hypot {}", x);
+            assert!(close(Float::powf(x.abs(), 1.5), x.abs().powf(1.5)), "powf {}", x);
+            assert_eq!(Float::ceil(x), x.ceil());
+            assert_eq!(Float::floor(x), x.floor());
+            assert_eq!(Float::round(x), x.round());
+
+            let x = x as f64;
+            let close = |a: f64, b: f64| (a - b).abs() <= 1e-12 * b.abs().max(1.0);
+            assert!(close(Float::sin(x), x.sin()), "sin {}", x);
+            assert!(close(Float::cos(x), x.cos()), "cos {}", x);
+            assert!(close(Float::sqrt(x.abs()), x.abs().sqrt()), "sqrt {}", x);
+        }
+        assert!(Float::sqrt(-1.0f32).is_nan());
+        assert_eq!(Float::sin_cos(0.5f32), (Float::sin(0.5f32), Float::cos(0.5f32)));
+    }
+}
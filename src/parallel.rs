@@ -0,0 +1,153 @@
+//! Spreading the work on big canvases over rayon's thread pool.
+//!
+//! These give exactly the same results as their serial versions, which are
+//! still faster for small canvases, see `worth_it`.
+
+use rayon::prelude::*;
+
+use crate::{bits, Canvas};
+
+/// The fewest pixels a canvas needs for splitting its work up to pay off.
+const PARALLEL_MIN_PIXELS: usize = 1 << 16;
+
+/// Whether to work on a canvas of `pixels` pixels in parallel. There's no
+/// point with only one thread to do it on.
+pub(crate) fn worth_it(pixels: usize) -> bool {
+    pixels >= PARALLEL_MIN_PIXELS && rayon::current_num_threads() > 1
+}
+
+/// The fewest pixel rows a band of `draw_quads_parallel` gets.
+const MIN_BAND_ROWS: usize = 16;
+
+impl Canvas {
+    /// `Canvas::render`, with the lines of braille rendered in parallel.
+    ///
+    /// Each line only reads from the canvas, so they're independent.
+    pub(crate) fn render_parallel(&self) -> String {
+        let lines: Vec<String> = (0..(self.height + 3) / 4).into_par_iter().map(|line| {
+            let mut out = String::with_capacity(self.width as usize * 3 / 2 + 1);
+            self.render_line(line * 4, &mut out);
+            out
+        }).collect();
+        lines.concat()
+    }
+
+    /// `Canvas::draw_quads`, with the canvas split into bands of rows that
+    /// are drawn in parallel.
+    ///
+    /// Each band is drawn as a canvas of its own, so no two threads ever
+    /// touch the same pixels. The quads that cross a band are moved up into
+    /// it by whole pixels, and `draw_line` draws the same pixels wherever a
+    /// line is, so every band ends up exactly like that part of the canvas
+    /// drawn all at once.
+    pub(crate) fn draw_quads_parallel(&mut self, quads: &[[(i32, i32); 4]], color: u8) {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Quads reaching up near i32::MIN can't be moved into a band without
+        // overflowing, so those few are drawn on the whole canvas afterwards.
+        let (movable, stuck): (Vec<&[(i32, i32); 4]>, Vec<_>) =
+            quads.iter().partition(|quad| quad.iter().all(|&(_, y)| y >= i32::MIN + height));
+
+        // Each band has to start on a whole word of pixels to be split off, so
+        // its rows are rounded up to a multiple of the rows that takes.
+        let whole_words = bits::WORD_BITS >> width.trailing_zeros().min(6);
+        let band_rows = (height as usize).div_ceil(4 * rayon::current_num_threads())
+                                         .max(MIN_BAND_ROWS)
+                                         .next_multiple_of(whole_words);
+        let band_words = band_rows * width as usize / bits::WORD_BITS;
+        self.pixels.par_chunks_mut(band_words).enumerate().for_each(|(i, rows)| {
+            let top = (i * band_rows) as i32;
+            let mut band = Canvas {
+                pixels: rows.to_vec(),
+                width,
+                height: (band_rows as i32).min(height - top),
+            };
+            for quad in movable.iter() {
+                let (low, high) = quad.iter().fold((i32::MAX, i32::MIN), |(low, high), &(_, y)| {
+                    (low.min(y), high.max(y))
+                });
+                if high < top || low >= top + band.height {
+                    continue;
+                }
+                band.draw_quad(&quad.map(|(x, y)| (x, y - top)), color);
+            }
+            rows.copy_from_slice(&band.pixels);
+        });
+
+        for quad in stuck {
+            self.draw_quad(quad, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use crate::SchotterConfig;
+
+    use rand::prelude::*;
+
+    #[test]
+    fn check_parallel_render_matches() {
+        let mut rng = StdRng::seed_from_u64(55);
+        for &(width, height) in [(0, 0), (1, 1), (3, 9), (255, 257), (512, 512), (301, 399)].iter() {
+            let canvas = Canvas::from_fn(width, height, |_, _| rng.gen_range(0, 2)).unwrap();
+            let mut serial = String::new();
+            for y in (0..canvas.height).step_by(4) {
+                canvas.render_line(y, &mut serial);
+            }
+            assert_eq!(canvas.render_parallel(), serial, "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn check_parallel_quads_match() {
+        let mut rng = StdRng::seed_from_u64(55);
+        let mut quads = vec![];
+        for _ in 0..500 {
+            let mut quad = [(0, 0); 4];
+            for corner in quad.iter_mut() {
+                *corner = (rng.gen_range(-50, 350), rng.gen_range(-50, 350));
+            }
+            quads.push(quad);
+        }
+        // Lines from way out of range, both movable and stuck.
+        quads.push([(0, i32::MIN), (299, i32::MAX), (i32::MIN, 5), (7, 7)]);
+        quads.push([(i32::MAX, -1), (3, 150), (-9, 300), (100, i32::MIN + 5)]);
+
+        let mut serial = Canvas::create(300, 300).unwrap();
+        for quad in quads.iter() {
+            serial.draw_quad(quad, 1);
+        }
+        let mut parallel = Canvas::create(300, 300).unwrap();
+        parallel.draw_quads_parallel(&quads, 1);
+        assert!(parallel.pixels == serial.pixels);
+    }
+
+    #[test]
+    fn check_parallel_schotter_is_deterministic() {
+        // Big enough to be drawn and rendered in parallel.
+        let config = SchotterConfig::new(400, 20, 20);
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let draw = || pool.install(|| {
+            assert!(rayon::current_num_threads() > 1);
+            Canvas::create_and_render_schotter_with(&config, &mut StdRng::seed_from_u64(7))
+                   .unwrap()
+        });
+        let canvas = draw();
+        assert!(canvas.pixel_count() >= PARALLEL_MIN_PIXELS);
+        assert!(canvas.pixels == draw().pixels);
+
+        // The same squares, drawn one by one.
+        let mut serial = Canvas::create(canvas.width as u32, canvas.height as u32).unwrap();
+        let quads = serial.schotter_squares(&config, 0..20, &mut StdRng::seed_from_u64(7), false, None)
+                          .unwrap();
+        for quad in quads.iter() {
+            serial.draw_quad(quad, 1);
+        }
+        assert!(canvas.pixels == serial.pixels);
+    }
+}
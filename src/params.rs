@@ -0,0 +1,266 @@
+//! A compact text form of Schotter's sizes and seed, like `66x8x12@42`.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use rand::prelude::*;
+
+#[cfg(feature = "std")]
+use crate::{Canvas, CanvasError};
+use crate::SchotterConfig;
+
+/// The sizes of a Schotter drawing and, optionally, the seed to draw it
+/// from.
+///
+/// As text, this is `COLSxPER_ROWxPER_COL[@SEED]`: the console columns, the
+/// squares per row and the squares per column, like `66x8x12`, with `@SEED`
+/// on the end when there's a seed, like `66x8x12@42`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SchotterParams {
+    pub console_cols:    i32,
+    pub squares_per_row: i32,
+    pub squares_per_col: i32,
+    pub seed:            Option<u64>,
+}
+
+impl SchotterParams {
+    /// A configuration with these sizes and the classic uniform jitter.
+    pub fn config(&self) -> SchotterConfig {
+        SchotterConfig::new(self.console_cols, self.squares_per_row, self.squares_per_col)
+    }
+}
+
+impl Default for SchotterParams {
+    /// The same sizes that Redis uses by default, with no seed.
+    fn default() -> SchotterParams {
+        let config = SchotterConfig::default();
+        SchotterParams {
+            console_cols:    config.console_cols,
+            squares_per_row: config.squares_per_row,
+            squares_per_col: config.squares_per_col,
+            seed:            None,
+        }
+    }
+}
+
+impl fmt::Display for SchotterParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}x{}", self.console_cols, self.squares_per_row, self.squares_per_col)?;
+        if let Some(seed) = self.seed {
+            write!(f, "@{}", seed)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing `SchotterParams` from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseParamsError {
+    input:     String,
+    component: &'static str,
+    problem:   Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Problem {
+    Missing,
+    /// The component's text, which isn't a number in range.
+    Invalid(String),
+    /// Whatever came after the last size.
+    Trailing(String),
+}
+
+impl ParseParamsError {
+    /// Which part of the input was wrong: `console_cols`, `squares_per_row`,
+    /// `squares_per_col`, or `seed`. Text left over after the sizes is blamed
+    /// on `squares_per_col`.
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+}
+
+impl fmt::Display for ParseParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid parameters `{}`: ", self.input)?;
+        match (&self.problem, self.component) {
+            (Problem::Missing, component) => {
+                write!(f, "{} is missing, expected COLSxPER_ROWxPER_COL[@SEED]", component)
+            }
+            (Problem::Invalid(text), "seed") => {
+                write!(f, "seed `{}` is not a decimal number", text)
+            }
+            (Problem::Invalid(text), component) => {
+                write!(f, "{} `{}` is not a number of at least 1", component, text)
+            }
+            (Problem::Trailing(text), component) => {
+                write!(f, "unexpected `{}` after {}", text, component)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseParamsError {}
+
+impl FromStr for SchotterParams {
+    type Err = ParseParamsError;
+
+    /// Parse `COLSxPER_ROWxPER_COL`, optionally followed by `@SEED`. Every
+    /// size must be at least 1, and the seed is a decimal `u64`.
+    fn from_str(s: &str) -> Result<SchotterParams, ParseParamsError> {
+        let err = |component, problem| ParseParamsError {
+            input: s.to_string(),
+            component,
+            problem,
+        };
+
+        let (sizes, seed) = match s.split_once('@') {
+            Some((sizes, seed)) => (sizes, Some(seed)),
+            None                => (s, None),
+        };
+
+        const NAMES: [&str; 3] = ["console_cols", "squares_per_row", "squares_per_col"];
+        let mut parts = sizes.splitn(NAMES.len(), 'x');
+        let mut values = [0; 3];
+        for (value, &name) in values.iter_mut().zip(NAMES.iter()) {
+            let part = parts.next().ok_or_else(|| err(name, Problem::Missing))?;
+            // The last part holds anything after a third `x` too.
+            let (part, rest) = match part.find('x') {
+                Some(i) => (&part[..i], Some(&part[i..])),
+                None    => (part, None),
+            };
+            if part.is_empty() {
+                return Err(err(name, Problem::Missing));
+            }
+            *value = match part.parse::<i32>() {
+                Ok(number) if number > 0 => number,
+                _ => return Err(err(name, Problem::Invalid(part.to_string()))),
+            };
+            if let Some(rest) = rest {
+                return Err(err(name, Problem::Trailing(rest.to_string())));
+            }
+        }
+
+        let seed = match seed {
+            None     => None,
+            Some("") => return Err(err("seed", Problem::Missing)),
+            Some(seed) => Some(seed.parse::<u64>()
+                                   .map_err(|_| err("seed", Problem::Invalid(seed.to_string())))?),
+        };
+
+        Ok(SchotterParams {
+            console_cols:    values[0],
+            squares_per_row: values[1],
+            squares_per_col: values[2],
+            seed,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Canvas {
+    /// Create a Canvas large enough for `params` and render Schotter onto it,
+    /// like `create_and_render_schotter`, but taking all randomness from
+    /// `params.seed` when there is one.
+    pub fn create_and_render_schotter_params(params: &SchotterParams)
+        -> Result<Canvas, CanvasError>
+    {
+        match params.seed {
+            Some(seed) => {
+                Canvas::create_and_render_schotter_with(&params.config(),
+                                                        &mut StdRng::seed_from_u64(seed))
+            }
+            None => Canvas::create_and_render_schotter_with(&params.config(), &mut thread_rng()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    fn params(cols: i32, per_row: i32, per_col: i32, seed: Option<u64>) -> SchotterParams {
+        SchotterParams {
+            console_cols:    cols,
+            squares_per_row: per_row,
+            squares_per_col: per_col,
+            seed,
+        }
+    }
+
+    #[test]
+    fn check_params_round_trip() {
+        let cases = [
+            ("66x8x12", params(66, 8, 12, None)),
+            ("66x8x12@42", params(66, 8, 12, Some(42))),
+            ("1x1x1@0", params(1, 1, 1, Some(0))),
+            ("40x200x3@18446744073709551615", params(40, 200, 3, Some(u64::MAX))),
+        ];
+        for &(text, expected) in cases.iter() {
+            let parsed: SchotterParams = text.parse().unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.to_string(), text);
+        }
+        assert_eq!(SchotterParams::default().to_string(), "66x8x12");
+        assert_eq!(params(40, 8, 12, None).config(), SchotterConfig::new(40, 8, 12));
+    }
+
+    #[test]
+    fn check_params_errors() {
+        let cases = [
+            // Missing components.
+            ("", "console_cols"),
+            ("66", "squares_per_row"),
+            ("66x8", "squares_per_col"),
+            ("66x8x", "squares_per_col"),
+            ("x8x12", "console_cols"),
+            ("66xx12", "squares_per_row"),
+            ("66x8x12@", "seed"),
+            // Not numbers, or out of range.
+            ("abcx8x12", "console_cols"),
+            ("66x0x12", "squares_per_row"),
+            ("66x8x-1", "squares_per_col"),
+            ("66x8x99999999999", "squares_per_col"),
+            ("66 x8x12", "console_cols"),
+            ("66x8x12@-1", "seed"),
+            ("66x8x12@0x10", "seed"),
+            ("66x8x12@1@2", "seed"),
+            // Too many components.
+            ("66x8x12x4", "squares_per_col"),
+            ("66x8x12x", "squares_per_col"),
+        ];
+        for &(text, component) in cases.iter() {
+            let err = text.parse::<SchotterParams>().unwrap_err();
+            assert_eq!(err.component(), component, "{:?}", text);
+        }
+
+        let message = |text: &str| text.parse::<SchotterParams>().unwrap_err().to_string();
+        assert_eq!(message("66x8"),
+                   "invalid parameters `66x8`: squares_per_col is missing, \
+                    expected COLSxPER_ROWxPER_COL[@SEED]");
+        assert_eq!(message("66x0x12"),
+                   "invalid parameters `66x0x12`: squares_per_row `0` is not a number of at least 1");
+        assert_eq!(message("66x8x12@x"),
+                   "invalid parameters `66x8x12@x`: seed `x` is not a decimal number");
+        assert_eq!(message("66x8x12x4"),
+                   "invalid parameters `66x8x12x4`: unexpected `x4` after squares_per_col");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn check_create_from_params() {
+        let seeded = params(40, 8, 12, Some(7));
+        let canvas = Canvas::create_and_render_schotter_params(&seeded).unwrap();
+        let expected = Canvas::create_and_render_schotter_with(&seeded.config(),
+                                                               &mut StdRng::seed_from_u64(7))
+                           .unwrap();
+        assert_eq!(canvas.render(), expected.render());
+
+        let unseeded = Canvas::create_and_render_schotter_params(&params(40, 8, 12, None)).unwrap();
+        assert_eq!((unseeded.width(), unseeded.height()), (expected.width(), expected.height()));
+    }
+}
@@ -0,0 +1,463 @@
+//! Textures and fills that cover the whole canvas, mostly useful as
+//! backgrounds for Schotter.
+
+use alloc::vec::Vec;
+use core::f32::consts::{FRAC_PI_2, PI};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Canvas;
+
+impl Canvas {
+    /// Fill the canvas with a cloud-like value noise texture.
+    ///
+    /// Random values are assigned to the corners of a lattice with cells
+    /// `scale` pixels wide, and smoothly interpolated in between. Pixels where
+    /// the noise is above `threshold` are set. Noise values are in `[0, 1)`,
+    /// so a `threshold` of 0.5 sets roughly half of the canvas.
+    /// Pixels below the threshold are left alone, so this can be layered
+    /// over other drawings.
+    ///
+    /// The same `seed` always produces the same texture. A non-positive
+    /// `scale` draws nothing.
+    pub fn noise_fill(&mut self, scale: f32, threshold: f32, seed: u64) {
+        if scale.is_nan() || scale <= 0.0 {
+            return;
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let noise = value_noise(x as f32 / scale, y as f32 / scale, seed);
+                if noise > threshold {
+                    self.draw_pixel(x, y, 1);
+                }
+            }
+        }
+    }
+}
+
+impl Canvas {
+    /// Cover the canvas in Truchet tiles `tile_size` pixels square.
+    ///
+    /// Each tile is split by a single diagonal line, running one way or the
+    /// other, like Truchet's original tiles. Together they make a maze of
+    /// zig-zags. See `draw_truchet_curved` for the tiles with arcs.
+    ///
+    /// The same `seed` always picks the same tiles. A `tile_size` of 0 draws
+    /// nothing.
+    pub fn draw_truchet_tiles(&mut self, tile_size: u32, seed: u64) {
+        self.draw_truchet_with(tile_size, seed, |canvas, x, y, size, flipped| {
+            if flipped {
+                canvas.draw_line(x + size, y, x, y + size, 1);
+            } else {
+                canvas.draw_line(x, y, x + size, y + size, 1);
+            }
+        });
+    }
+
+    /// Cover the canvas in curved Truchet tiles `tile_size` pixels square.
+    ///
+    /// Each tile holds two quarter circles that join the midpoints of
+    /// neighboring edges, centered on one or the other pair of opposite
+    /// corners. Whichever way each tile is turned, the arcs of neighboring
+    /// tiles meet, so the result is a web of winding, unbroken curves.
+    /// These are the curved tiles popularized by Cyril Stanley Smith.
+    ///
+    /// The same `seed` always picks the same tiles, turned the same ways as
+    /// `draw_truchet_tiles` turns its diagonals. A `tile_size` of 0 draws
+    /// nothing.
+    pub fn draw_truchet_curved(&mut self, tile_size: u32, seed: u64) {
+        let radius = tile_size as f32 / 2.0;
+        self.draw_truchet_with(tile_size, seed, |canvas, x, y, size, flipped| {
+            if flipped {
+                canvas.draw_arc(x + size, y, radius, FRAC_PI_2, PI, 1);
+                canvas.draw_arc(x, y + size, radius, PI + FRAC_PI_2, 2.0 * PI, 1);
+            } else {
+                canvas.draw_arc(x, y, radius, 0.0, FRAC_PI_2, 1);
+                canvas.draw_arc(x + size, y + size, radius, PI, PI + FRAC_PI_2, 1);
+            }
+        });
+    }
+
+    /// Call `draw_tile` with the top left corner and size of each tile, and
+    /// whether `seed` flipped it.
+    fn draw_truchet_with(&mut self,
+                         tile_size: u32,
+                         seed: u64,
+                         draw_tile: impl Fn(&mut Canvas, i32, i32, i32, bool))
+    {
+        if tile_size == 0 {
+            return;
+        }
+        let size = tile_size.min(i32::MAX as u32) as i32;
+
+        for (row, y) in (0..self.height).step_by(size as usize).enumerate() {
+            for (col, x) in (0..self.width).step_by(size as usize).enumerate() {
+                let flipped = lattice_value(col as i32, row as i32, seed) < 0.5;
+                draw_tile(self, x, y, size, flipped);
+            }
+        }
+    }
+}
+
+impl Canvas {
+    /// Replace the canvas with a checkerboard of `cell_size` pixel squares.
+    ///
+    /// The top-left cell is off, and the cells alternate from there. This
+    /// exercises every braille dot pattern along cell boundaries, which makes
+    /// it a handy test pattern for the renderers. A `cell_size` of 0 leaves
+    /// the canvas alone.
+    pub fn draw_checkerboard(&mut self, cell_size: u32) {
+        if cell_size == 0 {
+            return;
+        }
+        let size = cell_size.min(i32::MAX as u32) as i32;
+        self.fill_with(|x, y| ((x / size + y / size) % 2) as u8);
+    }
+
+    /// Replace the canvas with alternating stripes of on and off pixels,
+    /// running at `angle` radians clockwise from horizontal.
+    ///
+    /// Each stripe is `stripe_width` pixels wide, measured along whichever
+    /// axis is closer to straight across the stripes. This keeps every
+    /// stripe the same whole number of pixels wide, so at 45° the stripes
+    /// are clean staircases. The stripe through the top-left corner is off.
+    /// A `stripe_width` of 0 leaves the canvas alone.
+    pub fn draw_diagonal_stripes(&mut self, stripe_width: u32, angle: f32) {
+        if stripe_width == 0 {
+            return;
+        }
+        let (sin, cos) = (angle as f64).sin_cos();
+        let longest = sin.abs().max(cos.abs());
+        // Multiples of 45° can't be represented exactly, so snap what should
+        // be whole steps to them, or the stripes would drift out of line.
+        let snap = |v: f64| if (v - v.round()).abs() < 1e-6 { v.round() } else { v };
+        let (step_x, step_y) = (snap(-sin / longest), snap(cos / longest));
+        let width = stripe_width as f64;
+
+        self.fill_with(|x, y| {
+            // Distance across the stripes, in stripes.
+            let across = (x as f64 * step_x + y as f64 * step_y) / width;
+            (across.floor() as i64).rem_euclid(2) as u8
+        });
+    }
+}
+
+/// A Wang tile, by the colors of its north, east, south, and west edges.
+type WangTile = [bool; 4];
+const NORTH: usize = 0;
+const EAST:  usize = 1;
+const SOUTH: usize = 2;
+const WEST:  usize = 3;
+
+/// Two colors of edge, and four tiles. Every combination of north and west
+/// edge is matched by one tile, so a tiling can always be continued, and
+/// its east and south edges are the exclusive or of the two.
+const WANG_TILES: [WangTile; 4] = [
+    [false, false, false, false],
+    [false, true,  true,  true],
+    [true,  true,  true,  false],
+    [true,  false, false, true],
+];
+
+impl Canvas {
+    /// Cover the canvas in Wang tiles `tile_size` pixels square.
+    ///
+    /// Neighboring tiles always agree on the color of the edge they share.
+    /// The edges along the top and left of the canvas are colored at random,
+    /// and the tiles are placed from there, each matching the tiles above
+    /// and to the left of it. Each tile is drawn as spokes from its center
+    /// to the middle of every edge of the second color, so matching edges
+    /// join up into one continuous network of lines.
+    ///
+    /// The same `seed` always produces the same tiling. A `tile_size` of 0
+    /// draws nothing.
+    pub fn draw_wang_tiles(&mut self, tile_size: u32, seed: u64) {
+        if tile_size == 0 {
+            return;
+        }
+        let size = tile_size.min(i32::MAX as u32) as i32;
+        let half = size / 2;
+        let cols = (self.width as u32).div_ceil(tile_size);
+        let rows = (self.height as u32).div_ceil(tile_size);
+
+        let grid = wang_tiling(cols as usize, rows as usize, seed);
+        for (row, tiles) in grid.iter().enumerate() {
+            for (col, &tile) in tiles.iter().enumerate() {
+                let (x, y) = (col as i32 * size, row as i32 * size);
+                let center = (x + half, y + half);
+                let midpoints = [(x + half, y), (x + size, y + half),
+                                 (x + half, y + size), (x, y + half)];
+                for (&edge, &(mx, my)) in WANG_TILES[tile].iter().zip(midpoints.iter()) {
+                    if edge {
+                        self.draw_line(center.0, center.1, mx, my, 1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Choose a tile from `WANG_TILES` for each of `rows` by `cols` places,
+/// returning their indices.
+fn wang_tiling(cols: usize, rows: usize, seed: u64) -> Vec<Vec<usize>> {
+    let mut grid: Vec<Vec<usize>> = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let mut tiles: Vec<usize> = Vec::with_capacity(cols);
+        for col in 0..cols {
+            // Match the tiles already placed above and to the left. Along
+            // the canvas's edges, there's nothing to match, so pick a color.
+            let north = match row {
+                0 => lattice_value(col as i32, -1, seed) < 0.5,
+                _ => WANG_TILES[grid[row - 1][col]][SOUTH],
+            };
+            let west = match col {
+                0 => lattice_value(-1, row as i32, seed) < 0.5,
+                _ => WANG_TILES[tiles[col - 1]][EAST],
+            };
+
+            // Exactly one tile matches, so the seed only picks through the
+            // edges along the top and left.
+            let tile = WANG_TILES.iter()
+                                 .position(|tile| tile[NORTH] == north && tile[WEST] == west)
+                                 .unwrap();
+            tiles.push(tile);
+        }
+        grid.push(tiles);
+    }
+    grid
+}
+
+/// Hash a lattice point into a value in `[0, 1)`.
+fn lattice_value(x: i32, y: i32, seed: u64) -> f32 {
+    // This is the finalizer from splitmix64, which mixes its input well
+    // enough that neighboring lattice points look unrelated.
+    let mut h = seed ^ ((x as u32 as u64) << 32 | (y as u32 as u64));
+    h = h.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+
+    // Use the top 24 bits, which is all of the precision an f32 has.
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Sample 2D value noise at `(x, y)`, in lattice units.
+fn value_noise(x: f32, y: f32, seed: u64) -> f32 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+
+    // Smoothstep the interpolation weights so that the lattice doesn't show
+    // up as visible creases.
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let tx = smooth(x - x0);
+    let ty = smooth(y - y0);
+
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let v00 = lattice_value(x0,     y0,     seed);
+    let v10 = lattice_value(x0 + 1, y0,     seed);
+    let v01 = lattice_value(x0,     y0 + 1, seed);
+    let v11 = lattice_value(x0 + 1, y0 + 1, seed);
+
+    let top    = v00 + (v10 - v00) * tx;
+    let bottom = v01 + (v11 - v01) * tx;
+    top + (bottom - top) * ty
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    #[test]
+    fn check_noise_fill() {
+        let mut a = Canvas::create(80, 60).unwrap();
+        let mut b = Canvas::create(80, 60).unwrap();
+        a.noise_fill(8.0, 0.5, 42);
+        b.noise_fill(8.0, 0.5, 42);
+        assert_eq!(a.pixels, b.pixels);
+
+        let set = a.pixel_histogram()[1];
+        assert!(0 < set && set < a.pixel_count(), "set = {}", set);
+
+        let mut c = Canvas::create(80, 60).unwrap();
+        c.noise_fill(8.0, 0.5, 43);
+        assert_ne!(a.pixels, c.pixels);
+    }
+
+    #[test]
+    fn check_value_noise_is_continuous() {
+        for i in 0..1000 {
+            let x = i as f32 * 0.01;
+            let step = (value_noise(x + 0.001, 2.5, 1) - value_noise(x, 2.5, 1)).abs();
+            assert!(step < 0.01, "x = {}, step = {}", x, step);
+        }
+    }
+
+    #[test]
+    fn check_truchet_tiles() {
+        let mut a = Canvas::create(9, 9).unwrap();
+        let mut b = Canvas::create(9, 9).unwrap();
+        a.draw_truchet_tiles(3, 1);
+        b.draw_truchet_tiles(3, 1);
+        assert_eq!(a.pixels, b.pixels);
+
+        // Every tile is a diagonal line, one way or the other, even when the
+        // tiles are big.
+        for &(size, seed) in [(3u32, 1), (10, 7)].iter() {
+            let mut canvas = Canvas::create(size * 3, size * 3).unwrap();
+            canvas.draw_truchet_tiles(size, seed);
+            let (size, far) = (size as i32, size as i32 - 1);
+            let on = |x, y| canvas.get_pixel(x, y);
+            for y in (0..size * 3).step_by(size as usize) {
+                for x in (0..size * 3).step_by(size as usize) {
+                    let down = on(x + 1, y + 1) & on(x + far, y + far);
+                    let up   = on(x + far, y + 1) & on(x + 1, y + far);
+                    assert_eq!(down ^ up, 1, "{}: ({}, {})", size, x, y);
+                }
+            }
+        }
+
+        let mut empty = Canvas::create(10, 10).unwrap();
+        empty.draw_truchet_tiles(0, 1);
+        assert!(empty.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_truchet_curved() {
+        let mut a = Canvas::create(40, 40).unwrap();
+        let mut b = Canvas::create(40, 40).unwrap();
+        a.draw_truchet_curved(10, 7);
+        b.draw_truchet_curved(10, 7);
+        assert_eq!(a.pixels, b.pixels);
+        b.clear();
+        b.draw_truchet_curved(10, 8);
+        assert_ne!(a.pixels, b.pixels);
+
+        // Every tile's arcs reach the middle of all four of its edges, which
+        // is what joins them up with their neighbors.
+        for y in (0..40).step_by(10) {
+            for x in (0..40).step_by(10) {
+                for &(mx, my) in [(x + 5, y), (x, y + 5), (x + 10, y + 5), (x + 5, y + 10)].iter() {
+                    if mx < 40 && my < 40 {
+                        assert_eq!(a.get_pixel(mx, my), 1, "({}, {})", mx, my);
+                    }
+                }
+                // And the tiles' corners are clear.
+                assert_eq!(a.get_pixel(x + 1, y + 1) & a.get_pixel(x + 8, y + 1), 0);
+            }
+        }
+
+        // The middle of each tile is clear too, where the diagonals would
+        // cross.
+        for y in (0..40).step_by(10) {
+            for x in (0..40).step_by(10) {
+                assert_eq!(a.get_pixel(x + 5, y + 5), 0, "({}, {})", x, y);
+            }
+        }
+
+        let mut empty = Canvas::create(10, 10).unwrap();
+        empty.draw_truchet_curved(0, 1);
+        assert!(empty.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_wang_tiling_matches_edges() {
+        for seed in 0..20 {
+            let grid = wang_tiling(9, 7, seed);
+            assert_eq!(grid.len(), 7);
+            for row in 0..7 {
+                assert_eq!(grid[row].len(), 9);
+                for col in 0..9 {
+                    let tile = WANG_TILES[grid[row][col]];
+                    if col > 0 {
+                        assert_eq!(WANG_TILES[grid[row][col - 1]][EAST], tile[WEST]);
+                    }
+                    if row > 0 {
+                        assert_eq!(WANG_TILES[grid[row - 1][col]][SOUTH], tile[NORTH]);
+                    }
+                }
+            }
+        }
+
+        // The random edges make for different tilings, using every tile.
+        let a = wang_tiling(12, 12, 1);
+        assert_ne!(a, wang_tiling(12, 12, 2));
+        for i in 0..WANG_TILES.len() {
+            assert!(a.iter().flatten().any(|&tile| tile == i), "tile {} unused", i);
+        }
+    }
+
+    #[test]
+    fn check_wang_tiles() {
+        let mut a = Canvas::create(40, 30).unwrap();
+        let mut b = Canvas::create(40, 30).unwrap();
+        a.draw_wang_tiles(10, 3);
+        b.draw_wang_tiles(10, 3);
+        assert_eq!(a.pixels, b.pixels);
+
+        // Every tile's spokes are where its edges say, and so line up with
+        // the neighbors' spokes.
+        let grid = wang_tiling(4, 3, 3);
+        for (row, tiles) in grid.iter().enumerate() {
+            for (col, &tile) in tiles.iter().enumerate() {
+                let (x, y) = (col as i32 * 10, row as i32 * 10);
+                let tile = WANG_TILES[tile];
+                assert_eq!(a.get_pixel(x + 5, y + 2) != 0, tile[NORTH]);
+                assert_eq!(a.get_pixel(x + 8, y + 5) != 0, tile[EAST]);
+                assert_eq!(a.get_pixel(x + 5, y + 8) != 0, tile[SOUTH]);
+                assert_eq!(a.get_pixel(x + 2, y + 5) != 0, tile[WEST]);
+            }
+        }
+
+        let mut empty = Canvas::create(10, 10).unwrap();
+        empty.draw_wang_tiles(0, 1);
+        assert!(empty.pixels.iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_checkerboard() {
+        let mut canvas = Canvas::create(6, 4).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_checkerboard(2);
+        assert_eq!(canvas.pixel_bytes(), [0, 0, 1, 1, 0, 0,
+                                          0, 0, 1, 1, 0, 0,
+                                          1, 1, 0, 0, 1, 1,
+                                          1, 1, 0, 0, 1, 1]);
+
+        canvas.draw_checkerboard(1);
+        assert_eq!(canvas.render_ascii(), " # # #\n# # # \n # # #\n# # # \n");
+
+        let before = canvas.pixels.clone();
+        canvas.draw_checkerboard(0);
+        assert_eq!(canvas.pixels, before);
+    }
+
+    #[test]
+    fn check_diagonal_stripes() {
+        use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+        let mut canvas = Canvas::create(6, 4).unwrap();
+        canvas.draw_diagonal_stripes(2, 0.0);
+        assert_eq!(canvas.render_ascii(), "      \n      \n######\n######\n");
+
+        canvas.draw_diagonal_stripes(1, FRAC_PI_2);
+        assert_eq!(canvas.render_ascii(), " # # #\n # # #\n # # #\n # # #\n");
+
+        // At 45°, each stripe is a clean staircase, the same width all the
+        // way down.
+        let mut canvas = Canvas::create(40, 40).unwrap();
+        for &width in [1, 2, 3].iter() {
+            canvas.draw_diagonal_stripes(width, FRAC_PI_4);
+            for y in 0..40 {
+                for x in 0..40 {
+                    let expected = (y - x + 1000 * width as i32) / width as i32 % 2;
+                    assert_eq!(canvas.get_pixel(x, y) as i32, expected, "({}, {})", x, y);
+                }
+            }
+        }
+
+        let before = canvas.pixels.clone();
+        canvas.draw_diagonal_stripes(0, 1.0);
+        assert_eq!(canvas.pixels, before);
+    }
+}
@@ -0,0 +1,203 @@
+//! Penrose tilings, grown by subdividing triangles.
+//!
+//! A rhombus (P3) Penrose tiling can be cut into Robinson triangles: each
+//! rhombus is two of them, back to back. Every triangle can in turn be split
+//! into smaller triangles of the same two shapes, scaled down by the golden
+//! ratio, so starting from a wheel of ten triangles and splitting them over
+//! and over fills the wheel with an ever finer tiling.
+//!      https://preshing.com/20110831/penrose-tiling-explained/
+
+use core::f32::consts::PI;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Canvas;
+
+/// The golden ratio, `(1 + √5) / 2`.
+const PHI: f32 = 1.618_034;
+
+/// The shortest sides, in pixels, that tiles are subdivided into. Any smaller
+/// and the rhombi would just be blobs.
+const MIN_TILE_SIDE: f32 = 2.0;
+
+type Point = (f32, f32);
+
+/// The two shapes of Robinson triangle, each half of a rhombus.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Robinson {
+    /// The apex is 36°, and two of these make a thin rhombus.
+    Acute,
+    /// The apex is 108°, and two of these make a fat rhombus.
+    Obtuse,
+}
+
+impl Canvas {
+    /// Draw a rhombus Penrose tiling filling a decagon around
+    /// `(center_x, center_y)`, `size` pixels from its center to its corners.
+    ///
+    /// The tiling starts as a wheel of ten thin rhombi halves meeting in the
+    /// middle, and each generation splits every tile into smaller ones, the
+    /// golden ratio (about 1.618) times smaller. This stops after
+    /// `generations`, or once the tiles' sides would be shorter than 2 pixels,
+    /// whichever comes first. Only tiles that reach the canvas are split, so
+    /// a tiling much bigger than the canvas takes only as long as the part
+    /// that shows. Only the rhombi are outlined, not the triangles they're
+    /// made of.
+    ///
+    /// Nothing is drawn unless `size` is positive.
+    pub fn draw_penrose_tiling(&mut self,
+                               center_x: i32,
+                               center_y: i32,
+                               size: f32,
+                               generations: u32,
+                               color: u8)
+    {
+        if size.is_nan() || size <= 0.0 {
+            return;
+        }
+
+        let mut depth = 0;
+        let mut side = size;
+        while depth < generations && side / PHI >= MIN_TILE_SIDE {
+            side /= PHI;
+            depth += 1;
+        }
+
+        let center = (center_x as f32, center_y as f32);
+        let corner = |k: i32| {
+            let angle = k as f32 * PI / 10.0;
+            (center.0 + size * angle.cos(), center.1 + size * angle.sin())
+        };
+        for i in 0..10 {
+            let (mut b, mut c) = (corner(2 * i - 1), corner(2 * i + 1));
+            // Mirror every other triangle, so that neighbors fit together.
+            if i % 2 == 0 {
+                core::mem::swap(&mut b, &mut c);
+            }
+            self.draw_robinson(Robinson::Acute, center, b, c, depth, color);
+        }
+    }
+
+    /// Draw the triangle with apex `a` and base `bc`, split `depth` more
+    /// times. The smaller triangles stay inside the big one, so a triangle
+    /// that's off of the canvas is skipped entirely.
+    fn draw_robinson(&mut self,
+                     shape: Robinson,
+                     a: Point,
+                     b: Point,
+                     c: Point,
+                     depth: u32,
+                     color: u8)
+    {
+        if self.misses_canvas(&[a, b, c]) {
+            return;
+        }
+        if depth == 0 {
+            // The base is the middle of a rhombus, so only the legs are
+            // edges of the tiling.
+            let pixel = |(x, y): Point| (x.round() as i32, y.round() as i32);
+            let (a, b, c) = (pixel(a), pixel(b), pixel(c));
+            self.draw_line(c.0, c.1, a.0, a.1, color);
+            self.draw_line(a.0, a.1, b.0, b.1, color);
+            return;
+        }
+
+        // The point `1/φ` of the way from `from` to `to`.
+        let golden = |from: Point, to: Point| {
+            (from.0 + (to.0 - from.0) / PHI, from.1 + (to.1 - from.1) / PHI)
+        };
+        match shape {
+            Robinson::Acute => {
+                let p = golden(a, b);
+                self.draw_robinson(Robinson::Acute, c, p, b, depth - 1, color);
+                self.draw_robinson(Robinson::Obtuse, p, c, a, depth - 1, color);
+            }
+            Robinson::Obtuse => {
+                let q = golden(b, a);
+                let r = golden(b, c);
+                self.draw_robinson(Robinson::Obtuse, r, c, a, depth - 1, color);
+                self.draw_robinson(Robinson::Obtuse, q, r, b, depth - 1, color);
+                self.draw_robinson(Robinson::Acute, r, q, a, depth - 1, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use crate::t::centered_tiling;
+    use crate::Canvas;
+
+    fn tiling(size: f32, generations: u32) -> Canvas {
+        centered_tiling(Canvas::draw_penrose_tiling, 101, size, generations)
+    }
+
+    #[test]
+    fn check_penrose_wheel() {
+        // Before any splitting, it's ten spokes, 36° apart starting at 18°.
+        let wheel = tiling(40.0, 0);
+        assert_eq!(wheel.get_pixel(50, 50), 1);
+        for &(x, y) in [(88, 62), (50, 90), (12, 38), (50, 10)].iter() {
+            assert_eq!(wheel.get_pixel(x, y), 1, "({}, {})", x, y);
+        }
+        // But no rim between them.
+        assert_eq!(wheel.get_pixel(81, 72), 0);
+        assert_eq!(wheel.get_pixel(90, 50), 0);
+    }
+
+    #[test]
+    fn check_penrose_generations() {
+        let mut last = tiling(40.0, 0).pixel_histogram()[1];
+        for generations in 1..5 {
+            let canvas = tiling(40.0, generations);
+            let on = canvas.pixel_histogram()[1];
+            assert!(on > last, "{} generations: {} <= {}", generations, on, last);
+            last = on;
+
+            // Everything is inside the wheel.
+            for y in 0..101 {
+                for x in 0..101 {
+                    if canvas.get_pixel(x, y) != 0 {
+                        let (dx, dy) = ((x - 50) as f32, (y - 50) as f32);
+                        assert!(dx.hypot(dy) <= 41.0, "({}, {})", x, y);
+                    }
+                }
+            }
+        }
+
+        // Splitting stops once the sides get too short: 40 / φ^6 is 2.2,
+        // and 40 / φ^7 is 1.4.
+        assert!(tiling(40.0, 6).pixels != tiling(40.0, 5).pixels);
+        assert!(tiling(40.0, 7).pixels == tiling(40.0, 6).pixels);
+        assert!(tiling(40.0, 1000).pixels == tiling(40.0, 6).pixels);
+
+        for &size in [0.0, -3.0, f32::NAN].iter() {
+            assert_eq!(tiling(size, 3).pixel_histogram()[1], 0);
+        }
+    }
+
+    #[test]
+    fn check_penrose_off_canvas() {
+        // Part of a tiling is the same as that part of the whole thing.
+        let whole = tiling(45.0, 4);
+        let mut part = Canvas::create(30, 20).unwrap();
+        part.draw_penrose_tiling(50 - 60, 50 - 25, 45.0, 4, 1);
+        assert!(part.pixel_histogram()[1] > 0);
+        for y in 0..20 {
+            for x in 0..30 {
+                assert_eq!(part.get_pixel(x, y), whole.get_pixel(x + 60, y + 25), "({}, {})", x, y);
+            }
+        }
+
+        // And only that part is split, so a tiling of millions of pixels
+        // with tiny tiles is quick to draw a corner of.
+        let mut corner = Canvas::create(40, 40).unwrap();
+        corner.draw_penrose_tiling(0, 0, 5e6, u32::MAX, 1);
+        assert!(corner.pixel_histogram()[1] > 0);
+
+        // Nowhere near the canvas, there's nothing to draw.
+        let mut far = Canvas::create(40, 40).unwrap();
+        far.draw_penrose_tiling(-1000, 20, 100.0, 5, 1);
+        assert_eq!(far.pixel_histogram()[1], 0);
+    }
+}
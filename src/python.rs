@@ -0,0 +1,167 @@
+//! A `lolwut` Python module, through PyO3.
+//!
+//! To build the extension, link it as a cdylib without libpython, and name
+//! it the way Python expects:
+//!
+//! ```text
+//! cargo rustc --lib --release --features python,pyo3/extension-module --crate-type cdylib
+//! cp target/release/liblolwut.so lolwut.so
+//! ```
+//!
+//! Then `from lolwut import Canvas` works from the same directory.
+
+// PyO3's generated glue converts every error into a `PyErr`, even ones that
+// already are.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rand::prelude::*;
+
+use crate::{Canvas, CanvasError, SchotterConfig};
+
+/// A `Canvas`, for Python.
+#[pyclass(name = "Canvas")]
+pub struct PyCanvas {
+    canvas: Canvas,
+}
+
+#[pymethods]
+impl PyCanvas {
+    /// Create a blank canvas, `width` by `height` pixels.
+    #[new]
+    fn new(width: u32, height: u32) -> PyResult<PyCanvas> {
+        let canvas = Canvas::create(width, height).map_err(to_py)?;
+        Ok(PyCanvas { canvas })
+    }
+
+    #[getter]
+    fn width(&self) -> i32 {
+        self.canvas.width()
+    }
+
+    #[getter]
+    fn height(&self) -> i32 {
+        self.canvas.height()
+    }
+
+    /// Draw Schotter, taking all randomness from `seed` if there is one.
+    #[pyo3(signature = (cols, per_row, per_col, seed = None))]
+    fn draw_schotter(&mut self, cols: i32, per_row: i32, per_col: i32, seed: Option<u64>)
+        -> PyResult<()>
+    {
+        let config = SchotterConfig::new(cols, per_row, per_col);
+        match seed {
+            Some(seed) => {
+                self.canvas.draw_schotter_with(&config, &mut StdRng::seed_from_u64(seed))
+            }
+            None => self.canvas.draw_schotter_with(&config, &mut thread_rng()),
+        }
+        .map_err(to_py)
+    }
+
+    #[pyo3(signature = (x1, y1, x2, y2, color = 1))]
+    fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: u8) {
+        self.canvas.draw_line(x1, y1, x2, y2, color);
+    }
+
+    fn get_pixel(&self, x: i32, y: i32) -> u8 {
+        self.canvas.get_pixel(x, y)
+    }
+
+    /// Render the canvas as braille text, like `Canvas::render`.
+    fn render(&self) -> String {
+        self.canvas.render()
+    }
+
+    /// The pixels, row by row, with 1 for on and 0 for off. In a notebook,
+    /// `numpy.frombuffer(canvas.pixels(), numpy.uint8)
+    /// .reshape(canvas.height, canvas.width)` makes an array of them.
+    fn pixels<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.canvas.pixel_bytes())
+    }
+
+    /// The braille render, so that `print(canvas)` shows the drawing.
+    fn __str__(&self) -> String {
+        self.canvas.render()
+    }
+}
+
+fn to_py(err: CanvasError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// The `lolwut` module, with the `Canvas` class in it.
+#[pymodule]
+fn lolwut(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCanvas>()
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    #[test]
+    fn check_py_canvas() {
+        let mut canvas = PyCanvas::new(80, 118).unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (80, 118));
+        canvas.draw_schotter(40, 8, 12, Some(7)).unwrap();
+
+        // The same as drawing with the library directly.
+        let config = SchotterConfig::new(40, 8, 12);
+        let expected = Canvas::create_and_render_schotter_with(&config,
+                                                               &mut StdRng::seed_from_u64(7))
+                           .unwrap();
+        assert_eq!(canvas.render(), expected.render());
+        assert_eq!(canvas.__str__(), expected.render());
+        assert_eq!(canvas.canvas.pixel_bytes(), expected.pixel_bytes());
+        assert_eq!(canvas.get_pixel(1, 2), expected.get_pixel(1, 2));
+
+        let mut blank = PyCanvas::new(3, 2).unwrap();
+        blank.draw_line(0, 1, 2, 1, 1);
+        assert_eq!(blank.canvas.pixel_bytes(), [0, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn check_py_errors() {
+        pyo3::prepare_freethreaded_python();
+        let mut canvas = PyCanvas::new(10, 10).unwrap();
+        let err = canvas.draw_schotter(40, 8, 12, None).unwrap_err();
+        let expected = Canvas::create(10, 10).unwrap()
+                           .draw_schotter_with(&SchotterConfig::new(40, 8, 12), &mut thread_rng())
+                           .unwrap_err();
+        Python::with_gil(|py| {
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(err.value_bound(py).to_string(), expected.to_string());
+        });
+    }
+
+    #[test]
+    fn check_py_module() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "lolwut").unwrap();
+            lolwut(&module).unwrap();
+            let globals = [("lolwut", module)].into_py_dict_bound(py);
+            py.run_bound(r#"
+canvas = lolwut.Canvas(80, 118)
+canvas.draw_schotter(40, 8, 12, seed=7)
+assert (canvas.width, canvas.height) == (80, 118)
+assert str(canvas) == canvas.render()
+pixels = canvas.pixels()
+assert isinstance(pixels, bytes) and len(pixels) == 80 * 118
+assert pixels[80 * 3 + 2] == canvas.get_pixel(2, 3)
+
+canvas.draw_line(0, 0, 79, 0)
+assert canvas.get_pixel(40, 0) == 1
+try:
+    lolwut.Canvas(2, 2).draw_schotter(40, 8, 12)
+    raise AssertionError("expected a ValueError")
+except ValueError:
+    pass
+"#, Some(&globals), None).unwrap();
+        });
+    }
+}
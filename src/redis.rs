@@ -0,0 +1,139 @@
+//! The Redis side of a `LOLWUT.RS` command: Redis's arguments in, the reply
+//! text out.
+//!
+//! These don't touch the module API, so they can be tested without a
+//! server. The `lolwut_redis` example is the module that registers the
+//! command: it passes each call's arguments (everything after the command
+//! name) and a fresh seed to `lolwut_command`, and replies with the text as
+//! a bulk string, or with the error.
+
+use rand::prelude::*;
+
+use crate::{Canvas, SchotterConfig, ATTRIBUTION};
+
+/// The only version of LOLWUT this crate implements.
+pub const LOLWUT_VERSION: i64 = 5;
+
+/// The reply Redis gives for an argument that isn't a number.
+pub const NOT_AN_INTEGER: &str = "ERR value is not an integer or out of range";
+
+/// The arguments to `LOLWUT.RS`, parsed and clamped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LolwutArgs {
+    pub version: i64,
+    pub cols: i32,
+    pub squares_per_row: i32,
+    pub squares_per_col: i32,
+}
+
+/// Parse `[VERSION v] [cols [squares-per-row [squares-per-col]]]` the way
+/// Redis parses `LOLWUT`'s arguments.
+///
+/// `VERSION` is matched case insensitively, numbers must be written exactly
+/// as Redis would print them, and any arguments past the third number are
+/// ignored. Like Redis, out of range sizes are clamped rather than refused:
+/// `cols` to 1 through 1000, and the squares to 1 through 200. On failure,
+/// the error is the message to reply with.
+pub fn parse_lolwut_args(args: &[&[u8]]) -> Result<LolwutArgs, String> {
+    let mut args = args;
+    let mut version = LOLWUT_VERSION;
+    if args.len() >= 2 && args[0].eq_ignore_ascii_case(b"version") {
+        version = parse_long(args[1])?;
+        args = &args[2..];
+    }
+    if version != LOLWUT_VERSION {
+        return Err(format!("ERR LOLWUT.RS only implements version {}, not {}",
+                           LOLWUT_VERSION, version));
+    }
+
+    let mut sizes = [66, 8, 12];
+    for (size, arg) in sizes.iter_mut().zip(args) {
+        *size = parse_long(arg)?;
+    }
+    Ok(LolwutArgs {
+        version,
+        cols:            sizes[0].clamp(1, 1000) as i32,
+        squares_per_row: sizes[1].clamp(1, 200) as i32,
+        squares_per_col: sizes[2].clamp(1, 200) as i32,
+    })
+}
+
+/// The reply text: the rendered canvas, then the attribution and version on
+/// a line of their own after a blank line, as Redis does.
+pub fn format_lolwut_reply(canvas: &Canvas) -> String {
+    format!("{}\n{}. lolwut ver. {}\n", canvas.render(), ATTRIBUTION, env!("CARGO_PKG_VERSION"))
+}
+
+/// Run `LOLWUT.RS` with `args`, taking all randomness from `seed`, and
+/// return the reply text or the error message.
+pub fn lolwut_command(args: &[&[u8]], seed: u64) -> Result<String, String> {
+    let args = parse_lolwut_args(args)?;
+    let config = SchotterConfig::new(args.cols, args.squares_per_row, args.squares_per_col);
+    let canvas = Canvas::create_and_render_schotter_with(&config,
+                                                         &mut StdRng::seed_from_u64(seed))
+                     .map_err(|err| format!("ERR {}", err))?;
+    Ok(format_lolwut_reply(&canvas))
+}
+
+/// Parse a number the way Redis's `getLongFromObjectOrReply` does: only the
+/// canonical decimal form, with no sign for positive numbers, no leading
+/// zeros and no spaces.
+fn parse_long(arg: &[u8]) -> Result<i64, String> {
+    let text = core::str::from_utf8(arg).map_err(|_| NOT_AN_INTEGER.to_string())?;
+    match text.parse::<i64>() {
+        Ok(n) if n.to_string() == text => Ok(n),
+        _                              => Err(NOT_AN_INTEGER.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LolwutArgs, String> {
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        parse_lolwut_args(&args)
+    }
+
+    fn sizes(args: &[&str]) -> (i32, i32, i32) {
+        let args = parse(args).unwrap();
+        (args.cols, args.squares_per_row, args.squares_per_col)
+    }
+
+    #[test]
+    fn check_lolwut_args() {
+        assert_eq!(sizes(&[]), (66, 8, 12));
+        assert_eq!(sizes(&["40"]), (40, 8, 12));
+        assert_eq!(sizes(&["40", "4", "6", "ignored"]), (40, 4, 6));
+        assert_eq!(sizes(&["VERSION", "5", "40", "4"]), (40, 4, 12));
+        assert_eq!(sizes(&["version", "5"]), (66, 8, 12));
+        // Clamped, not refused.
+        assert_eq!(sizes(&["0", "-5", "1000000"]), (1, 1, 200));
+        assert_eq!(sizes(&["5000"]), (1000, 8, 12));
+        // A lone VERSION is just a bad number.
+        assert_eq!(parse(&["VERSION"]), Err(NOT_AN_INTEGER.to_string()));
+
+        for &bad in ["", "4.0", "+4", "04", "-0", " 4", "4x", "99999999999999999999"].iter() {
+            assert_eq!(parse(&[bad]), Err(NOT_AN_INTEGER.to_string()), "{:?}", bad);
+        }
+        assert_eq!(parse(&["VERSION", "five"]), Err(NOT_AN_INTEGER.to_string()));
+        assert_eq!(parse(&["VERSION", "6", "40"]),
+                   Err("ERR LOLWUT.RS only implements version 5, not 6".to_string()));
+    }
+
+    #[test]
+    fn check_lolwut_reply() {
+        let reply = lolwut_command(&[b"40", b"8", b"12"], 7).unwrap();
+        let config = SchotterConfig::new(40, 8, 12);
+        let canvas = Canvas::create_and_render_schotter_with(&config,
+                                                             &mut StdRng::seed_from_u64(7))
+                         .unwrap();
+        let attribution = format!("\n{}. lolwut ver. {}\n", ATTRIBUTION, env!("CARGO_PKG_VERSION"));
+        assert_eq!(reply, canvas.render() + &attribution);
+        assert_eq!(format_lolwut_reply(&canvas), reply);
+
+        // A fresh seed, a fresh drawing.
+        assert_ne!(lolwut_command(&[], 1).unwrap(), lolwut_command(&[], 2).unwrap());
+        assert_eq!(lolwut_command(&[b"x"], 1), Err(NOT_AN_INTEGER.to_string()));
+    }
+}
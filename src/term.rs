@@ -0,0 +1,182 @@
+//! Drawing canvases straight to a terminal, with plain ANSI escape
+//! sequences.
+//!
+//! Everything here writes to any `io::Write`, so it works the same on a
+//! `Vec<u8>` as on stdout. Windows consoles only understand the escape
+//! sequences once `enable_virtual_terminal` has been called.
+
+use std::io::{self, Write};
+
+use crate::Canvas;
+
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+/// Erase from the cursor to the end of the line.
+pub const CLEAR_LINE: &str = "\x1b[K";
+/// Erase from the cursor to the end of the screen.
+pub const CLEAR_BELOW: &str = "\x1b[J";
+
+/// Move the cursor up `lines` lines. CUU treats 0 as 1, so that's skipped.
+pub fn cursor_up(lines: usize) -> String {
+    if lines == 0 {
+        String::new()
+    } else {
+        format!("\x1b[{}A", lines)
+    }
+}
+
+/// Move the cursor to `row` and `col`, counting from 1 at the top-left
+/// corner of the screen, like CUP does.
+pub fn cursor_to(row: u16, col: u16) -> String {
+    format!("\x1b[{};{}H", row, col)
+}
+
+pub fn hide_cursor<W: Write>(mut out: W) -> io::Result<()> {
+    out.write_all(HIDE_CURSOR.as_bytes())
+}
+
+pub fn show_cursor<W: Write>(mut out: W) -> io::Result<()> {
+    out.write_all(SHOW_CURSOR.as_bytes())
+}
+
+/// Draw `canvas` as braille with its top-left cell at `row` and `col`,
+/// counting from 1 like `cursor_to`.
+///
+/// Each line is positioned on its own, so nothing outside the canvas's
+/// cells is touched, and the cursor is left just after the last cell.
+/// Lines that would fall below row 65535 are left out.
+pub fn draw_at<W: Write>(mut out: W, canvas: &Canvas, row: u16, col: u16) -> io::Result<()> {
+    let mut text = String::new();
+    for (line, row) in canvas.render().lines().zip(row..=u16::MAX) {
+        text += &cursor_to(row, col);
+        text += line;
+    }
+    out.write_all(text.as_bytes())
+}
+
+/// Draw `canvas` as braille over the `prev_lines` lines just above the
+/// cursor, like the frames of an animation.
+///
+/// Each line is cleared to its end, in case the last drawing was wider, and
+/// anything below the new drawing is cleared, in case it was taller. The
+/// cursor is left at the start of the line below, and the number of lines
+/// drawn is returned, to be passed as `prev_lines` next time.
+pub fn redraw_in_place<W: Write>(mut out: W, canvas: &Canvas, prev_lines: u16)
+    -> io::Result<u16>
+{
+    let mut text = cursor_up(prev_lines as usize);
+    let mut lines = 0u16;
+    for line in canvas.render().lines() {
+        text += line;
+        text += CLEAR_LINE;
+        text.push('\n');
+        lines = lines.saturating_add(1);
+    }
+    text += CLEAR_BELOW;
+    out.write_all(text.as_bytes())?;
+    Ok(lines)
+}
+
+/// Make the console understand ANSI escape sequences.
+///
+/// On Windows this turns on virtual terminal processing for stdout, which
+/// fails if stdout isn't a console. Everywhere else, terminals already
+/// understand them, and this does nothing.
+pub fn enable_virtual_terminal() -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        windows::enable_virtual_terminal()
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::io;
+    use std::os::raw::c_void;
+
+    type Handle = *mut c_void;
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const INVALID_HANDLE_VALUE: Handle = -1isize as Handle;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+    }
+
+    pub fn enable_virtual_terminal() -> io::Result<()> {
+        // These only read and set the mode of our own console.
+        unsafe {
+            let console = GetStdHandle(STD_OUTPUT_HANDLE);
+            if console.is_null() || console == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            let mut mode = 0;
+            if GetConsoleMode(console, &mut mode) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if SetConsoleMode(console, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    /// A 4x8 canvas, two cells by two, with only the top-left pixel and the
+    /// bottom-right one on.
+    fn corners() -> Canvas {
+        let mut canvas = Canvas::create(4, 8).unwrap();
+        canvas.draw_pixel(0, 0, 1);
+        canvas.draw_pixel(3, 7, 1);
+        canvas
+    }
+
+    #[test]
+    fn check_draw_at() {
+        let mut out = vec![];
+        draw_at(&mut out, &corners(), 3, 10).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[3;10H⠁⠀\x1b[4;10H⠀⢀");
+
+        // Rows past the bottom of the screen are dropped.
+        let mut out = vec![];
+        draw_at(&mut out, &corners(), u16::MAX, 1).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[65535;1H⠁⠀");
+
+        let mut out = vec![];
+        draw_at(&mut out, &Canvas::create(0, 0).unwrap(), 1, 1).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn check_redraw_in_place() {
+        let mut out = vec![];
+        assert_eq!(redraw_in_place(&mut out, &corners(), 0).unwrap(), 2);
+        assert_eq!(redraw_in_place(&mut out, &corners(), 2).unwrap(), 2);
+        let frame = "⠁⠀\x1b[K\n⠀⢀\x1b[K\n\x1b[J";
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{}\x1b[2A{}", frame, frame));
+    }
+
+    #[test]
+    fn check_cursor() {
+        let mut out = vec![];
+        hide_cursor(&mut out).unwrap();
+        show_cursor(&mut out).unwrap();
+        assert_eq!(out, b"\x1b[?25l\x1b[?25h");
+
+        assert_eq!(cursor_up(0), "");
+        assert_eq!(cursor_up(7), "\x1b[7A");
+        assert_eq!(cursor_to(1, 1), "\x1b[1;1H");
+    }
+}
@@ -0,0 +1,100 @@
+//! Bindings for using the canvas from JavaScript, through `wasm-bindgen`.
+
+use alloc::{string::{String, ToString}, vec::Vec};
+
+use rand::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{schotter_dimensions, Canvas, SchotterConfig};
+
+/// A `Canvas`, for JavaScript.
+///
+/// There's no `thread_rng` to fall back on in the browser, so everything
+/// random takes a seed. Seeds are `u64`s, which are `BigInt`s in JavaScript.
+#[wasm_bindgen]
+pub struct WasmCanvas {
+    canvas: Canvas,
+}
+
+#[wasm_bindgen]
+impl WasmCanvas {
+    /// Create a blank canvas, `width` by `height` pixels.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Result<WasmCanvas, JsError> {
+        let canvas = Canvas::create(width, height).map_err(to_js)?;
+        Ok(WasmCanvas { canvas })
+    }
+
+    /// Create a blank canvas just big enough for `drawSchotter` with the
+    /// same parameters.
+    #[wasm_bindgen(js_name = forSchotter)]
+    pub fn for_schotter(cols: i32, per_row: i32, per_col: i32) -> Result<WasmCanvas, JsError> {
+        let (width, height) = schotter_dimensions(cols, per_row, per_col).map_err(to_js)?;
+        WasmCanvas::new(width, height)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> i32 {
+        self.canvas.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> i32 {
+        self.canvas.height()
+    }
+
+    /// Draw Schotter, taking all randomness from `seed`.
+    #[wasm_bindgen(js_name = drawSchotter)]
+    pub fn draw_schotter(&mut self, cols: i32, per_row: i32, per_col: i32, seed: u64)
+        -> Result<(), JsError>
+    {
+        let config = SchotterConfig::new(cols, per_row, per_col);
+        self.canvas.draw_schotter_with(&config, &mut StdRng::seed_from_u64(seed))
+                   .map_err(to_js)
+    }
+
+    /// Render the canvas as braille text, like `Canvas::render`.
+    pub fn render(&self) -> String {
+        self.canvas.render()
+    }
+
+    /// A copy of the pixels, row by row, with 1 for on and 0 for off. This
+    /// is a `Uint8Array` in JavaScript, ready to paint onto a `<canvas>`.
+    pub fn pixels(&self) -> Vec<u8> {
+        self.canvas.pixel_bytes()
+    }
+}
+
+fn to_js(err: crate::CanvasError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+#[cfg(test)]
+mod t {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::WasmCanvas;
+    use crate::{Canvas, SchotterConfig};
+    use rand::prelude::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn check_wasm_canvas() {
+        let mut canvas = WasmCanvas::for_schotter(40, 8, 12).unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (80, 118));
+        canvas.draw_schotter(40, 8, 12, 7).unwrap();
+
+        // The same as drawing with the library directly.
+        let config = SchotterConfig::new(40, 8, 12);
+        let expected = Canvas::create_and_render_schotter_with(&config,
+                                                               &mut StdRng::seed_from_u64(7))
+                           .unwrap();
+        assert_eq!(canvas.render(), expected.render());
+        assert_eq!(canvas.pixels(), expected.pixel_bytes());
+
+        let blank = WasmCanvas::new(3, 2).unwrap();
+        assert_eq!(blank.pixels(), [0; 6]);
+        assert_eq!(blank.render(), "⠀⠀\n");
+    }
+}
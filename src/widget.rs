@@ -0,0 +1,179 @@
+//! Drawing canvases into `ratatui` layouts.
+
+use std::borrow::Cow;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+
+use crate::Canvas;
+
+/// A `ratatui` widget showing a `Canvas` as braille, one 2x4 cell of pixels
+/// to each terminal cell, like `Canvas::render`.
+///
+/// By default the canvas sits in the top-left corner of the widget's area,
+/// and whatever doesn't fit is cut off.
+#[derive(Clone)]
+pub struct CanvasWidget<'a> {
+    canvas: &'a Canvas,
+    style: Style,
+    centered: bool,
+    scaled_to_fit: bool,
+}
+
+impl<'a> CanvasWidget<'a> {
+    pub fn new(canvas: &'a Canvas) -> CanvasWidget<'a> {
+        CanvasWidget {
+            canvas,
+            style: Style::default(),
+            centered: false,
+            scaled_to_fit: false,
+        }
+    }
+
+    /// Style the braille with `style`, such as `Style::new().fg(Color::Green)`.
+    pub fn style(mut self, style: Style) -> CanvasWidget<'a> {
+        self.style = style;
+        self
+    }
+
+    /// Center the canvas in the widget's area, rather than putting it in the
+    /// top-left corner.
+    pub fn centered(mut self) -> CanvasWidget<'a> {
+        self.centered = true;
+        self
+    }
+
+    /// Shrink the canvas by a whole factor when it's too big for the area,
+    /// rather than cutting it off. Each block of pixels becomes one pixel,
+    /// which is on if any of the block was, so thin lines don't vanish.
+    pub fn scaled_to_fit(mut self) -> CanvasWidget<'a> {
+        self.scaled_to_fit = true;
+        self
+    }
+}
+
+impl<'a> Widget for CanvasWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        let (area_w, area_h) = (area.width as i32, area.height as i32);
+        let mut canvas = Cow::Borrowed(self.canvas);
+        if self.scaled_to_fit {
+            let factor = |size: i32, room: i32| (size + room - 1) / room.max(1);
+            let factor = factor(canvas.width, 2 * area_w).max(factor(canvas.height, 4 * area_h));
+            if factor > 1 {
+                canvas = Cow::Owned(downsampled(&canvas, factor));
+            }
+        }
+
+        // The canvas's size in cells, rounding up for partial ones.
+        let (cells_w, cells_h) = ((canvas.width + 1) / 2, (canvas.height + 3) / 4);
+        let (left, top) = if self.centered {
+            ((area_w - cells_w).max(0) / 2, (area_h - cells_h).max(0) / 2)
+        } else {
+            (0, 0)
+        };
+        for row in 0..cells_h.min(area_h - top) {
+            for col in 0..cells_w.min(area_w - left) {
+                let x = area.x + (left + col) as u16;
+                let y = area.y + (top + row) as u16;
+                buf.get_mut(x, y)
+                   .set_char(canvas.braille_cell(2 * col, 4 * row))
+                   .set_style(self.style);
+            }
+        }
+    }
+}
+
+/// Shrink `canvas` by `factor` in each direction, with each block of pixels
+/// on if any of it is.
+fn downsampled(canvas: &Canvas, factor: i32) -> Canvas {
+    let shrink = |size: i32| ((size + factor - 1) / factor) as u32;
+    let block = factor as u32;
+    Canvas::from_fn(shrink(canvas.width), shrink(canvas.height), |x, y| {
+        (canvas.sample_region(x * factor, y * factor, block, block) > 0.0) as u8
+    })
+    .expect("a smaller canvas always fits")
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+    use crate::SchotterConfig;
+    use rand::prelude::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::style::Color;
+    use ratatui::Terminal;
+
+    fn schotter() -> Canvas {
+        let config = SchotterConfig::new(40, 8, 12);
+        Canvas::create_and_render_schotter_with(&config, &mut StdRng::seed_from_u64(7)).unwrap()
+    }
+
+    /// The text of each row of `buf`.
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height).map(|y| {
+            (0..buf.area.width).map(|x| buf.get(x, y).symbol()).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn check_exact_fit() {
+        let canvas = schotter();
+        let mut terminal = Terminal::new(TestBackend::new(40, 30)).unwrap();
+        let style = Style::new().fg(Color::Green);
+        terminal.draw(|frame| {
+            frame.render_widget(CanvasWidget::new(&canvas).style(style), frame.size());
+        }).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let expected: Vec<String> = canvas.render().lines().map(String::from).collect();
+        assert_eq!(lines(buf), expected);
+        assert_eq!(buf.get(3, 4).fg, Color::Green);
+    }
+
+    #[test]
+    fn check_clipped_and_centered() {
+        let canvas = schotter();
+        let rendered: Vec<Vec<char>> = canvas.render().lines()
+                                             .map(|line| line.chars().collect())
+                                             .collect();
+
+        // Too big for the area: the top-left of the canvas, and nothing
+        // outside the area.
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 10));
+        CanvasWidget::new(&canvas).render(Rect::new(2, 1, 10, 5), &mut buf);
+        let lines = lines(&buf);
+        assert_eq!(lines[0], " ".repeat(20));
+        let row: String = rendered[0][..10].iter().collect();
+        assert_eq!(lines[1], format!("  {}        ", row));
+        assert_eq!(lines[6], " ".repeat(20));
+
+        // Smaller than the area: the canvas in the middle.
+        let small = Canvas::create(4, 4).unwrap();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+        CanvasWidget::new(&small).centered().render(buf.area, &mut buf);
+        assert_eq!(buf.get(2, 1).symbol(), "⠀");
+        assert_eq!(buf.get(1, 1).symbol(), " ");
+        assert_eq!(buf.get(2, 0).symbol(), " ");
+    }
+
+    #[test]
+    fn check_scaled_to_fit() {
+        // 40x60 pixels into 10x5 cells, 20x20 pixels: shrunk by 3.
+        let mut canvas = Canvas::create(40, 60).unwrap();
+        canvas.draw_line(0, 0, 39, 0, 1);
+        canvas.draw_pixel(39, 59, 1);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 5));
+        CanvasWidget::new(&canvas).scaled_to_fit().render(buf.area, &mut buf);
+
+        let mut expected = Canvas::create(14, 20).unwrap();
+        expected.draw_line(0, 0, 13, 0, 1);
+        expected.draw_pixel(13, 19, 1);
+        let expected: Vec<String> = expected.render().lines()
+                                            .map(|line| format!("{:<10}", line))
+                                            .collect();
+        assert_eq!(lines(&buf), expected);
+    }
+}